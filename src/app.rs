@@ -1,7 +1,9 @@
 use crate::{
-    bea_data, BTreeKeyMissing, BeaErr, BeaResponse, Dataset, DeriveFromStr, IoError,
-    JsonParseError, JsonParseErrorKind, KeyMissing, Method, MillionsOptions, Options,
-    ParameterKind, ParameterName, RateLimit, ReqwestError, Results, SerdeJson, VariantMissing,
+    outline, ApiErrorKind, ApiFailure, BTreeKeyMissing, Backend, BeaErr, BeaResponse, Data,
+    DataStream, Dataset, DatasetMissing, Datum, DeriveFromStr, FixedAssetData, FixedAssetDatum,
+    FsStorage, IoError, JsonParseError, JsonParseErrorKind, KeyMissing, Method, MillionsOptions,
+    MneDiData, MneDiDatum, Naics, NipaData, NipaDatum, Options, ParameterKind, ParameterName,
+    RateLimit, ReqwestError, Results, SerdeJson, Storage, StorageKey, VariantMissing,
 };
 use std::collections::BTreeMap;
 use std::str::FromStr;
@@ -53,11 +55,15 @@ pub struct App {
     url: url::Url,
     query: BTreeMap<String, String>,
     size_hint: Option<u64>,
+    /// Storage root holding downloads, in place of the `BEA_DATA` directory.
+    #[serde(default)]
+    #[getter(skip)]
+    root: std::path::PathBuf,
 }
 
 impl App {
-    ///   Create a new `App` from the user API key `key`, a set of user [`Options`] `options`, and the BEA url `url`,
-    ///   using the [`App::new`] method.
+    ///   Create a new `App` from the user API key `key`, a set of user [`Options`] `options`, the BEA url `url`,
+    ///   and the storage root `root` holding downloads, using the [`App::new`] method.
     ///
     ///   ```
     ///   use bears::{App, Options};
@@ -72,11 +78,13 @@ impl App {
     ///   let key = std::env::var("API_KEY").unwrap();
     ///   // and empty set of options
     ///   let options = Options::default();
+    ///   // folder holding downloads
+    ///   let root = std::env::var("BEA_DATA").unwrap();
     ///   // a "default" app
-    ///   let app = App::new(key, options, url);
+    ///   let app = App::new(key, options, url, root.into());
     ///
     ///   ```
-    pub fn new(key: String, options: Options, url: url::Url) -> Self {
+    pub fn new(key: String, options: Options, url: url::Url, root: std::path::PathBuf) -> Self {
         let mut query = options.params();
         query.insert("USERID".to_string(), key.clone());
         Self {
@@ -85,9 +93,22 @@ impl App {
             url,
             query,
             size_hint: None,
+            root,
         }
     }
 
+    /// The storage root of the request, set by [`App::new`], [`App::with_root`] or
+    /// [`BeaClient::request`](crate::BeaClient::request).
+    pub fn storage_root(&self) -> &std::path::PathBuf {
+        &self.root
+    }
+
+    /// The files under the storage root of the request, holding the dataset list, parameters
+    /// and parameter value caches.
+    pub fn root_storage(&self) -> FsStorage {
+        FsStorage::new(self.root.clone())
+    }
+
     /// The `with_options` method sets the value of the `options` field in self, using the provided
     /// [`Options`] in the `options` parameter.  The implementation updates the value of the `query`
     /// field in self, which includes any values added to `options`, so we must update `query` when `options` changes.
@@ -197,15 +218,28 @@ impl App {
     /// let data = app.get().await.unwrap();
     /// # }
     /// ```
+    ///
+    /// Calls share one connection pool across the process.  Prefer
+    /// [`BeaClient::get`](crate::BeaClient::get), which also applies the timeouts and rate limits
+    /// of the client.
     #[tracing::instrument(skip_all)]
     pub async fn get(&self) -> Result<reqwest::Response, ReqwestError> {
+        self.get_with(crate::client::shared_http(&self.url)).await
+    }
+
+    /// Sends the request using the HTTP `client`, so that concurrent requests can share a
+    /// connection pool.  Used by [`BeaClient::get`](crate::BeaClient::get).
+    #[tracing::instrument(skip_all)]
+    pub async fn get_with(
+        &self,
+        client: &reqwest::Client,
+    ) -> Result<reqwest::Response, ReqwestError> {
         tracing::trace!("Calling get for App.");
         let body = self
             .query
             .clone()
             .into_iter()
             .collect::<Vec<(String, String)>>();
-        let client = reqwest::Client::new();
         let req = client.get(self.url.clone()).query(&self.query);
         tracing::trace!("Sending request: {:?}", req);
         match req.send().await {
@@ -265,8 +299,7 @@ impl App {
         tracing::trace!("Params are {:#?}", query);
         let method = self.method()?;
         let dataset = self.dataset()?;
-        dotenvy::dotenv().ok();
-        let path = bea_data()?;
+        let path = self.root.clone();
         match method {
             Method::GetData => {
                 let path = path.join("data");
@@ -375,8 +408,10 @@ impl App {
     #[tracing::instrument(skip_all)]
 
     /// The `download` method sends a get request based upon the `App` configuration.
-    /// If the request is successful, it writes the JSON response to the `BEA_DATA` directory.
-    /// Returns a [`ResultStatus`] indicating the outcome the request.
+    /// If the request is successful, it streams the response to a temporary file in the
+    /// destination folder under the storage root, and renames the file into place once the
+    /// contents parse as JSON.  Returns a [`ResultStatus`] indicating the outcome the request,
+    /// recording the number of bytes written on success.
     ///
     /// The `id` parameter is the [`Event`](crate::Event) id assigned by the [`Tracker`](crate::Tracker) to the request.  Since
     /// downloads are asynchronous tasks and must be Send, we pass the event id to the
@@ -386,62 +421,175 @@ impl App {
     /// For one-off requests, tracking is unnecessary and it is better to use the [`App::get`]
     /// method directly.
     pub async fn download(&self, id: uuid::Uuid) -> Result<ResultStatus, BeaErr> {
+        let client = crate::client::shared_http(&self.url);
+        let storage = Backend::from(self.root_storage());
+        self.download_with(client, &storage, id).await
+    }
+
+    /// Variant of [`App::download`] sending the request using the HTTP `client` and saving the
+    /// response to `storage`.  Called by [`Queue::downloader`](crate::Queue::downloader), so that
+    /// downloads share a connection pool and storage backend.
+    ///
+    /// Fails on a non-success HTTP status, and on API errors that may clear on a later attempt,
+    /// such as a server error or an exceeded rate limit, so the caller can retry or abort.
+    /// Other API errors, and responses that are neither data nor a recognized error, are reported
+    /// as [`ResultStatus::Error`] without writing the response.
+    pub async fn download_with(
+        &self,
+        client: &reqwest::Client,
+        storage: &Backend,
+        id: uuid::Uuid,
+    ) -> Result<ResultStatus, BeaErr> {
         tracing::trace!("Calling download.");
         let query = self.query();
         tracing::trace!("Params are {:#?}", query);
         let method = self.method()?;
         match method {
             Method::GetData => {
-                let data = self.get().await?;
-                let length = data.content_length().unwrap();
-                match data.json::<serde_json::Value>().await {
-                    Ok(json) => match BeaResponse::try_from(&json) {
-                        Ok(response) => match response.results() {
-                            Results::ApiError(error) => {
-                                tracing::error!("{error}");
-                                return Ok(ResultStatus::Error(id));
-                            }
-                            Results::MneError(error) => {
-                                tracing::trace!("{error}");
-                                return Ok(ResultStatus::Error(id));
-                            }
-                            Results::RequestsExceeded(error) => {
-                                let error =
-                                    RateLimit::new(error.to_string(), line!(), file!().to_string());
-                                tracing::error!("{error}");
-                                // return Err(error.into());
-                                return Ok(ResultStatus::Abort);
-                            }
-                            _ => {
-                                self.save(json)?;
-                                return Ok(ResultStatus::Success(id, length));
-                            }
-                        },
-                        Err(_) => {
-                            tracing::warn!("Unrecognized response.");
-                            self.save(json)?;
-                            return Ok(ResultStatus::Success(id, length));
+                let mut data =
+                    self.get_with(client)
+                        .await?
+                        .error_for_status()
+                        .map_err(|source| {
+                            let url = self.url().to_string();
+                            let mut error = ReqwestError::new(
+                                url,
+                                "get".into(),
+                                source,
+                                line!(),
+                                file!().to_string(),
+                            );
+                            error.with_body(self.query.clone().into_iter().collect());
+                            error
+                        })?;
+                // Stream the body to a staged object, so an interrupted download never leaves a
+                // partial file at the destination.
+                let mut staged = storage.stage(&self.storage_key()?)?;
+                while let Some(chunk) = data.chunk().await.map_err(|source| {
+                    let url = self.url().to_string();
+                    ReqwestError::new(url, "chunk".into(), source, line!(), file!().to_string())
+                })? {
+                    staged.write(&chunk)?;
+                }
+                let json = match staged.outline() {
+                    Ok(json) => json,
+                    Err(source) => {
+                        tracing::warn!("{source}");
+                        return Ok(ResultStatus::Error(id, None));
+                    }
+                };
+                // The outline drops the records, so data responses are kept without parsing them
+                // again, including those of datasets the library cannot yet read.
+                let results = json.pointer("/BEAAPI/Results");
+                if results
+                    .is_some_and(|results| results.is_array() || results.get("Data").is_some())
+                {
+                    let length = staged.commit().await?;
+                    return Ok(ResultStatus::Success(id, length));
+                }
+                let failure = |kind: ApiErrorKind, code, description: &String| {
+                    let error = ApiFailure::new(
+                        kind,
+                        code,
+                        description.clone(),
+                        line!(),
+                        file!().to_string(),
+                    );
+                    Err(error.into())
+                };
+                match BeaResponse::try_from(&json) {
+                    Ok(response) => match response.results() {
+                        Results::ApiError(error) if error.kind().is_server_error() => {
+                            failure(error.kind(), *error.code(), error.description())
+                        }
+                        Results::MneError(error) if error.kind().is_server_error() => {
+                            failure(error.kind(), *error.number(), error.error())
+                        }
+                        Results::ApiError(error) => {
+                            tracing::error!("{error}");
+                            Ok(ResultStatus::Error(id, Some(error.kind())))
+                        }
+                        Results::MneError(error) => {
+                            tracing::trace!("{error}");
+                            Ok(ResultStatus::Error(id, Some(error.kind())))
+                        }
+                        Results::RequestsExceeded(error) => {
+                            let error =
+                                RateLimit::new(error.to_string(), line!(), file!().to_string());
+                            Err(error.into())
+                        }
+                        _ => {
+                            let length = staged.commit().await?;
+                            Ok(ResultStatus::Success(id, length))
                         }
                     },
                     Err(source) => {
-                        let url = self.url().to_string();
-                        let method = "get".to_string();
-                        let error =
-                            ReqwestError::new(url, method, source, line!(), file!().to_string());
-                        tracing::warn!("{error}");
-                        // return Err(error.into());
-                        return Ok(ResultStatus::Error(id));
+                        // Dropping the staged object leaves any earlier download in place.
+                        tracing::warn!("Unrecognized response: {source}");
+                        Ok(ResultStatus::Error(id, None))
                     }
                 }
             }
             _ => {
                 tracing::info!("{method} not implemented.");
-                Ok(ResultStatus::Error(id))
+                Ok(ResultStatus::Error(id, None))
+            }
+        }
+    }
+
+    /// The key of the response to the request in a [`Storage`] backend, the path of
+    /// [`App::destination`] relative to the storage root.  Fails unless the request is for data
+    /// from a dataset with a defined layout.
+    pub fn storage_key(&self) -> Result<StorageKey, BeaErr> {
+        let path = self.destination(false)?;
+        match path.strip_prefix(&self.root) {
+            Ok(relative) if relative.extension().is_some() => Ok(StorageKey::from_path(relative)),
+            _ => {
+                let error = VariantMissing::new(
+                    "Storage key requires a data request with a defined layout".to_string(),
+                    self.dataset()?.to_string(),
+                    line!(),
+                    file!().to_string(),
+                );
+                Err(error.into())
             }
         }
     }
 
-    /// The `save` method writes a [`serde_json::Value`] to the `BEA_DATA` directory.
+    /// Writes the response `json` to `storage` under the key [`App::storage_key`].
+    pub async fn save_to<S: Storage>(
+        &self,
+        storage: &S,
+        json: &serde_json::Value,
+    ) -> Result<(), BeaErr> {
+        let key = self.storage_key()?;
+        let contents = serde_json::to_vec(json)
+            .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+        storage.put(&key, contents).await
+    }
+
+    /// Reads the response to the request from `storage`.  Fails if absent.
+    pub async fn load_from<S: Storage>(&self, storage: &S) -> Result<BeaResponse, BeaErr> {
+        let (response, _) = self.load_sized(storage).await?;
+        Ok(response)
+    }
+
+    /// Reads the response to the request from `storage`, with the size of the stored object in
+    /// bytes.  Fails if absent.
+    pub async fn load_sized<S: Storage>(&self, storage: &S) -> Result<(BeaResponse, u64), BeaErr> {
+        let key = self.storage_key()?;
+        let Some(contents) = storage.get(&key).await? else {
+            let error = std::io::Error::from(std::io::ErrorKind::NotFound);
+            let error = IoError::new(key.as_str().into(), error, line!(), file!().into());
+            return Err(error.into());
+        };
+        let size = contents.len() as u64;
+        let json = outline(contents.as_slice())?;
+        let response = read_outlined(&json, || Ok(std::io::Cursor::new(contents)))?;
+        Ok((response, size))
+    }
+
+    /// The `save` method writes a [`serde_json::Value`] to the storage root of the request.
     pub fn save(&self, json: serde_json::Value) -> Result<(), BeaErr> {
         tracing::trace!("Calling save.");
         let method = self.method()?;
@@ -451,8 +599,7 @@ impl App {
                     .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
 
                 let path = self.destination(true)?;
-                std::fs::write(&path, contents)
-                    .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
+                FsStorage::write_atomic(&path, &contents)?;
             }
             _ => {
                 tracing::info!("Not implemented for {method}.");
@@ -461,8 +608,9 @@ impl App {
         Ok(())
     }
 
-    /// The `load` method reads a [`BeaResponse`] from the `BEA_DATA` directory.  Uses the `App`
-    /// configuration to determine the file destination.
+    /// The `load` method reads a [`BeaResponse`] from the storage root of the `App`, using the `App`
+    /// configuration to determine the file destination.  The file is read in two streaming passes,
+    /// see [`read_outlined`], so no more than the records themselves are held in memory.
     pub fn load(&self) -> Result<BeaResponse, BeaErr> {
         tracing::trace!("Calling load.");
         let query = self.query();
@@ -472,14 +620,13 @@ impl App {
             Method::GetData => {
                 let path = self.destination(false)?;
                 tracing::info!("Opening {path:?}.");
-                // Create reader from path.
-                let file = std::fs::File::open(&path)
-                    .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
-                let rdr = std::io::BufReader::new(file);
-                // Deserialize to serde_json::Value.
-                let json: serde_json::Value = serde_json::from_reader(rdr)
-                    .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
-                BeaResponse::try_from(&json)
+                let open = || {
+                    std::fs::File::open(&path)
+                        .map(std::io::BufReader::new)
+                        .map_err(|e| IoError::new(path.clone(), e, line!(), file!().into()).into())
+                };
+                let json = outline(open()?)?;
+                read_outlined(&json, open)
             }
             _ => {
                 let msg = format!("load not implemented for {method}");
@@ -490,6 +637,80 @@ impl App {
             }
         }
     }
+
+    /// The `stream` method reads the file at [`App::destination`] under the storage root one record
+    /// at a time, as an alternative to [`App::load`] for large responses.  Memory use stays bounded by
+    /// the [`DataStream`] buffer rather than growing with the file size.
+    pub fn stream(&self) -> Result<DataStream<Datum>, BeaErr> {
+        tracing::trace!("Calling stream.");
+        let method = self.method()?;
+        match method {
+            Method::GetData => {
+                let path = self.destination(false)?;
+                tracing::info!("Streaming {path:?}.");
+                let dataset = self.dataset()?;
+                match dataset {
+                    Dataset::Nipa | Dataset::NIUnderlyingDetail => {
+                        DataStream::from_path_for(path, &[dataset], |m| {
+                            NipaDatum::read_json(m).map(Datum::from)
+                        })
+                    }
+                    Dataset::FixedAssets => DataStream::from_path_for(path, &[dataset], |m| {
+                        FixedAssetDatum::read_json(m).map(Datum::from)
+                    }),
+                    Dataset::Mne => {
+                        let naics = Naics::from_csv("data/naics_codes.csv")?;
+                        DataStream::from_path_for(path, &[dataset], move |m| {
+                            MneDiDatum::read_json(m, &naics).map(Datum::from)
+                        })
+                    }
+                    _ => {
+                        let error =
+                            DatasetMissing::new(dataset.to_string(), line!(), file!().to_string());
+                        Err(error.into())
+                    }
+                }
+            }
+            _ => {
+                let msg = format!("stream not implemented for {method}");
+                tracing::info!(msg);
+                let error =
+                    VariantMissing::new(msg, method.to_string(), line!(), file!().to_string());
+                Err(error.into())
+            }
+        }
+    }
+}
+
+/// Reads a response from its outline `json`, as returned by [`outline`].  If the response holds
+/// data, the records are collected from a [`DataStream`] over the reader returned by `open`, so
+/// the raw JSON of the records is never held in memory at once.  Other responses, such as errors
+/// and parameter values, are read from the outline alone and never call `open`.
+fn read_outlined<R, F>(json: &serde_json::Value, open: F) -> Result<BeaResponse, BeaErr>
+where
+    R: std::io::Read + Send + 'static,
+    F: FnOnce() -> Result<R, BeaErr>,
+{
+    let response = BeaResponse::try_from(json)?;
+    let Some(data) = response.data() else {
+        return Ok(response);
+    };
+    let rdr = open()?;
+    let data = match data {
+        Data::Nipa(_) => DataStream::from_reader(rdr, NipaDatum::read_json)
+            .collect::<Result<Vec<NipaDatum>, BeaErr>>()
+            .map(|data| Data::from(NipaData::from(data)))?,
+        Data::FixedAssets(_) => DataStream::from_reader(rdr, FixedAssetDatum::read_json)
+            .collect::<Result<Vec<FixedAssetDatum>, BeaErr>>()
+            .map(|data| Data::from(FixedAssetData::from(data)))?,
+        Data::MneDi(_) => {
+            let naics = Naics::from_csv("data/naics_codes.csv")?;
+            DataStream::from_reader(rdr, move |m| MneDiDatum::read_json(m, &naics))
+                .collect::<Result<Vec<MneDiDatum>, BeaErr>>()
+                .map(|data| Data::from(MneDiData::from(data)))?
+        }
+    };
+    Ok(response.with_data(data))
 }
 
 /// The `ResultStatus` enum represents different possible outcomes for async load and download
@@ -518,16 +739,23 @@ pub enum ResultStatus {
     #[display("Success")]
     Success(uuid::Uuid, u64),
     /// The event resulted in an error.
-    /// Used for MneError and ReqwestError outcomes.
-    /// Contains and event id.
+    /// Used for ApiError, MneError and ReqwestError outcomes.
+    /// Contains an event id and the kind of error reported by the BEA, if any.
     /// The event id is used to update the status of a pending event.
-    #[display("Error")]
-    Error(uuid::Uuid),
+    /// The kind is `None` when the request failed without a response from the BEA API, such as on
+    /// a network error.
+    #[display("{}", match _1 { Some(kind) => format!("Error({kind})"), None => "Error".to_string() })]
+    Error(uuid::Uuid, Option<ApiErrorKind>),
     /// The task was skipped.
     /// Used when the download path exists and overwrite is false.
     /// Contains and event id.
     #[display("Pass")]
     Pass(uuid::Uuid),
+    /// The task was cancelled before it completed.
+    /// Used when a download or load is stopped through its cancellation token.
+    /// Contains an event id.
+    #[display("Cancelled")]
+    Cancelled(uuid::Uuid),
     /// The event has not yet received a status update from the listener.
     /// New events begin in this state.
     Pending,
@@ -544,13 +772,24 @@ impl FromStr for ResultStatus {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let status = match s {
             "Success" => Self::Success(uuid::Uuid::new_v4(), 0),
-            "Error" => Self::Error(uuid::Uuid::new_v4()),
+            "Error" => Self::Error(uuid::Uuid::new_v4(), None),
             "Pass" => Self::Pass(uuid::Uuid::new_v4()),
+            "Cancelled" => Self::Cancelled(uuid::Uuid::new_v4()),
             "Pending" => Self::Pending,
             "Abort" => Self::Abort,
             _ => {
-                let error = KeyMissing::new(s.to_string(), line!(), file!().to_string());
-                return Err(error.into());
+                // errors with a known kind display as "Error(Kind)"
+                let kind = s
+                    .strip_prefix("Error(")
+                    .and_then(|s| s.strip_suffix(')'))
+                    .and_then(|kind| ApiErrorKind::from_str(kind).ok());
+                match kind {
+                    Some(kind) => Self::Error(uuid::Uuid::new_v4(), Some(kind)),
+                    None => {
+                        let error = KeyMissing::new(s.to_string(), line!(), file!().to_string());
+                        return Err(error.into());
+                    }
+                }
             }
         };
         Ok(status)
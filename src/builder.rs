@@ -0,0 +1,431 @@
+use crate::validate::check_params;
+use crate::{
+    App, BeaClient, BeaErr, Catalog, Dataset, DatasetMissing, Footnotes, Frequency,
+    MillionsOptions, ParameterName, ParamsInvalid, Request, ValueSet, ValueSets,
+};
+use std::collections::BTreeMap;
+
+/// Finds the cached values for `dataset` in `values`.
+fn value_set(values: &ValueSets, dataset: Dataset) -> Result<&ValueSet, DatasetMissing> {
+    values
+        .get(dataset)
+        .ok_or_else(|| DatasetMissing::new(dataset.to_string(), line!(), file!().to_string()))
+}
+
+/// Checks `params` against the cached values for `dataset` in `values`, and that each of
+/// `required` is present, failing with every problem found.
+fn checked(
+    values: &ValueSets,
+    dataset: Dataset,
+    params: BTreeMap<String, String>,
+    required: &[ParameterName],
+) -> Result<BTreeMap<String, String>, BeaErr> {
+    let set = value_set(values, dataset)?;
+    let missing = required
+        .iter()
+        .map(|name| name.to_string())
+        .filter(|name| !params.contains_key(name))
+        .collect::<Vec<String>>();
+    let errors = check_params(set, &params);
+    if missing.is_empty() && errors.is_empty() {
+        Ok(params)
+    } else {
+        let error = ParamsInvalid::new(
+            dataset.to_string(),
+            missing,
+            errors,
+            line!(),
+            file!().to_string(),
+        );
+        Err(error.into())
+    }
+}
+
+/// Creates an [`App`] requesting data from `dataset` with parameters `params` through `client`,
+/// completed and checked against the rules in `catalog`.
+fn build(
+    client: &BeaClient,
+    dataset: Dataset,
+    mut params: BTreeMap<String, String>,
+    catalog: &Catalog,
+) -> Result<App, BeaErr> {
+    catalog.enforce_params(dataset, &mut params)?;
+    let mut app = client.request(Request::Data);
+    app.with_dataset(dataset);
+    app.with_params(params);
+    Ok(app)
+}
+
+/// The `NipaRequest` struct builds a request for a table of the NIPA dataset, checking each
+/// value against the cached parameter values before producing an [`App`].  Every invalid value is
+/// reported together in a [`ParamsInvalid`] error.
+///
+/// Years default to all available years and frequency defaults to annual.
+///
+/// ```
+/// use bears::{Frequency, NipaRequest};
+///
+/// let _request = NipaRequest::table("T10101")
+///     .frequency(Frequency::Quarterly)
+///     .years([2022, 2023]);
+/// ```
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub struct NipaRequest {
+    table_name: String,
+    frequency: Vec<Frequency>,
+    years: Vec<String>,
+    show_millions: MillionsOptions,
+}
+
+impl NipaRequest {
+    /// Starts a request for the table named `table_name`, such as "T10101".
+    pub fn table<S: ToString>(table_name: S) -> Self {
+        Self {
+            table_name: table_name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Adds `frequency` to the frequencies requested.
+    pub fn frequency(mut self, frequency: Frequency) -> Self {
+        if !self.frequency.contains(&frequency) {
+            self.frequency.push(frequency);
+        }
+        self
+    }
+
+    /// Adds `years` to the years requested.
+    pub fn years<I: IntoIterator<Item = T>, T: ToString>(mut self, years: I) -> Self {
+        self.years.extend(years.into_iter().map(|y| y.to_string()));
+        self
+    }
+
+    /// Sets whether to report values in millions rather than billions.
+    pub fn show_millions(mut self, show_millions: MillionsOptions) -> Self {
+        self.show_millions = show_millions;
+        self
+    }
+
+    /// Checks the request against the NIPA values in `values`, returning the request parameters.
+    ///
+    /// Years are checked against the years published for the table at each frequency requested,
+    /// and each frequency must be published for the table.
+    pub fn params(&self, values: &ValueSets) -> Result<BTreeMap<String, String>, BeaErr> {
+        let frequency = match self.frequency.is_empty() {
+            true => Frequency::Annual.value(),
+            false => self
+                .frequency
+                .iter()
+                .map(|f| f.value())
+                .collect::<Vec<String>>()
+                .join(","),
+        };
+        let mut params = BTreeMap::new();
+        params.insert(
+            ParameterName::TableName.to_string(),
+            self.table_name.clone(),
+        );
+        params.insert(ParameterName::Frequency.to_string(), frequency);
+        params.insert(ParameterName::Year.to_string(), years_param(&self.years));
+        let (key, value) = self.show_millions.params();
+        params.insert(key, value);
+        checked(values, Dataset::Nipa, params, &[])
+    }
+
+    /// Checks the request against the NIPA values in `values` and the parameter rules in `catalog`,
+    /// and creates an [`App`] to send it through `client`.
+    pub fn build(
+        &self,
+        client: &BeaClient,
+        values: &ValueSets,
+        catalog: &Catalog,
+    ) -> Result<App, BeaErr> {
+        let params = self.params(values)?;
+        build(client, Dataset::Nipa, params, catalog)
+    }
+}
+
+/// Joins `years` for a request, defaulting to all years when none are given.
+fn years_param(years: &[String]) -> String {
+    match years.is_empty() {
+        true => "ALL".to_string(),
+        false => years.join(","),
+    }
+}
+
+/// The `RegionalRequest` struct builds a request for a table of the Regional dataset, checking
+/// each value against the cached parameter values before producing an [`App`].
+///
+/// GeoFips accepts the keywords in [`GEO_FIPS_KEYWORDS`](crate::validate::GEO_FIPS_KEYWORDS) as well as individual codes.  Years
+/// default to the last five years.
+///
+/// ```
+/// use bears::RegionalRequest;
+///
+/// let _request = RegionalRequest::table("CAINC1")
+///     .line_code(1)
+///     .geo_fips("COUNTY")
+///     .years(["2022"]);
+/// ```
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub struct RegionalRequest {
+    table_name: String,
+    line_code: Option<String>,
+    geo_fips: Vec<String>,
+    years: Vec<String>,
+}
+
+impl RegionalRequest {
+    /// Starts a request for the table named `table_name`, such as "CAINC1".
+    pub fn table<S: ToString>(table_name: S) -> Self {
+        Self {
+            table_name: table_name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the line of the table requested.
+    pub fn line_code<S: ToString>(mut self, line_code: S) -> Self {
+        self.line_code = Some(line_code.to_string());
+        self
+    }
+
+    /// Adds `geo_fips` to the areas requested.
+    pub fn geo_fips<S: ToString>(mut self, geo_fips: S) -> Self {
+        self.geo_fips.push(geo_fips.to_string());
+        self
+    }
+
+    /// Adds `years` to the years requested.
+    pub fn years<I: IntoIterator<Item = T>, T: ToString>(mut self, years: I) -> Self {
+        self.years.extend(years.into_iter().map(|y| y.to_string()));
+        self
+    }
+
+    /// Checks the request against the Regional values in `values`, returning the request
+    /// parameters.  The line code and at least one GeoFips value are required.
+    pub fn params(&self, values: &ValueSets) -> Result<BTreeMap<String, String>, BeaErr> {
+        let mut params = BTreeMap::new();
+        params.insert(
+            ParameterName::TableName.to_string(),
+            self.table_name.clone(),
+        );
+        if let Some(line_code) = &self.line_code {
+            params.insert(ParameterName::LineCode.to_string(), line_code.clone());
+        }
+        if !self.geo_fips.is_empty() {
+            params.insert(ParameterName::GeoFips.to_string(), self.geo_fips.join(","));
+        }
+        let years = match self.years.is_empty() {
+            true => "LAST5".to_string(),
+            false => self.years.join(","),
+        };
+        params.insert(ParameterName::Year.to_string(), years);
+        let required = [ParameterName::LineCode, ParameterName::GeoFips];
+        checked(values, Dataset::Regional, params, &required)
+    }
+
+    /// Checks the request against the Regional values in `values` and the parameter rules in `catalog`,
+    /// and creates an [`App`] to send it through `client`.
+    pub fn build(
+        &self,
+        client: &BeaClient,
+        values: &ValueSets,
+        catalog: &Catalog,
+    ) -> Result<App, BeaErr> {
+        let params = self.params(values)?;
+        build(client, Dataset::Regional, params, catalog)
+    }
+}
+
+/// The `MneRequest` struct builds a request for the MNE dataset, checking each value against the
+/// cached parameter values before producing an [`App`].
+///
+/// Countries, industries, series and years default to "all".  Ownership level and nonbank
+/// affiliates apply only to statistics on the activities of multinational enterprises (AMNE),
+/// and are omitted when unset.
+///
+/// ```
+/// use bears::MneRequest;
+///
+/// let _request = MneRequest::direction("outward")
+///     .classification("country")
+///     .countries([650])
+///     .years([2021, 2022]);
+/// ```
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub struct MneRequest {
+    direction_of_investment: String,
+    classification: String,
+    series_id: Vec<String>,
+    countries: Vec<String>,
+    industries: Vec<String>,
+    states: Vec<String>,
+    years: Vec<String>,
+    ownership_level: Option<String>,
+    nonbank_affiliates_only: Option<String>,
+    footnotes: Footnotes,
+}
+
+impl MneRequest {
+    /// Starts a request for the direction of investment `direction`, such as "outward".
+    pub fn direction<S: ToString>(direction: S) -> Self {
+        Self {
+            direction_of_investment: direction.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the classification of the statistics, such as "country" or "industry".
+    pub fn classification<S: ToString>(mut self, classification: S) -> Self {
+        self.classification = classification.to_string();
+        self
+    }
+
+    /// Adds `series_id` to the series requested.
+    pub fn series_id<I: IntoIterator<Item = T>, T: ToString>(mut self, series_id: I) -> Self {
+        self.series_id
+            .extend(series_id.into_iter().map(|s| s.to_string()));
+        self
+    }
+
+    /// Adds `countries` to the countries requested.
+    pub fn countries<I: IntoIterator<Item = T>, T: ToString>(mut self, countries: I) -> Self {
+        self.countries
+            .extend(countries.into_iter().map(|c| c.to_string()));
+        self
+    }
+
+    /// Adds `industries` to the industries requested.
+    pub fn industries<I: IntoIterator<Item = T>, T: ToString>(mut self, industries: I) -> Self {
+        self.industries
+            .extend(industries.into_iter().map(|i| i.to_string()));
+        self
+    }
+
+    /// Adds `states` to the states requested.
+    pub fn states<I: IntoIterator<Item = T>, T: ToString>(mut self, states: I) -> Self {
+        self.states
+            .extend(states.into_iter().map(|s| s.to_string()));
+        self
+    }
+
+    /// Adds `years` to the years requested.
+    pub fn years<I: IntoIterator<Item = T>, T: ToString>(mut self, years: I) -> Self {
+        self.years.extend(years.into_iter().map(|y| y.to_string()));
+        self
+    }
+
+    /// Sets the ownership level of AMNE statistics.
+    pub fn ownership_level<S: ToString>(mut self, ownership_level: S) -> Self {
+        self.ownership_level = Some(ownership_level.to_string());
+        self
+    }
+
+    /// Sets whether AMNE statistics cover nonbank affiliates only.
+    pub fn nonbank_affiliates_only<S: ToString>(mut self, nonbank: S) -> Self {
+        self.nonbank_affiliates_only = Some(nonbank.to_string());
+        self
+    }
+
+    /// Sets whether to include footnotes in the response.
+    pub fn footnotes(mut self, footnotes: Footnotes) -> Self {
+        self.footnotes = footnotes;
+        self
+    }
+
+    /// Checks the request against the MNE values in `values`, returning the request parameters.
+    /// The direction of investment and classification are required.
+    pub fn params(&self, values: &ValueSets) -> Result<BTreeMap<String, String>, BeaErr> {
+        let mut params = BTreeMap::new();
+        let (key, value) = self.footnotes.params();
+        params.insert(key, value);
+        if !self.direction_of_investment.is_empty() {
+            params.insert(
+                ParameterName::DirectionOfInvestment.to_string(),
+                self.direction_of_investment.clone(),
+            );
+        }
+        if !self.classification.is_empty() {
+            params.insert(
+                ParameterName::Classification.to_string(),
+                self.classification.clone(),
+            );
+        }
+        if let Some(level) = &self.ownership_level {
+            params.insert(ParameterName::OwnershipLevel.to_string(), level.clone());
+        }
+        if let Some(nonbank) = &self.nonbank_affiliates_only {
+            params.insert(
+                ParameterName::NonbankAffiliatesOnly.to_string(),
+                nonbank.clone(),
+            );
+        }
+        let lists = [
+            (ParameterName::SeriesID, &self.series_id),
+            (ParameterName::Country, &self.countries),
+            (ParameterName::Industry, &self.industries),
+            (ParameterName::Year, &self.years),
+        ];
+        for (name, values) in lists {
+            let value = match values.is_empty() {
+                true => "all".to_string(),
+                false => values.join(","),
+            };
+            params.insert(name.to_string(), value);
+        }
+        if !self.states.is_empty() {
+            params.insert(ParameterName::State.to_string(), self.states.join(","));
+        }
+        let required = [
+            ParameterName::DirectionOfInvestment,
+            ParameterName::Classification,
+        ];
+        checked(values, Dataset::Mne, params, &required)
+    }
+
+    /// Checks the request against the MNE values in `values` and the parameter rules in `catalog`,
+    /// and creates an [`App`] to send it through `client`.
+    pub fn build(
+        &self,
+        client: &BeaClient,
+        values: &ValueSets,
+        catalog: &Catalog,
+    ) -> Result<App, BeaErr> {
+        let params = self.params(values)?;
+        build(client, Dataset::Mne, params, catalog)
+    }
+}
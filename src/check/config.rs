@@ -0,0 +1,154 @@
+use super::fixtures::bea_url;
+use crate::{
+    BeaClient, BeaErr, BeaErrorKind, Dataset, ExportFormat, IoError, Method, Overrides, Profiles,
+    RateLimits, Request, Settings, BEA_URL,
+};
+
+/// Builds requests from a client, whose clones share one rate limiter.
+#[tracing::instrument]
+pub async fn shared_client() -> Result<(), BeaErr> {
+    let url = bea_url()?;
+    let client = BeaClient::new("key".to_string(), url.clone(), "data".into())?;
+    let mut app = client.request(Request::Data);
+    app.with_dataset(Dataset::Nipa);
+    assert_eq!(app.method()?, Method::GetData);
+    assert_eq!(app.url(), &url);
+    assert_eq!(app.query().get("USERID"), Some(&"key".to_string()));
+    assert_eq!(app.dataset()?, Dataset::Nipa);
+    for request in [Request::Dataset, Request::ParameterValueFilter] {
+        assert_eq!(client.request(request).method()?, request.method());
+    }
+
+    // clones share the rate limiter
+    let other = client.clone();
+    assert!(std::sync::Arc::ptr_eq(client.tracker(), other.tracker()));
+    Ok(())
+}
+
+/// Resolves settings from profiles, the environment and flags, in order of precedence.
+#[tracing::instrument]
+pub fn config_profiles() -> Result<(), BeaErr> {
+    let dir = std::env::temp_dir().join(format!("bears_profiles_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| IoError::new(dir.clone(), e, line!(), file!().into()))?;
+    let key_file = dir.join("staging.key");
+    std::fs::write(&key_file, "staging-key\n")
+        .map_err(|e| IoError::new(key_file.clone(), e, line!(), file!().into()))?;
+    let contents = format!(
+        r#"
+default = "production"
+
+[profile.production]
+key = "production-key"
+data = "/srv/bea"
+
+[profile.staging]
+key_file = "{}"
+url = "http://localhost:8080/api/data"
+data = "/srv/staging"
+datasets = ["Nipa", "Regional"]
+
+[profile.staging.limits]
+calls = 50
+
+[profile.staging.export]
+format = "json"
+directory = "tables"
+
+[profile.broken]
+key_file = "{}"
+data = "/srv/broken"
+"#,
+        key_file.display(),
+        dir.join("missing.key").display()
+    );
+    let path = dir.join("bears.toml");
+    std::fs::write(&path, contents)
+        .map_err(|e| IoError::new(path.clone(), e, line!(), file!().into()))?;
+    let profiles = Profiles::from_path(&path)?;
+    let no_env = |_: &str| None;
+
+    // the default profile applies when none is named
+    let production = profiles
+        .profile(None)?
+        .map(|p| ("production".to_string(), p));
+    let settings = Settings::from_sources(&Overrides::default(), no_env, production)?;
+    assert_eq!(settings.api_key()?.as_deref(), Some("production-key"));
+    assert_eq!(settings.url().as_str(), BEA_URL);
+    assert_eq!(settings.datasets().len(), 13);
+    assert_eq!(settings.limits(), &RateLimits::default());
+    assert_eq!(settings.export().format(), &ExportFormat::Csv);
+    assert_eq!(
+        settings.export_directory(),
+        std::path::PathBuf::from("/srv/bea/export")
+    );
+
+    let staging = profiles.profile(Some("staging"))?;
+    let staging = staging.map(|p| ("staging".to_string(), p));
+    let settings = Settings::from_sources(&Overrides::default(), no_env, staging.clone())?;
+    assert_eq!(settings.api_key()?.as_deref(), Some("staging-key"));
+    assert_eq!(settings.url().port(), Some(8080));
+    assert_eq!(settings.datasets(), &vec![Dataset::Nipa, Dataset::Regional]);
+    assert_eq!(*settings.limits().calls(), 50);
+    assert_eq!(*settings.limits().errors(), *RateLimits::default().errors());
+    // the export section of the profile replaces the defaults
+    assert_eq!(settings.export().format(), &ExportFormat::Json);
+    assert_eq!(
+        settings.export_directory(),
+        std::path::PathBuf::from("/srv/staging/tables")
+    );
+
+    // environment beats profile, and flags beat environment
+    let env = |name: &str| match name {
+        "API_KEY" => Some("env-key".to_string()),
+        "BEA_DATA" => Some("/env/data".to_string()),
+        "BEA_EXPORT_FORMAT" => Some("csv".to_string()),
+        "BEA_EXPORT_DIR" => Some("env_tables".to_string()),
+        _ => None,
+    };
+    let settings = Settings::from_sources(&Overrides::default(), env, staging.clone())?;
+    assert_eq!(settings.export().format(), &ExportFormat::Csv);
+    assert_eq!(
+        settings.export_directory(),
+        std::path::PathBuf::from("/env/data/env_tables")
+    );
+    let mut overrides = Overrides::default();
+    overrides.with_data("/flag/data");
+    overrides.with_export_format(ExportFormat::Json);
+    overrides.with_export_dir("/flag/tables");
+    let settings = Settings::from_sources(&overrides, env, staging)?;
+    assert_eq!(settings.export().format(), &ExportFormat::Json);
+    assert_eq!(
+        settings.export_directory(),
+        std::path::PathBuf::from("/flag/tables")
+    );
+    assert_eq!(settings.api_key()?.as_deref(), Some("env-key"));
+    assert_eq!(settings.data(), &std::path::PathBuf::from("/flag/data"));
+    assert_eq!(settings.url().port(), Some(8080));
+    let client = settings.client()?;
+    assert_eq!(*client.tracker().blocking_lock().limits().calls(), 50);
+
+    // a key file is only read for the key, so a missing one leaves the data root usable
+    let broken = profiles.profile(Some("broken"))?;
+    let broken = broken.map(|p| ("broken".to_string(), p));
+    let settings = Settings::from_sources(&Overrides::default(), no_env, broken)?;
+    assert_eq!(settings.data(), &std::path::PathBuf::from("/srv/broken"));
+    assert!(settings.api_key().is_err());
+    assert!(settings.client().is_err());
+
+    // no data root anywhere
+    let error = Settings::from_sources(&Overrides::default(), no_env, None).unwrap_err();
+    assert!(matches!(&**error, BeaErrorKind::ConfigMissing(_)));
+    let env = |name: &str| match name {
+        "BEA_DATA" => Some("/env/data".to_string()),
+        "BEA_EXPORT_FORMAT" => Some("xml".to_string()),
+        _ => None,
+    };
+    let error = Settings::from_sources(&Overrides::default(), env, None).unwrap_err();
+    assert!(matches!(&**error, BeaErrorKind::DeriveFromStr(_)));
+    let error = profiles.profile(Some("missing")).unwrap_err();
+    assert_eq!(error.profile(), "missing");
+    std::fs::remove_dir_all(&dir)
+        .map_err(|e| IoError::new(dir.clone(), e, line!(), file!().into()))?;
+    Ok(())
+}
@@ -1,9 +1,10 @@
-use crate::{trace_init, BeaErr, Dataset, History, Mode, Naics};
+use crate::{trace_init, BeaClient, BeaErr, Dataset, History, Mode, Naics};
 
 /// Pings the BEA API.
 #[tracing::instrument]
 pub async fn data_to_json() -> Result<(), BeaErr> {
     trace_init()?;
+    let client = BeaClient::from_env()?;
     let datasets = vec![Dataset::Mne];
     for dataset in datasets {
         // let queue = dataset.queue()?;
@@ -34,7 +35,7 @@ pub async fn data_to_json() -> Result<(), BeaErr> {
         // tracing::info!("Counter is length {}", counter.len());
 
         // queue.active_subset(false)?;
-        let history = History::try_from((dataset, Mode::Download))?;
+        let history = History::from_dataset(client.root(), dataset, Mode::Download)?;
         queue.exclude(&history)?;
         // queue.errors(&history, false)?;
         tracing::info!("Queue is length {}", queue.len());
@@ -48,6 +49,7 @@ pub async fn data_to_json() -> Result<(), BeaErr> {
 #[tracing::instrument(skip_all)]
 pub async fn data_from_json() -> Result<(), BeaErr> {
     trace_init()?;
+    let client = BeaClient::from_env()?;
     let datasets = vec![Dataset::Mne];
     for dataset in datasets {
         // let queue = dataset.queue()?;
@@ -59,7 +61,7 @@ pub async fn data_from_json() -> Result<(), BeaErr> {
         tracing::info!("Queue length: {}", queue.len());
         // queue.dedup();
         // tracing::info!("Queue length: {}", queue.len());
-        let history = History::try_from((dataset, Mode::Load))?;
+        let history = History::from_dataset(client.root(), dataset, Mode::Load)?;
         // let path = "/home/erik/bea/history/history_MNE_Errors.log";
         // let path = std::path::PathBuf::from(path);
         // let history = History::try_from(&path)?;
@@ -95,7 +97,7 @@ pub fn naics() -> Result<(), BeaErr> {
 #[tracing::instrument]
 pub fn download_history() -> Result<(), BeaErr> {
     trace_init()?;
-    let history = History::from_env()?;
+    let history = History::from_root(BeaClient::from_env()?.root())?;
     tracing::info!("History: {history:#?}");
     Ok(())
 }
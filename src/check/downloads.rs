@@ -0,0 +1,419 @@
+use super::fixtures::{listen, serve_chunked, serve_status};
+use crate::{
+    Action, ApiErrorKind, Backend, BeaClient, BeaErr, CancellationToken, Check, Dataset,
+    ErrorClass, Event, FsStorage, History, IoError, MemoryStorage, Mode, Plan, Progress, Queue,
+    RateLimits, Reporter, Request, ResultStatus, SerdeJson, Storage, UrlParseError,
+};
+
+/// Reports the progress of a queue whose files exist, and counts outcomes from the listener.
+#[tracing::instrument]
+pub async fn queue_progress() -> Result<(), BeaErr> {
+    // every file exists, so each request is skipped without calling the server
+    let storage = MemoryStorage::default();
+    let url = url::Url::parse("http://localhost:0/api/data").map_err(|e| {
+        UrlParseError::new(
+            "http://localhost:0/api/data".into(),
+            e,
+            line!(),
+            file!().into(),
+        )
+    })?;
+    let client =
+        BeaClient::new("key".to_string(), url, std::env::temp_dir())?.with_storage(storage.clone());
+    let mut apps = Vec::new();
+    for table in ["FAAt101", "FAAt102", "FAAt103"] {
+        let mut app = client.request(Request::Data);
+        app.with_dataset(Dataset::FixedAssets);
+        let mut params = app.params();
+        params.insert("TableName".to_string(), table.to_string());
+        app.with_params(params);
+        storage.put(&app.storage_key()?, b"{}".to_vec()).await?;
+        apps.push(app);
+    }
+    let updates = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let record = updates.clone();
+    let reporter = Reporter::callback(move |progress: &Progress| {
+        record.lock().unwrap().push(*progress);
+    });
+    let progress = Queue::new(apps)
+        .download_reported(&client, false, &reporter, &CancellationToken::new())
+        .await?;
+    assert_eq!(*progress.skipped(), 3);
+    assert_eq!(progress.remaining(), 0);
+    assert_eq!(progress.eta(), None);
+    // one report on starting, then one per request
+    assert_eq!(updates.lock().unwrap().len(), 4);
+
+    // the listener counts outcomes, bytes and slack from the tracker
+    let (tx, mut rx) = tokio::sync::mpsc::channel(3);
+    let tracker = client.tracker().clone();
+    tx.send(ResultStatus::Success(uuid::Uuid::new_v4(), 2048))
+        .await
+        .map_err(|e| Check::from(format!("Status not sent: {e}")))?;
+    tx.send(ResultStatus::Error(uuid::Uuid::new_v4(), None))
+        .await
+        .map_err(|e| Check::from(format!("Status not sent: {e}")))?;
+    drop(tx);
+    let progress = Progress::new(Mode::Download, 4);
+    let progress = Queue::listen_reported(&mut rx, tracker, progress, &reporter).await?;
+    assert_eq!((*progress.done(), *progress.failed()), (1, 1));
+    assert_eq!(*progress.bytes(), 2048);
+    assert!(progress.slack().is_some());
+    assert_eq!(progress.remaining(), 2);
+    assert!(progress.eta().is_some());
+    Ok(())
+}
+
+/// Cancels a queue before starting and with requests in flight, writing nothing.
+#[tracing::instrument]
+pub async fn queue_cancellation() -> Result<(), BeaErr> {
+    // a server that accepts connections but never answers, so requests stay in flight
+    let (listener, url) = listen().await?;
+    tokio::spawn(async move {
+        let mut sockets = Vec::new();
+        while let Ok((socket, _)) = listener.accept().await {
+            sockets.push(socket);
+        }
+    });
+    let storage = MemoryStorage::default();
+    let client =
+        BeaClient::new("key".to_string(), url, std::env::temp_dir())?.with_storage(storage.clone());
+    let mut apps = Vec::new();
+    for table in ["FAAt101", "FAAt102", "FAAt103"] {
+        let mut app = client.request(Request::Data);
+        app.with_dataset(Dataset::FixedAssets);
+        let mut params = app.params();
+        params.insert("TableName".to_string(), table.to_string());
+        app.with_params(params);
+        apps.push(app);
+    }
+    let queue = Queue::new(apps);
+
+    // cancelled before starting, nothing is sent
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+    let progress = queue
+        .download_reported(&client, false, &Reporter::default(), &cancel)
+        .await?;
+    assert_eq!(progress.finished(), 0);
+    assert_eq!(progress.unfinished(), 3);
+
+    // cancelled in flight, each request reports cancellation and writes nothing
+    let cancel = CancellationToken::new();
+    let stop = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        stop.cancel();
+    });
+    let progress = tokio::time::timeout(
+        std::time::Duration::from_secs(10),
+        queue.download_reported(&client, false, &Reporter::default(), &cancel),
+    )
+    .await
+    .map_err(|e| Check::from(format!("Cancelled downloads did not stop: {e}")))??;
+    assert_eq!(*progress.cancelled(), 3);
+    assert_eq!(*progress.done() + *progress.failed(), 0);
+    assert!(storage.is_empty());
+    Ok(())
+}
+
+/// Streams chunked responses to storage, leaving no partial file when a response is truncated.
+#[tracing::instrument]
+pub async fn streamed_download() -> Result<(), BeaErr> {
+    let body = r#"{"BEAAPI": {"Request": {}, "Results": {"Data": [{"TableName": "FAAt101"}]}}}"#;
+    let url = serve_chunked(vec![body, r#"{"BEAAPI": {"Request": {}, "Resu"#]).await?;
+    let root = std::env::temp_dir().join(format!("bears_stream_{}", uuid::Uuid::new_v4()));
+    let client = BeaClient::new("key".to_string(), url, root.clone())?;
+    let mut app = client.request(Request::Data);
+    app.with_dataset(Dataset::FixedAssets);
+    let mut params = app.params();
+    params.insert("TableName".to_string(), "FAAt101".to_string());
+    app.with_params(params);
+    let storage = Backend::from(FsStorage::new(root.clone()));
+    let path = app.destination(false)?;
+    let folder = path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+    let id = uuid::Uuid::new_v4();
+
+    // a chunked response is written whole, recording the bytes written
+    let status = app.download_with(client.http(), &storage, id).await?;
+    assert_eq!(status, ResultStatus::Success(id, body.len() as u64));
+    assert_eq!(
+        std::fs::read_to_string(&path).map_err(|e| IoError::new(
+            path.clone(),
+            e,
+            line!(),
+            file!().into()
+        ))?,
+        body
+    );
+
+    // a truncated response leaves the earlier file and no temporary file
+    let status = app.download_with(client.http(), &storage, id).await?;
+    assert_eq!(status, ResultStatus::Error(id, None));
+    assert_eq!(
+        std::fs::read_to_string(&path).map_err(|e| IoError::new(
+            path.clone(),
+            e,
+            line!(),
+            file!().into()
+        ))?,
+        body
+    );
+    assert_eq!(
+        std::fs::read_dir(&folder)
+            .map_err(|e| IoError::new(folder.clone(), e, line!(), file!().into()))?
+            .count(),
+        1
+    );
+    std::fs::remove_dir_all(&root)
+        .map_err(|e| IoError::new(root.clone(), e, line!(), file!().into()))?;
+    Ok(())
+}
+
+/// Retries server errors, records request errors and aborts the queue when the rate limit is
+/// exceeded.
+#[tracing::instrument]
+pub async fn download_failures() -> Result<(), BeaErr> {
+    let response = |results: serde_json::Value| {
+        let json = serde_json::json!({
+            "BEAAPI": {
+                "Request": {
+                    "RequestParam": [
+                        { "ParameterName": "METHOD", "ParameterValue": "GETDATA" },
+                        { "ParameterName": "DATASETNAME", "ParameterValue": "FIXEDASSETS" }
+                    ]
+                },
+                "Results": results
+            }
+        });
+        json.to_string()
+    };
+    let error = |description: &'static str| {
+        response(serde_json::json!({
+            "Error": { "APIErrorCode": "101", "APIErrorDescription": description }
+        }))
+    };
+    let responses = vec![
+        (503, String::new()),
+        (404, String::new()),
+        (200, error("Internal server error")),
+        (200, error("Invalid Table Name")),
+        (200, response(serde_json::json!({ "Notes": [] }))),
+        (
+            200,
+            response(serde_json::json!({ "Data": [{ "Unknown": "1" }] })),
+        ),
+    ];
+    let (url, _) = serve_status(responses).await?;
+    let storage = MemoryStorage::default();
+    let client =
+        BeaClient::new("key".to_string(), url, std::env::temp_dir())?.with_storage(storage.clone());
+    let app = |table: &str| {
+        let mut app = client.request(Request::Data);
+        app.with_dataset(Dataset::FixedAssets);
+        let mut params = app.params();
+        params.insert("TableName".to_string(), table.to_string());
+        app.with_params(params);
+        app
+    };
+    let id = uuid::Uuid::new_v4();
+    let download = || async {
+        app("FAAt101")
+            .download_with(client.http(), client.storage(), id)
+            .await
+    };
+    // HTTP errors and server errors reported by the API fail, so they can be retried
+    let class = |result: Result<ResultStatus, BeaErr>| result.map_err(|e| e.class());
+    assert_eq!(class(download().await), Err(ErrorClass::Server));
+    assert_eq!(class(download().await), Err(ErrorClass::Request));
+    assert_eq!(class(download().await), Err(ErrorClass::Server));
+    // other API errors are recorded without retrying
+    assert_eq!(
+        download().await?,
+        ResultStatus::Error(id, Some(ApiErrorKind::InvalidValue))
+    );
+    assert!(storage.is_empty());
+    // an unrecognized response is an error and is not kept
+    assert_eq!(download().await?, ResultStatus::Error(id, None));
+    assert!(storage.is_empty());
+    // data responses are kept without parsing the records
+    assert!(matches!(download().await?, ResultStatus::Success(_, _)));
+    assert_eq!(storage.len(), 1);
+    storage.delete(&app("FAAt101").storage_key()?).await?;
+
+    // exceeding the rate limit is never retried, and aborts the queue
+    let (url, count) = serve_status(vec![(429, String::new()); 5]).await?;
+    let client =
+        BeaClient::new("key".to_string(), url, std::env::temp_dir())?.with_storage(storage.clone());
+    let queue = Queue::new(
+        ["FAAt101", "FAAt102", "FAAt103"]
+            .iter()
+            .map(|table| {
+                let mut app = client.request(Request::Data);
+                app.with_dataset(Dataset::FixedAssets);
+                let mut params = app.params();
+                params.insert("TableName".to_string(), table.to_string());
+                app.with_params(params);
+                app
+            })
+            .collect(),
+    );
+    let cancel = CancellationToken::new();
+    let result = tokio::time::timeout(
+        std::time::Duration::from_secs(10),
+        queue.download_reported(&client, false, &Reporter::default(), &cancel),
+    )
+    .await
+    .map_err(|e| Check::from(format!("Rate limit did not abort the queue: {e}")))?;
+    assert_eq!(
+        result.map_err(|e| e.class()).err(),
+        Some(ErrorClass::RateLimit)
+    );
+    assert!(count.load(std::sync::atomic::Ordering::SeqCst) <= 3);
+    assert!(!cancel.is_cancelled());
+    assert!(storage.is_empty());
+    Ok(())
+}
+
+/// Plans a download from the history and sends only the planned requests.
+#[tracing::instrument]
+pub async fn download_plan() -> Result<(), BeaErr> {
+    let storage = MemoryStorage::default();
+    let data = r#"{"BEAAPI": {"Request": {}, "Results": {"Data": []}}}"#;
+    let (url, count) = serve_status(vec![(200, data.to_string()); 2]).await?;
+    let client = BeaClient::new("key".to_string(), url, std::env::temp_dir())?
+        .with_limits(RateLimits::new(2, 10, 1_000_000))
+        .with_storage(storage.clone());
+    let mut apps = Vec::new();
+    for table in ["FAAt101", "FAAt102", "FAAt103", "FAAt104", "FAAt105"] {
+        let mut app = client.request(Request::Data);
+        app.with_dataset(Dataset::FixedAssets);
+        let mut params = app.params();
+        params.insert("TableName".to_string(), table.to_string());
+        app.with_params(params);
+        apps.push(app);
+    }
+    let paths = apps
+        .iter()
+        .map(|app| app.destination(false))
+        .collect::<Result<Vec<_>, _>>()?;
+    // the first table exists, the second has no data, the third is excluded, the fourth
+    // downloaded before and the fifth is new
+    storage.put(&apps[0].storage_key()?, b"{}".to_vec()).await?;
+    let event = |path: &std::path::PathBuf, length: Option<u64>, status: serde_json::Value| {
+        serde_json::from_value::<Event>(serde_json::json!({
+            "id": uuid::Uuid::new_v4(),
+            "length": length,
+            "mode": "Download",
+            "path": path,
+            "status": status,
+            "time": "2025-03-01T12:00:00Z",
+        }))
+    };
+    let id = uuid::Uuid::new_v4();
+    let history = History::from(vec![
+        event(
+            &paths[1],
+            None,
+            serde_json::json!({ "Error": [id, "NoData"] }),
+        )
+        .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?,
+        event(
+            &paths[3],
+            Some(3_000_000),
+            serde_json::json!({ "Success": [id, 3_000_000] }),
+        )
+        .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?,
+    ]);
+    let exclude = History::from(vec![event(
+        &paths[2],
+        None,
+        serde_json::json!({ "Error": [id, null] }),
+    )
+    .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?]);
+
+    let queue = Queue::new(apps);
+    let plan = queue.plan(&client, &history, Some(&exclude), false).await?;
+    let actions = plan
+        .steps()
+        .iter()
+        .map(|step| *step.action())
+        .collect::<Vec<Action>>();
+    assert_eq!(
+        actions,
+        vec![
+            Action::Exists,
+            Action::KnownError,
+            Action::Excluded,
+            Action::Download,
+            Action::Download
+        ]
+    );
+    assert_eq!(plan.steps()[1].request(), "TableName=FAAt102");
+    assert!(!plan.steps()[0].request().contains("key"));
+    assert_eq!(*plan.steps()[3].size(), Some(3_000_000));
+    // the new table counts at the median recorded size
+    assert_eq!(plan.total_size(), 6_000_000);
+    // two calls fit in a minute, but six megabytes take six minutes at one per minute
+    assert_eq!(plan.estimate(), std::time::Duration::from_secs(360));
+    assert!(plan.download_paths().contains(paths[4].as_path()));
+    assert!(plan.to_string().contains("2 to download"));
+
+    // the download sends only the planned requests
+    let progress = queue
+        .download_planned(
+            &client,
+            &plan,
+            &Reporter::default(),
+            &CancellationToken::new(),
+        )
+        .await?;
+    assert_eq!(count.load(std::sync::atomic::Ordering::SeqCst), 2);
+    assert_eq!(*progress.done(), 2);
+    assert!(!storage.exists(&queue[1].storage_key()?).await?);
+    assert!(!storage.exists(&queue[2].storage_key()?).await?);
+    assert!(storage.exists(&queue[4].storage_key()?).await?);
+
+    // the typical size is the median of the recorded sizes, whatever their order
+    let mut recorded = vec![event(
+        &paths[3],
+        Some(3_000_000),
+        serde_json::json!({ "Success": [id, 3_000_000] }),
+    )
+    .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?];
+    for (name, size) in [
+        ("e", 9_000_000),
+        ("a", 1_000_000),
+        ("d", 7_000_000),
+        ("b", 5_000_000),
+    ] {
+        let path = std::env::temp_dir().join(format!("{name}.json"));
+        recorded.push(
+            event(
+                &path,
+                Some(size),
+                serde_json::json!({ "Success": [id, size] }),
+            )
+            .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?,
+        );
+    }
+    let unsorted = queue
+        .plan(&client, &History::from(recorded), None, false)
+        .await?;
+    assert_eq!(*unsorted.typical_size(), 5_000_000);
+
+    // with overwrite the existing file is planned again, and the plan round trips through JSON
+    let plan = queue.plan(&client, &history, None, true).await?;
+    assert_eq!(plan.count(Action::Download), 4);
+    let json = serde_json::to_string(&plan)
+        .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+    assert_eq!(
+        serde_json::from_str::<Plan>(&json).map_err(|e| SerdeJson::new(
+            e,
+            line!(),
+            file!().to_string()
+        ))?,
+        plan
+    );
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use crate::{
+    ApiErrorKind, ApiFailure, BeaErr, BeaResponse, DatasetMissing, ErrorClass, Event, IoError,
+    RateLimit, ResultStatus, Results,
+};
+
+/// Classifies API errors by code and description, and records the kind in the history log.
+#[tracing::instrument]
+pub fn api_error_kinds() -> Result<(), BeaErr> {
+    use std::str::FromStr;
+
+    let json = serde_json::json!({
+        "BEAAPI": {
+            "Request": {
+                "RequestParam": [
+                    { "ParameterName": "METHOD", "ParameterValue": "GETDATA" },
+                    { "ParameterName": "DATASETNAME", "ParameterValue": "NIPA" }
+                ]
+            },
+            "Results": {
+                "Error": {
+                    "APIErrorCode": "101",
+                    "APIErrorDescription": "Error retrieving NIPA/Fixed Assets data: Invalid Table Name"
+                }
+            }
+        }
+    });
+    let response = BeaResponse::try_from(&json)?;
+    match response.results() {
+        Results::ApiError(error) => assert_eq!(error.kind(), ApiErrorKind::InvalidValue),
+        other => panic!("expected an API error, found {other:?}"),
+    }
+    let kinds = [
+        ("Invalid API UserId", ApiErrorKind::InvalidKey),
+        (
+            "The dataset requested does not exist",
+            ApiErrorKind::UnknownDataset,
+        ),
+        (
+            "Parameter LineCode is required",
+            ApiErrorKind::MissingParameter,
+        ),
+        (
+            "No data exists for the requested parameters",
+            ApiErrorKind::NoData,
+        ),
+        ("Internal server error", ApiErrorKind::Server),
+        // phrases shared between kinds resolve to the more specific kind
+        ("Request size exceeded", ApiErrorKind::TooLarge),
+        (
+            "Number of requests exceeded",
+            ApiErrorKind::RequestsExceeded,
+        ),
+        (
+            "Invalid value for parameter Year in dataset NIPA",
+            ApiErrorKind::InvalidValue,
+        ),
+        ("Invalid Method", ApiErrorKind::InvalidMethod),
+    ];
+    for (description, kind) in kinds {
+        assert_eq!(ApiErrorKind::from_description(description), kind);
+    }
+    // a code with a single meaning settles the kind, whatever the description says
+    assert_eq!(
+        ApiErrorKind::classify(40, "The dataset requested requires parameter:YEAR"),
+        ApiErrorKind::MissingParameter
+    );
+    assert_eq!(
+        ApiErrorKind::classify(429, "Request size exceeded"),
+        ApiErrorKind::RequestsExceeded
+    );
+    // shared codes fall back to the description
+    assert_eq!(ApiErrorKind::from_code(101), None);
+    assert_eq!(
+        ApiErrorKind::classify(101, "Request size exceeded"),
+        ApiErrorKind::TooLarge
+    );
+    assert!(ApiErrorKind::NoData.is_no_data());
+    assert!(ApiErrorKind::InvalidValue.is_request_error());
+    assert!(ApiErrorKind::Server.is_server_error());
+
+    // the kind survives the round trip through the history log
+    let status = ResultStatus::Error(uuid::Uuid::new_v4(), Some(ApiErrorKind::NoData));
+    assert_eq!(status.to_string(), "Error(NoData)");
+    let parsed = ResultStatus::from_str("Error(NoData)")?;
+    assert!(matches!(
+        parsed,
+        ResultStatus::Error(_, Some(ApiErrorKind::NoData))
+    ));
+    assert!(matches!(
+        ResultStatus::from_str("Error")?,
+        ResultStatus::Error(_, None)
+    ));
+    let line = serde_json::json!({
+        "fields": {
+            "message": "id: 0, length: None, mode: Download, path: \"/data/NIPA_T10101.json\", status: Error(NoData), time: 2025-01-01T00:00:00Z"
+        }
+    });
+    let event = Event::try_from(&line)?;
+    assert_eq!(event.error_kind(), Some(ApiErrorKind::NoData));
+    Ok(())
+}
+
+/// Sorts errors into classes, deciding which are retried and the exit code of each.
+#[tracing::instrument]
+pub fn error_classes() -> Result<(), BeaErr> {
+    let api = |kind| {
+        BeaErr::from(ApiFailure::new(
+            kind,
+            101,
+            "description".to_string(),
+            line!(),
+            file!().to_string(),
+        ))
+    };
+    assert_eq!(api(ApiErrorKind::Server).class(), ErrorClass::Server);
+    assert!(api(ApiErrorKind::Server).is_retryable());
+    assert_eq!(
+        api(ApiErrorKind::RequestsExceeded).class(),
+        ErrorClass::RateLimit
+    );
+    assert_eq!(api(ApiErrorKind::NoData).class(), ErrorClass::Request);
+    assert!(!api(ApiErrorKind::InvalidValue).is_retryable());
+    assert_eq!(api(ApiErrorKind::InvalidKey).class(), ErrorClass::Config);
+
+    let limit = BeaErr::from(RateLimit::new(
+        "exceeded".to_string(),
+        line!(),
+        file!().to_string(),
+    ));
+    // the queue stops on a rate limit rather than retrying, so it is not reported as retryable
+    assert_eq!(limit.class(), ErrorClass::RateLimit);
+    assert!(!limit.is_retryable());
+    // a response from the wrong dataset is a data error, not a configuration error
+    let dataset = BeaErr::from(DatasetMissing::new(
+        "Regional".to_string(),
+        line!(),
+        file!().to_string(),
+    ));
+    assert_eq!(dataset.class(), ErrorClass::Parse);
+    assert_eq!(dataset.class().exit_code(), 65);
+    let io = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+    let io = BeaErr::from(IoError::new(
+        "/missing".into(),
+        io,
+        line!(),
+        file!().to_string(),
+    ));
+    assert_eq!(io.class(), ErrorClass::Io);
+    assert!(!io.is_retryable());
+    assert_ne!(ErrorClass::Io.exit_code(), ErrorClass::Config.exit_code());
+    Ok(())
+}
@@ -0,0 +1,216 @@
+use crate::{BeaErr, IoError, SerdeJson, UrlParseError, ValueFilter, ValueSet, ValueSets, BEA_URL};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+pub(crate) const NIPA_RESPONSE: &str = r#"{"BEAAPI":{"Request":{"RequestParam":[]},"Results":{"Statistic":"NIPA Table","Data":[
+{"TableName":"T10105","SeriesCode":"A191RC","LineNumber":"1","LineDescription":"Gross domestic product","TimePeriod":"2023Q1","METRIC_NAME":"Current Dollars","CL_UNIT":"Level","UNIT_MULT":"6","DataValue":"26,813,601","NoteRef":"T10105"},
+{"TableName":"T10105","SeriesCode":"A191RC","LineNumber":"1","LineDescription":"Gross domestic product","TimePeriod":"2023Q2","METRIC_NAME":"Current Dollars","CL_UNIT":"Level","UNIT_MULT":"6","DataValue":"27,063,012","NoteRef":"T10105"}
+],"Notes":[]}}}"#;
+
+pub(crate) fn nipa_record(period: &str, value: &str) -> String {
+    format!(
+        r#"{{"TableName":"T10105","SeriesCode":"A191RC","LineNumber":"1","LineDescription":"Gross domestic product","TimePeriod":"{period}","METRIC_NAME":"Current Dollars","CL_UNIT":"Level","UNIT_MULT":"6","DataValue":"{value}","NoteRef":"T10105"}}"#
+    )
+}
+
+/// Parameter values for the NIPA and Regional datasets, in the form cached by [`ValueSet`].
+pub(crate) fn cached_values() -> Result<ValueSets, BeaErr> {
+    let sets = serde_json::json!([
+        {
+            "Nipa": {
+                "frequency": ["Annual", "Monthly", "Quarterly"],
+                "show_millions": [
+                    { "description": "Millions", "show": true },
+                    { "description": "Billions", "show": false }
+                ],
+                "table_name": [
+                    { "name": "T10101", "description": "Percent change in real GDP" },
+                    { "name": "T10105", "description": "Gross domestic product" },
+                    { "name": "T20600", "description": "Personal income, monthly" }
+                ],
+                "year": {
+                    "T10101": {
+                        "annual": { "first": "1930-01-01", "last": "2023-01-01" },
+                        "monthly": null,
+                        "quarterly": { "first": "1947-01-01", "last": "2023-01-01" }
+                    }
+                }
+            }
+        },
+        {
+            "Regional": {
+                "geo_fips": [
+                    { "value": 0, "description": "United States" },
+                    { "value": 1000, "description": "Alabama" },
+                    { "value": 2000, "description": "Alaska" }
+                ],
+                "line_code": [
+                    { "value": 1, "description": "Personal income" },
+                    { "value": 2, "description": "Population" }
+                ],
+                "table_name": [
+                    { "name": "CAINC1", "description": "Personal income summary" },
+                    { "name": "SAINC1", "description": "State personal income summary" }
+                ],
+                "year": [
+                    { "date": "2022-01-01", "description": "2022" },
+                    { "date": "2023-01-01", "description": "2023" }
+                ]
+            }
+        }
+    ]);
+    let sets: Vec<ValueSet> = serde_json::from_value(sets)
+        .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+    Ok(sets.into())
+}
+
+/// Writes `contents` to `path`, creating its parent directories.
+pub(crate) fn write_file(path: &std::path::Path, contents: &[u8]) -> Result<(), BeaErr> {
+    if let Some(folder) = path.parent() {
+        std::fs::create_dir_all(folder)
+            .map_err(|e| IoError::new(folder.to_path_buf(), e, line!(), file!().into()))?;
+    }
+    std::fs::write(path, contents)
+        .map_err(|e| IoError::new(path.to_path_buf(), e, line!(), file!().into()))?;
+    Ok(())
+}
+
+/// Writes a GetParameterValuesFiltered response for `filter` to the cache under `root`.
+pub(crate) fn cache_filtered(
+    root: &std::path::Path,
+    filter: &ValueFilter,
+    values: &[(&str, &str)],
+) -> Result<(), BeaErr> {
+    let values = values
+        .iter()
+        .map(|(key, desc)| serde_json::json!({ "Key": key, "Desc": desc }))
+        .collect::<Vec<serde_json::Value>>();
+    let json = serde_json::json!({
+        "BEAAPI": {
+            "Request": {
+                "RequestParam": [
+                    { "ParameterName": "METHOD", "ParameterValue": "GETPARAMETERVALUESFILTERED" },
+                    { "ParameterName": "TARGETPARAMETER", "ParameterValue": filter.target().to_string() }
+                ]
+            },
+            "Results": { "ParamValue": values }
+        }
+    });
+    let contents =
+        serde_json::to_vec(&json).map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+    write_file(&filter.path(root), &contents)?;
+    Ok(())
+}
+
+/// Writes a GetParameterValuesFiltered response reporting no data for `filter` to the cache
+/// under `root`.
+pub(crate) fn cache_no_data(root: &std::path::Path, filter: &ValueFilter) -> Result<(), BeaErr> {
+    let json = serde_json::json!({
+        "BEAAPI": {
+            "Request": {
+                "RequestParam": [
+                    { "ParameterName": "METHOD", "ParameterValue": "GETPARAMETERVALUESFILTERED" }
+                ]
+            },
+            "Results": {
+                "Error": {
+                    "APIErrorCode": "101",
+                    "APIErrorDescription": "No data exists for the selected parameters."
+                }
+            }
+        }
+    });
+    let contents =
+        serde_json::to_vec(&json).map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+    write_file(&filter.path(root), &contents)?;
+    Ok(())
+}
+
+/// Parses the url of the BEA API, for clients that never send a request.
+pub(crate) fn bea_url() -> Result<url::Url, BeaErr> {
+    let url = url::Url::parse(BEA_URL)
+        .map_err(|e| UrlParseError::new(BEA_URL.into(), e, line!(), file!().into()))?;
+    Ok(url)
+}
+
+/// Binds a listener to a free local port, returning it with the url of its data endpoint.
+pub(crate) async fn listen() -> Result<(tokio::net::TcpListener, url::Url), BeaErr> {
+    let address = "127.0.0.1:0";
+    let listener = tokio::net::TcpListener::bind(address)
+        .await
+        .map_err(|e| IoError::new(address.into(), e, line!(), file!().into()))?;
+    let local = listener
+        .local_addr()
+        .map_err(|e| IoError::new(address.into(), e, line!(), file!().into()))?;
+    let target = format!("http://{local}/api/data");
+    let url = url::Url::parse(&target)
+        .map_err(|e| UrlParseError::new(target.clone(), e, line!(), file!().into()))?;
+    Ok((listener, url))
+}
+
+/// Serves each body in `bodies` to one connection, using chunked encoding so the response has no
+/// Content-Length header.
+pub(crate) async fn serve_chunked(bodies: Vec<&'static str>) -> Result<url::Url, BeaErr> {
+    let (listener, url) = listen().await?;
+    tokio::spawn(async move {
+        for body in bodies {
+            let Ok((mut socket, _)) = listener.accept().await else {
+                return;
+            };
+            // a GET request ends with the blank line after its headers
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                match socket.read(&mut buffer).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => request.extend_from_slice(&buffer[..n]),
+                }
+            }
+            let mut response = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Transfer-Encoding: chunked\r\nConnection: close\r\n\r\n"
+                .to_string();
+            for chunk in body.as_bytes().chunks(8) {
+                response.push_str(&format!("{:x}\r\n", chunk.len()));
+                response.push_str(std::str::from_utf8(chunk).unwrap_or_default());
+                response.push_str("\r\n");
+            }
+            response.push_str("0\r\n\r\n");
+            socket.write_all(response.as_bytes()).await.ok();
+            socket.shutdown().await.ok();
+        }
+    });
+    Ok(url)
+}
+
+/// Serves each `(status, body)` pair in `responses` to one connection, counting the requests
+/// received in the returned counter.
+pub(crate) async fn serve_status(
+    responses: Vec<(u16, String)>,
+) -> Result<(url::Url, std::sync::Arc<std::sync::atomic::AtomicUsize>), BeaErr> {
+    let (listener, url) = listen().await?;
+    let count = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let counter = count.clone();
+    tokio::spawn(async move {
+        for (status, body) in responses {
+            let Ok((mut socket, _)) = listener.accept().await else {
+                return;
+            };
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                match socket.read(&mut buffer).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => request.extend_from_slice(&buffer[..n]),
+                }
+            }
+            let response = format!(
+                "HTTP/1.1 {status} Status\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).await.ok();
+            socket.shutdown().await.ok();
+        }
+    });
+    Ok((url, count))
+}
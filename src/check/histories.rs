@@ -1,15 +1,16 @@
-use crate::{trace_init, BeaErr, Dataset, History, Mode};
+use crate::{trace_init, BeaClient, BeaErr, Dataset, History, Mode};
 
 #[tracing::instrument]
 pub async fn download_summary() -> Result<(), BeaErr> {
     trace_init()?;
+    let client = BeaClient::from_env()?;
     let datasets = vec![
         Dataset::Nipa,
         Dataset::NIUnderlyingDetail,
         Dataset::FixedAssets,
     ];
     for dataset in datasets {
-        let history = History::try_from((dataset, Mode::Download))?;
+        let history = History::from_dataset(client.root(), dataset, Mode::Download)?;
         tracing::info!("Dataset: {dataset}");
         history.summary();
     }
@@ -1,24 +1,46 @@
+mod config;
 mod data;
 mod datasets;
+mod downloads;
+mod errors;
+mod fixtures;
 mod histories;
 mod parameter_values;
 mod parameters;
+mod requests;
+mod search;
+mod series;
+mod storage;
 mod values;
 
+pub use config::{config_profiles, shared_client};
 pub use data::{data_from_json, data_to_json, download_history, naics};
 pub use datasets::{
     check_datasets, datasets_from_file, datasets_json_to_bin, datasets_to_json,
     deserialize_datasets,
 };
+pub use downloads::{
+    download_failures, download_plan, queue_cancellation, queue_progress, streamed_download,
+};
+pub use errors::{api_error_kinds, error_classes};
 pub use histories::download_summary;
 pub use parameter_values::{
     parameter_value_filtered, parameter_value_from_bin, parameter_value_from_file,
     parameter_value_json_to_bin, parameter_values_to_json,
 };
 pub use parameters::{
-    deserialize_parameters, diff_parameters, parameter_names, parameters_from_file,
-    parameters_json_to_bin, parameters_to_json,
+    deserialize_parameters, diff_parameters, parameter_names, parameter_rules,
+    parameters_from_file, parameters_json_to_bin, parameters_to_json,
+};
+pub use requests::{
+    catalog_rules, prune_iterator, prune_warm, request_builders, validate_queue, value_filter,
+};
+pub use search::{catalog_search, search_index_saved};
+pub use series::{
+    data_stream, deflate, measure_units, period, rebase, series_aggregate, series_collection,
+    series_measurement, series_transforms, table_view,
 };
+pub use storage::{s3_signature, storage_backends, storage_roots};
 pub use values::{
     api_error, requests_exceeded, value_sets, values_filtered, values_filtered_subset,
     values_gdp_filtered, values_ugdp_filtered,
@@ -1,6 +1,6 @@
 use crate::{
-    bea_data, trace_init, App, BeaErr, BeaResponse, Dataset, IoError, Json, ParameterName, Request,
-    ReqwestError, SerdeJson,
+    bea_data, trace_init, App, BeaErr, BeaResponse, Catalog, Dataset, IoError, Json, ParameterName,
+    Request, ReqwestError, SerdeJson,
 };
 use std::str::FromStr;
 use strum::IntoEnumIterator;
@@ -164,3 +164,17 @@ pub fn parameter_names() -> Result<(), BeaErr> {
 
     Ok(())
 }
+
+/// Builds the parameter rules catalog from the saved `GetParameterList` responses, and checks
+/// [`Dataset::names`] against it.
+#[tracing::instrument]
+pub fn parameter_rules() -> Result<(), BeaErr> {
+    trace_init()?;
+    let catalog = Catalog::from_env()?;
+    for (dataset, rules) in catalog.iter() {
+        tracing::info!("{dataset} has {} parameter rules.", rules.rules().len());
+    }
+    catalog.check_names()?;
+    tracing::info!("Parameter names match the catalog.");
+    Ok(())
+}
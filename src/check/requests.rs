@@ -0,0 +1,411 @@
+use super::fixtures::{bea_url, cache_filtered, cache_no_data, cached_values, serve_chunked};
+use crate::validate::{GeoFips, TableName};
+use crate::{
+    value_dependencies, BeaClient, BeaErr, BeaErrorKind, Catalog, Dataset, Frequency, Integer,
+    IoError, MneRequest, NipaRequest, ParameterName, Parameters, Prune, RegionalRequest, Rules,
+    SerdeJson, ValueFilter, ValueSet,
+};
+
+/// Builds NIPA and Regional requests against cached parameter values, suggesting corrections for
+/// invalid values.
+#[tracing::instrument]
+pub fn request_builders() -> Result<(), BeaErr> {
+    let values = cached_values()?;
+    assert_eq!(
+        values
+            .get(Dataset::Regional)
+            .and_then(|set| set.values(ParameterName::TableName)),
+        Some(vec!["CAINC1".to_string(), "SAINC1".to_string()])
+    );
+
+    let params = NipaRequest::table("T10101")
+        .frequency(Frequency::Quarterly)
+        .years([2022, 2023])
+        .params(&values)?;
+    assert_eq!(params.get("Frequency"), Some(&"Q".to_string()));
+    assert_eq!(params.get("Year"), Some(&"2022,2023".to_string()));
+    assert_eq!(params.get("ShowMillions"), Some(&"N".to_string()));
+    // built requests carry the key and url of the client, without reading the environment
+    let url = bea_url()?;
+    let client = BeaClient::new("key".to_string(), url, std::env::temp_dir())?;
+    let app = NipaRequest::table("T10101").build(&client, &values, &Catalog::default())?;
+    assert_eq!(app.key(), "key");
+    assert_eq!(app.query().get("TableName"), Some(&"T10101".to_string()));
+
+    let all_invalid = |result: Result<_, BeaErr>| match result {
+        Err(e) => match &**e {
+            BeaErrorKind::ParamsInvalid(e) => Some(e.clone()),
+            _ => None,
+        },
+        Ok(_) => None,
+    };
+    let invalid = |result| all_invalid(result).map(|e| e.errors()[0].clone());
+    let error = invalid(NipaRequest::table("T1010").params(&values)).unwrap();
+    assert_eq!(error.parameter(), "TableName");
+    assert_eq!(error.suggestions()[..2], ["T10101", "T10105"]);
+    let error = invalid(
+        NipaRequest::table("T10101")
+            .frequency(Frequency::Monthly)
+            .params(&values),
+    )
+    .unwrap();
+    assert_eq!(error.suggestions(), &vec!["A".to_string(), "Q".to_string()]);
+    let error = invalid(NipaRequest::table("T10101").years([1920]).params(&values)).unwrap();
+    assert_eq!(error.parameter(), "Year");
+    let error = all_invalid(NipaRequest::table("T1010").years([1920]).params(&values)).unwrap();
+    let parameters = error
+        .errors()
+        .iter()
+        .map(|e| e.parameter().as_str())
+        .collect::<Vec<&str>>();
+    assert_eq!(parameters.len(), 2);
+    assert!(parameters.contains(&"TableName") && parameters.contains(&"Year"));
+
+    let params = RegionalRequest::table("cainc1")
+        .line_code(1)
+        .geo_fips("01000")
+        .geo_fips("COUNTY")
+        .params(&values)?;
+    assert_eq!(params.get("GeoFips"), Some(&"01000,COUNTY".to_string()));
+    assert_eq!(params.get("Year"), Some(&"LAST5".to_string()));
+    let error = all_invalid(
+        RegionalRequest::table("CAINC1")
+            .geo_fips("01000")
+            .params(&values),
+    )
+    .unwrap();
+    assert_eq!(error.missing(), &vec!["LineCode".to_string()]);
+    assert!(error.errors().is_empty());
+    let error = all_invalid(RegionalRequest::table("CAINC1").params(&values)).unwrap();
+    assert_eq!(error.missing().len(), 2);
+    let error = invalid(
+        RegionalRequest::table("CAINC1")
+            .line_code(1)
+            .geo_fips("COUNTIES")
+            .params(&values),
+    )
+    .unwrap();
+    assert_eq!(error.suggestions()[0], "COUNTY");
+    assert!(MneRequest::direction("outward").params(&values).is_err());
+    Ok(())
+}
+
+/// Validates the parameters of a queue of requests against cached parameter values.
+#[tracing::instrument]
+pub fn validate_queue() -> Result<(), BeaErr> {
+    use crate::{App, Dataset, Options, ParameterName, Queue, Validate};
+
+    let values = cached_values()?;
+    let validate = Validate::new(values.clone());
+    let pn = ParameterName::GeoFips;
+    assert_eq!(
+        validate.is_valid(Dataset::Regional, pn, "01000,STATE"),
+        Some(true)
+    );
+    assert_eq!(
+        validate.is_valid(Dataset::Regional, pn, "01000,99000"),
+        Some(false)
+    );
+    assert_eq!(
+        validate.is_valid(Dataset::Regional, ParameterName::Industry, "1"),
+        None
+    );
+    assert!(validate
+        .check(Dataset::Nipa, ParameterName::TableName, "T10105")
+        .is_ok());
+
+    let url = bea_url()?;
+    let app = |dataset: Dataset, params: &[(ParameterName, &str)]| {
+        let mut app = App::new(
+            "key".to_string(),
+            Options::default(),
+            url.clone(),
+            std::env::temp_dir(),
+        );
+        app.with_dataset(dataset);
+        let params = params
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        app.with_params(params);
+        app
+    };
+    let queue = Queue::new(vec![
+        app(
+            Dataset::Nipa,
+            &[
+                (ParameterName::TableName, "T10101"),
+                (ParameterName::Frequency, "Q"),
+                (ParameterName::Year, "2023"),
+            ],
+        ),
+        app(
+            Dataset::Nipa,
+            &[
+                (ParameterName::TableName, "T10101"),
+                (ParameterName::Frequency, "A,M"),
+                (ParameterName::Year, "1925"),
+            ],
+        ),
+        app(
+            Dataset::Regional,
+            &[
+                (ParameterName::TableName, "CAINC9"),
+                (ParameterName::GeoFips, "COUNTY"),
+                (ParameterName::LineCode, "1"),
+            ],
+        ),
+        app(Dataset::Mne, &[(ParameterName::Classification, "country")]),
+    ]);
+    let invalid = validate.queue(&queue)?;
+    assert_eq!(invalid.len(), 2);
+    assert_eq!(invalid[0].index(), &1);
+    let names = invalid[0]
+        .errors()
+        .iter()
+        .map(|e| e.parameter().as_str())
+        .collect::<Vec<&str>>();
+    assert_eq!(names, vec!["Frequency", "Year"]);
+    assert_eq!(invalid[1].index(), &2);
+    assert_eq!(invalid[1].errors()[0].suggestions()[0], "CAINC1");
+    let mut queue = queue;
+    let removed = queue.validate(&validate)?;
+    assert_eq!(removed.len(), 2);
+    assert_eq!(queue.len(), 2);
+    assert!(validate.queue(&queue)?.is_empty());
+
+    if let Some(ValueSet::Regional(regional)) = values.get(Dataset::Regional) {
+        assert!(GeoFips::from(regional).contains("01000"));
+        assert!(!TableName::from(regional).contains("CAINC9"));
+    }
+    Ok(())
+}
+
+/// Enforces the parameter rules of a dataset, as read from the parameter lists in the catalog.
+#[tracing::instrument]
+pub fn catalog_rules() -> Result<(), BeaErr> {
+    let rule = |name: &str, required: &str, multiple: &str, default: &str, all: &str| {
+        serde_json::json!({
+            "ParameterName": name,
+            "ParameterDataType": "string",
+            "ParameterDescription": name,
+            "ParameterIsRequiredFlag": required,
+            "ParameterDefaultValue": default,
+            "MultipleAcceptedFlag": multiple,
+            "AllValue": all,
+        })
+    };
+    let json = serde_json::json!({
+        "Parameter": [
+            rule("GeoFips", "1", "1", "", ""),
+            rule("LineCode", "1", "0", "", ""),
+            rule("TableName", "1", "0", "", ""),
+            rule("Year", "0", "1", "LAST5", "ALL"),
+        ]
+    });
+    let parameters = Parameters::try_from(&json)?;
+    let rules = Rules::from_parameters(Dataset::Regional, &parameters);
+    assert!(rules.check_names().is_ok());
+    assert_eq!(
+        rules.rules()[&ParameterName::Year].default(),
+        &Some("LAST5".to_string())
+    );
+    assert_eq!(rules.rules()[&ParameterName::GeoFips].default(), &None);
+
+    let mut params = std::collections::BTreeMap::from([
+        ("TableName".to_string(), "CAINC1".to_string()),
+        ("linecode".to_string(), "1".to_string()),
+        ("GeoFips".to_string(), "01000,02000".to_string()),
+    ]);
+    rules.enforce(&mut params)?;
+    assert_eq!(params.get("Year"), Some(&"LAST5".to_string()));
+
+    params.insert("linecode".to_string(), "1,2".to_string());
+    let result = rules.enforce(&mut params);
+    assert!(matches!(
+        result.map_err(|e| matches!(&**e, BeaErrorKind::MultipleValues(_))),
+        Err(true)
+    ));
+    params.remove("linecode");
+    let result = rules.enforce(&mut params);
+    assert!(matches!(
+        result.map_err(|e| matches!(&**e, BeaErrorKind::ParameterMissing(_))),
+        Err(true)
+    ));
+
+    let nipa = Rules::from_parameters(Dataset::Nipa, &parameters);
+    assert!(nipa.check_names().is_err());
+
+    // datasets without a saved parameter list are left out of the catalog
+    let root = std::env::temp_dir().join(format!("bears_rules_{}", std::process::id()));
+    std::fs::create_dir_all(root.join("parameters"))
+        .map_err(|e| IoError::new(root.join("parameters"), e, line!(), file!().into()))?;
+    let response = serde_json::json!({
+        "BEAAPI": {
+            "Request": {
+                "RequestParam": [
+                    { "ParameterName": "METHOD", "ParameterValue": "GETPARAMETERLIST" },
+                    { "ParameterName": "DATASETNAME", "ParameterValue": "Regional" }
+                ]
+            },
+            "Results": json
+        }
+    });
+    let path = root.join("parameters/Regional_parameters.json");
+    std::fs::write(
+        &path,
+        serde_json::to_vec(&response)
+            .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?,
+    )
+    .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
+    let catalog = Catalog::from_path(&root)?;
+    assert_eq!(catalog.keys().collect::<Vec<_>>(), vec![&Dataset::Regional]);
+    let mut params = std::collections::BTreeMap::from([
+        ("TableName".to_string(), "CAINC1".to_string()),
+        ("LineCode".to_string(), "1".to_string()),
+        ("GeoFips".to_string(), "STATE".to_string()),
+    ]);
+    catalog.enforce_params(Dataset::Regional, &mut params)?;
+    assert_eq!(params.get("Year"), Some(&"LAST5".to_string()));
+    params.remove("GeoFips");
+    assert!(catalog
+        .enforce_params(Dataset::Regional, &mut params)
+        .is_err());
+    let mut nipa = std::collections::BTreeMap::new();
+    catalog.enforce_params(Dataset::Nipa, &mut nipa)?;
+    assert!(nipa.is_empty());
+    std::fs::remove_dir_all(&root)
+        .map_err(|e| IoError::new(root.clone(), e, line!(), file!().into()))?;
+    Ok(())
+}
+
+/// Reads filtered parameter values from the cache, including responses reporting no data.
+#[tracing::instrument]
+pub fn value_filter() -> Result<(), BeaErr> {
+    let root = std::env::temp_dir().join(format!("bears_filter_{}", std::process::id()));
+    let filter = ValueFilter::new(Dataset::Regional, ParameterName::LineCode)
+        .with(ParameterName::TableName, "CAINC5N");
+    assert_eq!(
+        filter.params().get("TargetParameter"),
+        Some(&"LineCode".to_string())
+    );
+    assert!(filter.cached(&root)?.is_none());
+
+    cache_filtered(
+        &root,
+        &filter,
+        &[
+            ("10", "[CAINC5N] Personal income (thousands of dollars)"),
+            ("20", "[CAINC5N] Population (persons)"),
+        ],
+    )?;
+
+    let codes = filter
+        .cached_values::<Integer, _>(&root)?
+        .expect("values cached");
+    let codes = codes.iter().map(|c| *c.value()).collect::<Vec<i32>>();
+    assert_eq!(codes, vec![10, 20]);
+    // a different filter has its own cache
+    let other = ValueFilter::new(Dataset::Regional, ParameterName::LineCode)
+        .with(ParameterName::TableName, "SAINC1");
+    assert!(other.cached(&root)?.is_none());
+
+    // filter values differing only in punctuation have distinct caches
+    let fips = |value: &str| {
+        ValueFilter::new(Dataset::Regional, ParameterName::Year)
+            .with(ParameterName::GeoFips, value)
+            .path(&root)
+    };
+    assert_ne!(fips("01000,02000"), fips("01000-02000"));
+    assert_ne!(fips("a b"), fips("a_b"));
+    assert!(fips("01000,02000").ends_with("Regional_Year_GeoFips-01000%2C02000.json"));
+
+    // a "no data" response is cached as an empty set of values
+    let empty = ValueFilter::new(Dataset::Regional, ParameterName::LineCode)
+        .with(ParameterName::TableName, "CAINC99");
+    cache_no_data(&root, &empty)?;
+    assert_eq!(empty.cached(&root)?.map(|values| values.len()), Some(0));
+    std::fs::remove_dir_all(&root)
+        .map_err(|e| IoError::new(root.clone(), e, line!(), file!().into()))?;
+    Ok(())
+}
+
+/// Drops requests for parameter values the cached filtered values show are not published.
+#[tracing::instrument]
+pub fn prune_iterator() -> Result<(), BeaErr> {
+    let root = std::env::temp_dir().join(format!("bears_prune_{}", std::process::id()));
+    let filter = ValueFilter::new(Dataset::Regional, ParameterName::LineCode)
+        .with(ParameterName::TableName, "CAINC5N");
+    cache_filtered(
+        &root,
+        &filter,
+        &[("10", "Personal income"), ("20", "Population")],
+    )?;
+    let empty = ValueFilter::new(Dataset::Regional, ParameterName::LineCode)
+        .with(ParameterName::TableName, "CAINC99");
+    cache_no_data(&root, &empty)?;
+
+    let request = |table: &str, line_code: &str| {
+        std::collections::BTreeMap::from([
+            ("TableName".to_string(), table.to_string()),
+            ("LineCode".to_string(), line_code.to_string()),
+            ("GeoFips".to_string(), "STATE".to_string()),
+        ])
+    };
+    let requests = vec![
+        request("CAINC5N", "10"),
+        request("CAINC5N", "30"),
+        request("CAINC5N", "20"),
+        // no filtered values cached for this table, so the request passes
+        request("SAINC1", "30"),
+        // the BEA reported no line codes for this table
+        request("CAINC99", "10"),
+    ];
+    let prune = Prune::new(&root, Dataset::Regional);
+    let mut pruned = prune.prune(requests.into_iter());
+    let kept = pruned
+        .by_ref()
+        .map(|params| params["LineCode"].clone())
+        .collect::<Vec<String>>();
+    assert_eq!(kept, vec!["10", "20", "30"]);
+    assert_eq!(pruned.skipped(), 2);
+    std::fs::remove_dir_all(&root)
+        .map_err(|e| IoError::new(root.clone(), e, line!(), file!().into()))?;
+    Ok(())
+}
+
+/// Warms the filtered value cache from a local server before pruning requests.
+#[tracing::instrument]
+pub async fn prune_warm() -> Result<(), BeaErr> {
+    // the frequencies of NIPA tables come from the table metadata, not filtered values
+    assert!(value_dependencies(Dataset::Nipa).is_empty());
+
+    let body = r#"{"BEAAPI": {"Request": {"RequestParam": [
+        {"ParameterName": "METHOD", "ParameterValue": "GETPARAMETERVALUESFILTERED"}]},
+        "Results": {"ParamValue": [{"Key": "10", "Desc": "Personal income"}]}}}"#;
+    let url = serve_chunked(vec![body]).await?;
+    let root = std::env::temp_dir().join(format!("bears_warm_{}", std::process::id()));
+    let client = BeaClient::new("key".to_string(), url, root.clone())?;
+    let request = |line_code: &str| {
+        std::collections::BTreeMap::from([
+            ("TableName".to_string(), "CAINC5N".to_string()),
+            ("LineCode".to_string(), line_code.to_string()),
+        ])
+    };
+    let requests = vec![request("10"), request("30")];
+    let prune = Prune::new(&root, Dataset::Regional);
+
+    // against a cold cache nothing is pruned
+    assert_eq!(prune.prune(requests.clone().into_iter()).count(), 2);
+    // warming requests the one missing filter, and then prunes the unpublished line code
+    assert_eq!(prune.warm(&client, requests.clone().into_iter()).await?, 1);
+    assert_eq!(prune.warm(&client, requests.clone().into_iter()).await?, 0);
+    let kept = prune
+        .prune(requests.into_iter())
+        .map(|params| params["LineCode"].clone())
+        .collect::<Vec<String>>();
+    assert_eq!(kept, vec!["10"]);
+    std::fs::remove_dir_all(&root)
+        .map_err(|e| IoError::new(root.clone(), e, line!(), file!().into()))?;
+    Ok(())
+}
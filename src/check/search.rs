@@ -0,0 +1,163 @@
+use super::fixtures::{write_file, NIPA_RESPONSE};
+use crate::{
+    BeaErr, DataStream, Dataset, Integer, IoError, MneDiDatum, Naics, NipaDatum, SearchIndex,
+    SerdeJson, TableName,
+};
+
+/// Searches an index of table names and NIPA, Regional and MNE series.
+#[tracing::instrument]
+pub fn catalog_search() -> Result<(), BeaErr> {
+    let mut index = SearchIndex::default();
+    let tables = vec![
+        TableName::new(
+            "T20100".into(),
+            "Table 2.1. Personal Income and Its Disposition (A) (Q)".into(),
+        ),
+        TableName::new(
+            "T10105".into(),
+            "Table 1.1.5. Gross Domestic Product (A) (Q)".into(),
+        ),
+    ];
+    index.with_tables(Dataset::Nipa, &tables);
+    let rdr = std::io::Cursor::new(NIPA_RESPONSE.as_bytes().to_vec());
+    let data = DataStream::from_reader(rdr, NipaDatum::read_json)
+        .collect::<Result<Vec<NipaDatum>, BeaErr>>()?;
+    index.with_nipa(Dataset::Nipa, &data);
+    // two quarters of the same line are indexed once
+    assert_eq!(index.len(), 3);
+
+    let hits = index.search("gross domestic product", 10);
+    assert_eq!(hits.len(), 2);
+    assert!(hits[0].entry().is_table());
+    assert_eq!(hits[0].entry().table_name(), "T10105");
+    assert_eq!(hits[1].entry().line_number(), &Some(1));
+    assert!(
+        index.search("personal saving rate", 10)[0]
+            .entry()
+            .table_name()
+            == "T20100"
+    );
+    assert!(index.search("", 10).is_empty());
+
+    assert_eq!(index.series("a191rc").len(), 1);
+    let locations = index.locate("A191RC");
+    assert_eq!(locations.len(), 1);
+    assert_eq!(locations[0].table_name(), "T10105");
+    assert_eq!(
+        locations[0].table_description().as_deref(),
+        Some("Table 1.1.5. Gross Domestic Product (A) (Q)")
+    );
+    assert!(index.locate("XXXXXX").is_empty());
+
+    // Regional lines take their table from the bracketed prefix of the description
+    index.with_regional(&[
+        Integer::new(10, "[CAINC5N] Wages and salaries".into()),
+        Integer::new(11, "Lines without a table".into()),
+    ]);
+    let hits = index.search("wages salaries", 10);
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].entry().dataset(), &Dataset::Regional);
+    assert_eq!(hits[0].entry().table_name(), "CAINC5N");
+    assert_eq!(hits[0].entry().description(), "Wages and salaries");
+
+    // MNE rows are indexed once for each series
+    let record = |column: &str| {
+        serde_json::json!({
+            "Column": column, "ColumnCode": "1", "ColumnGParent": "All", "ColumnGParentCode": "0",
+            "ColumnParent": "All", "ColumnParentCode": "0", "DataValue": "5",
+            "DataValueUnformatted": "5", "Row": "Chemical manufacturing", "RowCode": "325",
+            "SeriesID": "4", "SeriesName": "Direct Investment Position",
+            "TableColumnDisplayOrder": "1", "TableRowDisplayOrder": "2",
+            "TableScale": "Millions of Dollars", "Year": "2022"
+        })
+    };
+    let data = ["2022", "2023"]
+        .iter()
+        .map(|column| match record(column) {
+            serde_json::Value::Object(m) => MneDiDatum::read_json(&m, &Naics::default()),
+            _ => unreachable!(),
+        })
+        .collect::<Result<Vec<MneDiDatum>, BeaErr>>()?;
+    index.with_mne(&data);
+    let hits = index.search("chemical", 10);
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].entry().table_name(), "Direct Investment Position");
+    assert_eq!(index.series("4").len(), 1);
+    Ok(())
+}
+
+/// Saves the search index, reading it back until a source file is newer.
+#[tracing::instrument]
+pub fn search_index_saved() -> Result<(), BeaErr> {
+    let root = std::env::temp_dir().join(format!("bears_search_{}", std::process::id()));
+    let tables = |tables: &[(&str, &str)]| {
+        let values = tables
+            .iter()
+            .map(|(key, desc)| serde_json::json!({ "Key": key, "Desc": desc }))
+            .collect::<Vec<serde_json::Value>>();
+        serde_json::json!({
+            "BEAAPI": {
+                "Request": {
+                    "RequestParam": [
+                        { "ParameterName": "METHOD", "ParameterValue": "GETPARAMETERVALUES" }
+                    ]
+                },
+                "Results": { "ParamValue": values }
+            }
+        })
+    };
+    let source = root.join("parameter_values/FixedAssets_TableName_parameter_values.json");
+    let contents = serde_json::to_vec(&tables(&[("FAAt101", "Fixed Assets")]))
+        .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+    write_file(&source, &contents)?;
+    let age = |path: &std::path::Path, seconds: i64| -> Result<(), BeaErr> {
+        let time = std::time::SystemTime::now();
+        let time = match seconds < 0 {
+            true => time - std::time::Duration::from_secs(seconds.unsigned_abs()),
+            false => time + std::time::Duration::from_secs(seconds as u64),
+        };
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .and_then(|file| file.set_modified(time))
+            .map_err(|e| IoError::new(path.to_path_buf(), e, line!(), file!().into()))?;
+        Ok(())
+    };
+
+    // the first load builds and saves the index
+    let index = SearchIndex::load(&root)?;
+    assert_eq!(index.len(), 1);
+    let saved = SearchIndex::index_path(&root);
+    assert!(saved.exists());
+
+    // a fresh saved index is read rather than rebuilt
+    let mut other = SearchIndex::default();
+    other.with_tables(
+        Dataset::FixedAssets,
+        &[
+            TableName::new("FAAt101".into(), "Fixed Assets".into()),
+            TableName::new("FAAt102".into(), "Saved only".into()),
+        ],
+    );
+    other.save(&root)?;
+    age(&saved, 60)?;
+    assert_eq!(SearchIndex::load(&root)?.len(), 2);
+
+    // a source newer than the saved index forces a rebuild
+    age(&saved, -60)?;
+    std::fs::write(
+        &source,
+        serde_json::to_vec(&tables(&[
+            ("FAAt101", "Fixed Assets"),
+            ("FAAt201", "Chain"),
+        ]))
+        .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?,
+    )
+    .map_err(|e| IoError::new(source.clone(), e, line!(), file!().into()))?;
+    let index = SearchIndex::load(&root)?;
+    assert_eq!(index.search("chain", 10).len(), 1);
+    assert!(index.search("saved", 10).is_empty());
+    std::fs::remove_dir_all(&root)
+        .map_err(|e| IoError::new(root.clone(), e, line!(), file!().into()))?;
+    Ok(())
+}
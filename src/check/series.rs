@@ -0,0 +1,515 @@
+use super::fixtures::{bea_url, nipa_record, write_file, NIPA_RESPONSE};
+use crate::{
+    Aggregation, BeaClient, BeaErr, BeaErrorKind, DataStream, Deflator, ExportFormat, Frequency,
+    Hierarchy, IndexKind, IoError, Measure, Measurement, NipaData, NipaDatum, Period, SerdeJson,
+    Series, SeriesCollection, TableLayout, TableView, Unit, ADDITIVITY_TOLERANCE,
+};
+
+/// Streams the records of a NIPA response, rejecting malformed input and other datasets.
+#[tracing::instrument]
+pub fn data_stream() -> Result<(), BeaErr> {
+    let rdr = std::io::Cursor::new(NIPA_RESPONSE.as_bytes().to_vec());
+    let stream = DataStream::from_reader(rdr, NipaDatum::read_json);
+    let data = stream.collect::<Result<Vec<NipaDatum>, BeaErr>>()?;
+    assert_eq!(data.len(), 2);
+    let rdr = std::io::Cursor::new(br#"{"BEAAPI":{"Results":{"Error":{}}}}"#.to_vec());
+    let mut stream = DataStream::from_reader(rdr, NipaDatum::read_json);
+    assert!(stream.next().is_some_and(|result| result.is_err()));
+    // A response from another dataset fails before any record is read.
+    let response = NIPA_RESPONSE.replace(
+        r#""RequestParam":[]"#,
+        r#""RequestParam":[{"ParameterName":"DATASETNAME","ParameterValue":"FixedAssets"}]"#,
+    );
+    let rdr = std::io::Cursor::new(response.clone().into_bytes());
+    let datasets = NipaDatum::DATASETS;
+    let mut stream = DataStream::from_reader_for(rdr, &datasets, NipaDatum::read_json);
+    let error = stream.next().expect("error reported").unwrap_err();
+    assert!(matches!(&**error, BeaErrorKind::DatasetMissing(_)));
+    assert!(stream.next().is_none());
+    let path = std::env::temp_dir().join(format!("bears_stream_{}.json", uuid::Uuid::new_v4()));
+    std::fs::write(&path, &response)
+        .map_err(|e| IoError::new(path.clone(), e, line!(), file!().into()))?;
+    assert!(NipaData::try_from(&path).is_err());
+    std::fs::remove_file(&path)
+        .map_err(|e| IoError::new(path.clone(), e, line!(), file!().into()))?;
+    // A malformed response reports the parse error, and dropping a stream early stops the parser.
+    let truncated = &NIPA_RESPONSE[..NIPA_RESPONSE.len() / 2];
+    let rdr = std::io::Cursor::new(truncated.as_bytes().to_vec());
+    let stream = DataStream::from_reader(rdr, NipaDatum::read_json);
+    let results = stream.collect::<Vec<_>>();
+    assert!(results.last().is_some_and(|result| result.is_err()));
+    let rdr = std::io::Cursor::new(NIPA_RESPONSE.as_bytes().to_vec());
+    let mut stream = DataStream::from_reader(rdr, NipaDatum::read_json);
+    assert!(stream.next().is_some_and(|result| result.is_ok()));
+    drop(stream);
+    Ok(())
+}
+
+/// Scales reported values by their unit multiplier and refuses to add values of different units.
+#[tracing::instrument]
+pub fn measure_units() -> Result<(), BeaErr> {
+    let rdr = std::io::Cursor::new(NIPA_RESPONSE.as_bytes().to_vec());
+    let stream = DataStream::from_reader(rdr, NipaDatum::read_json);
+    let data = stream.collect::<Result<Vec<NipaDatum>, BeaErr>>()?;
+    let first = data[0].measure().expect("value reported");
+    assert_eq!(first.unit(), &Unit::CurrentDollars);
+    assert_eq!(first.scaled(), 26_813_601_000_000.0);
+    let sum = first.checked_add(&data[1].measure().expect("value reported"))?;
+    assert_eq!(sum.value(), &53_876_613.0);
+    let index = Measure::new(100.0, Unit::Index(IndexKind::Price), 0);
+    assert!(first.checked_add(&index).is_err());
+    assert!(first.try_cmp(&index).is_err());
+    assert!(index.checked_add(&index).is_err());
+    Ok(())
+}
+
+/// Parses, orders and steps through time periods, up to the edges of the civil calendar.
+#[tracing::instrument]
+pub fn period() -> Result<(), BeaErr> {
+    let rdr = std::io::Cursor::new(NIPA_RESPONSE.as_bytes().to_vec());
+    let stream = DataStream::from_reader(rdr, NipaDatum::read_json);
+    let data = stream.collect::<Result<Vec<NipaDatum>, BeaErr>>()?;
+    assert_eq!(data[0].time_period(), &Period::Quarter(2023, 1));
+    assert_eq!(data[0].time_period().next(), Some(*data[1].time_period()));
+    let month = "2023M04".parse::<Period>()?;
+    let quarter = "2023Q2".parse::<Period>()?;
+    assert_eq!(month.start()?, quarter.start()?);
+    assert_ne!(month, quarter);
+    assert!(quarter < month);
+    assert!(quarter.contains(&month));
+    assert_eq!(Period::Month(2023, 1).prev(), Some(Period::Month(2022, 12)));
+    assert_eq!(
+        Period::range(Period::Year(2020), Period::Year(2023))?.count(),
+        4
+    );
+    assert!("2023Q5".parse::<Period>().is_err());
+    assert!("2023M13".parse::<Period>().is_err());
+    // Years outside the range of a civil date are rejected rather than panicking later.
+    assert!("20000".parse::<Period>().is_err());
+    assert!("-10000Q1".parse::<Period>().is_err());
+    assert!(Period::quarter(10_000, 1).is_err());
+    // Out of range quarters and months built directly fail to convert to dates.
+    assert!(Period::quarter(2023, 5).is_err());
+    assert!(Period::month(2023, 13).is_err());
+    assert!(Period::Quarter(2023, 5).start().is_err());
+    assert!(Period::Quarter(2023, 0).end().is_err());
+    assert!(Period::Month(2023, 13).start().is_err());
+    assert!(Period::Month(2023, 0).end().is_err());
+    assert!(Period::Year(20_000).start().is_err());
+    assert_eq!(
+        Period::Month(2024, 2).end()?,
+        jiff::civil::date(2024, 2, 29)
+    );
+    assert_eq!("9999M12".parse::<Period>()?.next(), None);
+    assert_eq!(Period::Year(-9999).prev(), None);
+    assert_eq!(Period::Year(2023).offset(i64::MAX), None);
+    assert_eq!(
+        Period::range(Period::Year(9998), Period::Year(9999))?.count(),
+        2
+    );
+    // Ordering and containment do not need a civil date.
+    assert!(Period::Year(20_000) > Period::Year(2023));
+    assert!(Period::Year(20_000).contains(&Period::Month(20_000, 6)));
+    Ok(())
+}
+
+/// Collects NIPA records into series keyed by series code and frequency.
+#[tracing::instrument]
+pub fn series_collection() -> Result<(), BeaErr> {
+    let rdr = std::io::Cursor::new(NIPA_RESPONSE.as_bytes().to_vec());
+    let stream = DataStream::from_reader(rdr, NipaDatum::read_json);
+    let data = NipaData::from(stream.collect::<Result<Vec<_>, BeaErr>>()?);
+    let collection = SeriesCollection::from(&data);
+    assert_eq!(collection.len(), 1);
+    let series = collection
+        .get("A191RC", Frequency::Quarterly)
+        .expect("series present");
+    assert_eq!(series.len(), 2);
+    assert_eq!(series.get(&Period::Quarter(2023, 2)), Some(27_063_012.0));
+    assert!(collection.get("A191RC", Frequency::Annual).is_none());
+    let line = collection.by_line("T10105", 1, Frequency::Quarterly);
+    assert_eq!(line, Some(series));
+    assert_eq!(collection.by_table("T10105").len(), 1);
+    let mut annual = Series::default();
+    annual.insert(Period::Year(2023), Some(1.0));
+    assert!(Series::align(&[series, &annual]).is_err());
+    Ok(())
+}
+
+/// Computes percent changes, growth rates and contributions over a series with gaps.
+#[tracing::instrument]
+pub fn series_transforms() -> Result<(), BeaErr> {
+    let close = |value: Option<f64>, target: f64| value.is_some_and(|v| (v - target).abs() < 1e-9);
+    let mut total = Series::default();
+    total.with_unit(Unit::CurrentDollars);
+    let mut part = total.clone();
+    for (i, period) in
+        Period::range(Period::Quarter(2022, 1), Period::Quarter(2023, 2))?.enumerate()
+    {
+        total.insert(period, Some(100.0 + i as f64 * 10.0));
+        part.insert(period, Some(50.0 + i as f64 * 5.0));
+    }
+    // Suppressed value and a gap.
+    total.insert(Period::Quarter(2022, 3), None);
+    part.insert(Period::Quarter(2022, 3), None);
+    let change = total.percent_change();
+    assert_eq!(change.unit(), &Unit::PercentChange);
+    assert_eq!(change.len(), 5);
+    assert!(close(change.get(&Period::Quarter(2022, 2)), 10.0));
+    assert_eq!(change.get(&Period::Quarter(2022, 3)), None);
+    assert_eq!(change.get(&Period::Quarter(2022, 4)), None);
+    let yoy = total.year_over_year();
+    assert_eq!(yoy.len(), 2);
+    assert!(close(yoy.get(&Period::Quarter(2023, 1)), 40.0));
+    let annualized = total.annualized_growth();
+    let rate = annualized
+        .get(&Period::Quarter(2022, 2))
+        .expect("rate available");
+    assert!((rate - 46.41).abs() < 1e-9);
+    let cagr = total
+        .cagr(&Period::Quarter(2022, 1), &Period::Quarter(2023, 1))?
+        .expect("rate available");
+    assert!((cagr - 40.0).abs() < 1e-9);
+    assert!(total
+        .cagr(&Period::Year(2022), &Period::Year(2023))
+        .is_err());
+    let contributions = part.contributions(&total)?;
+    assert_eq!(contributions.unit(), &Unit::PercentagePoints);
+    assert!(close(contributions.get(&Period::Quarter(2022, 2)), 5.0));
+    let mut chained = part.clone();
+    chained.with_unit(Unit::ChainedDollars);
+    assert!(chained.contributions(&total).is_err());
+    Ok(())
+}
+
+/// Aggregates quarterly series to annual values and reports frequencies that disagree.
+#[tracing::instrument]
+pub fn series_aggregate() -> Result<(), BeaErr> {
+    let records = [
+        nipa_record("2022Q1", "100"),
+        nipa_record("2022Q2", "110"),
+        nipa_record("2022Q3", "(NA)"),
+        nipa_record("2022Q4", "130"),
+        nipa_record("2023Q1", "100"),
+        nipa_record("2023Q2", "110"),
+        nipa_record("2023Q3", "120"),
+        nipa_record("2023Q4", "130"),
+        nipa_record("2024Q1", "140"),
+        nipa_record("2022", "115"),
+        // Mismatched annual value, as if from the wrong file.
+        nipa_record("2023", "460"),
+    ];
+    let json = format!(
+        r#"{{"BEAAPI":{{"Results":{{"Data":[{}]}}}}}}"#,
+        records.join(",")
+    );
+    let rdr = std::io::Cursor::new(json.into_bytes());
+    let stream = DataStream::from_reader(rdr, NipaDatum::read_json);
+    let data = NipaData::from(stream.collect::<Result<Vec<_>, BeaErr>>()?);
+    let collection = SeriesCollection::from(&data);
+    let quarterly = collection
+        .get("A191RC", Frequency::Quarterly)
+        .expect("series present");
+    let annual = quarterly.aggregate_by_measurement(Frequency::Annual)?;
+    // 2024 is incomplete and omitted, 2022 has a suppressed quarter.
+    assert_eq!(annual.len(), 2);
+    assert_eq!(annual.get(&Period::Year(2022)), None);
+    assert_eq!(annual.get(&Period::Year(2023)), Some(115.0));
+    let sum = quarterly.aggregate(Frequency::Annual, Aggregation::Sum)?;
+    assert_eq!(sum.get(&Period::Year(2023)), Some(460.0));
+    assert!(annual
+        .aggregate(Frequency::Quarterly, Aggregation::Sum)
+        .is_err());
+    let discrepancies = collection.check_frequencies(1e-3)?;
+    assert_eq!(discrepancies.len(), 1);
+    assert_eq!(discrepancies[0].period(), &Period::Year(2023));
+    assert!(quarterly
+        .percent_change()
+        .aggregate_by_measurement(Frequency::Annual)
+        .is_err());
+    Ok(())
+}
+
+/// Infers whether a series is a flow, stock, rate or annual rate, and aggregates it accordingly.
+#[tracing::instrument]
+pub fn series_measurement() -> Result<(), BeaErr> {
+    let record = |table: &str, line: &str, metric: &str, unit: &str, q: u8, value: u8| {
+        format!(
+            r#"{{"TableName":"{table}","SeriesCode":"{table}L","LineNumber":"1","LineDescription":"{line}","TimePeriod":"2023Q{q}","METRIC_NAME":"{metric}","CL_UNIT":"{unit}","UNIT_MULT":"0","DataValue":"{value}","NoteRef":"{table}"}}"#
+        )
+    };
+    let series = [
+        (
+            "T10105",
+            "Gross domestic product",
+            "Current Dollars",
+            "Level",
+        ),
+        ("T50803", "Private inventories", "Current Dollars", "Level"),
+        (
+            "T10104",
+            "Gross domestic product",
+            "Fisher Price Index",
+            "Fisher Price Index",
+        ),
+        (
+            "T60900",
+            "Hours worked by full-time employees",
+            "Hours",
+            "Level",
+        ),
+    ];
+    let mut records = Vec::new();
+    for (table, line, metric, unit) in series {
+        for q in 1..=4 {
+            records.push(record(table, line, metric, unit, q, q * 10));
+        }
+    }
+    let json = format!(
+        r#"{{"BEAAPI":{{"Results":{{"Data":[{}]}}}}}}"#,
+        records.join(",")
+    );
+    let rdr = std::io::Cursor::new(json.into_bytes());
+    let stream = DataStream::from_reader(rdr, NipaDatum::read_json);
+    let data = NipaData::from(stream.collect::<Result<Vec<_>, BeaErr>>()?);
+    let collection = SeriesCollection::from(&data);
+    let annual = |code: &str| -> Result<(Option<Measurement>, Option<f64>), BeaErr> {
+        let quarterly = collection
+            .get(code, Frequency::Quarterly)
+            .expect("series present");
+        let annual = quarterly.aggregate_by_measurement(Frequency::Annual)?;
+        Ok((*quarterly.measurement(), annual.get(&Period::Year(2023))))
+    };
+    // Dollars at seasonally adjusted annual rates average.
+    assert_eq!(
+        annual("T10105L")?,
+        (Some(Measurement::AnnualRate), Some(25.0))
+    );
+    // Inventories are a stock, valued at the end of the year.
+    assert_eq!(annual("T50803L")?, (Some(Measurement::Stock), Some(40.0)));
+    // Indexes average.
+    assert_eq!(annual("T10104L")?, (Some(Measurement::Rate), Some(25.0)));
+    // Hours are a flow, summed over the year.
+    assert_eq!(annual("T60900L")?, (Some(Measurement::Flow), Some(100.0)));
+    assert_eq!(
+        Aggregation::for_measurement(Measurement::Stock),
+        Aggregation::EndOfPeriod
+    );
+    // A change in inventories is a flow, not a stock.
+    let change = Measurement::infer(
+        &Unit::CurrentDollars,
+        &["Change in private inventories"],
+        Measurement::AnnualRate,
+    );
+    assert_eq!(change, Some(Measurement::AnnualRate));
+    Ok(())
+}
+
+/// Loads a price deflator from a saved download and deflates a nominal series with it.
+#[tracing::instrument]
+pub async fn deflate() -> Result<(), BeaErr> {
+    let record = |period: &str, value: &str| {
+        format!(
+            r#"{{"TableName":"T10109","SeriesCode":"A191RD","LineNumber":"1","LineDescription":"Gross domestic product","TimePeriod":"{period}","METRIC_NAME":"Implicit Price Deflator","CL_UNIT":"Implicit Price Deflator","UNIT_MULT":"0","DataValue":"{value}","NoteRef":"T10109"}}"#
+        )
+    };
+    let records = [
+        record("2017", "100.000"),
+        record("2022", "118.000"),
+        record("2023", "(NA)"),
+    ];
+    let json = format!(
+        r#"{{"BEAAPI":{{"Request":{{"RequestParam":[{{"ParameterName":"METHOD","ParameterValue":"GETDATA"}},{{"ParameterName":"DATASETNAME","ParameterValue":"NIPA"}}]}},"Results":{{"Data":[{}]}}}}}}"#,
+        records.join(",")
+    );
+    let root = std::env::temp_dir().join(format!("bears_deflate_{}", std::process::id()));
+    let url = bea_url()?;
+    let client = BeaClient::new("key".into(), url, root.clone())?;
+    let path = Deflator::Gdp.app(&client).destination(false)?;
+    assert_eq!(path, root.join("data/NIPA/NIPA_T10109.json"));
+    write_file(&path, json.as_bytes())?;
+    let prices = Deflator::Gdp.load_from(&client, Frequency::Annual).await?;
+    assert!(Deflator::Pce
+        .load_from(&client, Frequency::Annual)
+        .await
+        .is_err());
+    std::fs::remove_dir_all(&root)
+        .map_err(|e| IoError::new(root.clone(), e, line!(), file!().into()))?;
+    assert_eq!(
+        prices.unit(),
+        &Unit::Index(IndexKind::ImplicitPriceDeflator)
+    );
+
+    let mut nominal = Series::default();
+    nominal.with_unit(Unit::CurrentDollars);
+    nominal.insert(Period::Year(2022), Some(236.0));
+    nominal.insert(Period::Year(2023), Some(250.0));
+    let real = nominal.deflate(&prices, 2017)?;
+    assert_eq!(real.unit(), &Unit::ChainedDollars);
+    assert_eq!(real.get(&Period::Year(2022)), Some(200.0));
+    assert_eq!(real.get(&Period::Year(2023)), None);
+    assert!(nominal.deflate(&prices, 2010).is_err());
+    assert!(real.deflate(&prices, 2017).is_err());
+    assert!(nominal.deflate(&nominal, 2017).is_err());
+    Ok(())
+}
+
+/// Rebases an index series to a reference period or range of periods.
+#[tracing::instrument]
+pub fn rebase() -> Result<(), BeaErr> {
+    let close = |value: Option<f64>, target: f64| value.is_some_and(|v| (v - target).abs() < 1e-9);
+    let mut index = Series::default();
+    index.with_unit(Unit::Index(IndexKind::Price));
+    let values = [96.0, 98.0, 102.0, 104.0, 110.0];
+    let periods = Period::range(Period::Quarter(2022, 1), Period::Quarter(2023, 1))?;
+    for (period, value) in periods.zip(values) {
+        index.insert(period, Some(value));
+    }
+    let rebased = index.rebase(Period::Year(2022))?;
+    assert!(close(rebased.get(&Period::Quarter(2022, 1)), 96.0));
+    assert!(close(rebased.get(&Period::Quarter(2023, 1)), 110.0));
+    assert_eq!(
+        rebased.reference(),
+        &Some((Period::Year(2022), Period::Year(2022)))
+    );
+    let rebased = index.rebase_range(Period::Quarter(2022, 3), Period::Quarter(2022, 4))?;
+    assert!(close(
+        rebased.get(&Period::Quarter(2023, 1)),
+        110.0 / 103.0 * 100.0
+    ));
+    assert!(index.rebase(Period::Year(2019)).is_err());
+    let mut dollars = index.clone();
+    dollars.with_unit(Unit::CurrentDollars);
+    assert!(dollars.rebase(Period::Year(2022)).is_err());
+    index.insert(Period::Quarter(2022, 2), None);
+    let error = index.rebase(Period::Year(2022)).unwrap_err();
+    assert!(matches!(&**error, BeaErrorKind::BaseSuppressed(_)));
+    let error = index.rebase(Period::Year(2019)).unwrap_err();
+    assert!(matches!(&**error, BeaErrorKind::BTreeKeyMissing(_)));
+    Ok(())
+}
+
+/// Builds a table view from a series collection, checking its hierarchy and additivity.
+#[tracing::instrument]
+pub fn table_view() -> Result<(), BeaErr> {
+    let lines = [
+        (1, "Gross domestic product", 100.0),
+        (2, "Personal consumption expenditures", 70.0),
+        (3, "Goods", 30.0),
+        (4, "Services", 40.0),
+        (5, "Net exports of goods and services", -5.0),
+        (6, "Exports", 10.0),
+        (7, "Imports", 15.0),
+        (8, "Government consumption expenditures", 35.0),
+    ];
+    let record = |table: &str, line: i64, desc: &str, year: i16, value: f64| {
+        format!(
+            r#"{{"TableName":"{table}","SeriesCode":"S{line}","LineNumber":"{line}","LineDescription":"{desc}","TimePeriod":"{year}","METRIC_NAME":"Current Dollars","CL_UNIT":"Level","UNIT_MULT":"6","DataValue":"{value}","NoteRef":"{table}"}}"#
+        )
+    };
+    let mut records = Vec::new();
+    for (line, desc, value) in lines {
+        records.push(record("T10105", line, desc, 2022, value));
+        records.push(record("T10105", line, desc, 2023, value * 1.1));
+        // The second table does not add up in 2023.
+        let skew = if line == 3 { 1.0 } else { 1.1 };
+        records.push(record("T10106", line, desc, 2022, value));
+        records.push(record("T10106", line, desc, 2023, value * skew));
+    }
+    let json = format!(
+        r#"{{"BEAAPI":{{"Results":{{"Data":[{}]}}}}}}"#,
+        records.join(",")
+    );
+    let rdr = std::io::Cursor::new(json.into_bytes());
+    let stream = DataStream::from_reader(rdr, NipaDatum::read_json);
+    let data = NipaData::from(stream.collect::<Result<Vec<_>, BeaErr>>()?);
+    let collection = SeriesCollection::from(&data);
+    let view = TableView::from_collection(&collection, "T10105", Frequency::Annual)?;
+    let parents = view
+        .lines()
+        .iter()
+        .map(|l| (*l.parent(), *l.depth()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        parents,
+        vec![
+            (None, 0),
+            (Some(1), 1),
+            (Some(2), 2),
+            (Some(2), 2),
+            (Some(1), 1),
+            (Some(5), 2),
+            (Some(5), 2),
+            (Some(1), 1),
+        ]
+    );
+    assert_eq!(view.lines()[6].sign(), &-1);
+    assert_eq!(view.lines()[2].label(), "    Goods");
+    // A hierarchy inferred from the values adds up by construction, so it cannot be checked.
+    assert_eq!(view.hierarchy(), &Hierarchy::Inferred);
+    assert!(view.check_additivity(ADDITIVITY_TOLERANCE).is_err());
+    let pivot = view.pivot();
+    assert_eq!(pivot[0].1.len(), 2);
+    assert_eq!(TableView::tables(&collection).len(), 2);
+
+    // Inferred from its own values, the skewed table drops Goods and Services from the
+    // hierarchy rather than reporting the residual.
+    let mut skewed = TableView::from_collection(&collection, "T10106", Frequency::Annual)?;
+    assert_eq!(skewed.lines()[2].parent(), &None);
+    // The published layout restores the hierarchy and exposes the residual.
+    let layout = TableLayout::new(
+        "T10106",
+        vec![
+            (1, 0),
+            (2, 1),
+            (3, 2),
+            (4, 2),
+            (5, 1),
+            (6, 2),
+            (7, 2),
+            (8, 1),
+        ],
+    );
+    skewed.with_layout(&layout);
+    assert_eq!(skewed.hierarchy(), &Hierarchy::Layout);
+    assert_eq!(skewed.lines()[2].parent(), &Some(2));
+    assert_eq!(skewed.lines()[6].sign(), &-1);
+    let residuals = skewed.check_additivity(ADDITIVITY_TOLERANCE)?;
+    assert_eq!(residuals.len(), 1);
+    assert_eq!(residuals[0].line_number(), &2);
+    assert_eq!(residuals[0].period(), &Period::Year(2023));
+    // The same layout carries over to a table in chained dollars, or from another table.
+    let mut adopted = TableView::from_collection(&collection, "T10106", Frequency::Annual)?;
+    adopted.adopt_hierarchy(&view);
+    assert_eq!(adopted.check_additivity(ADDITIVITY_TOLERANCE)?, residuals);
+    let layout_path = std::env::temp_dir().join(format!("bears_layout_{}.csv", std::process::id()));
+    TableLayout::from_view(&view).to_csv(&layout_path)?;
+    let read = TableLayout::from_csv("T10105", &layout_path)?;
+    std::fs::remove_file(&layout_path)
+        .map_err(|e| IoError::new(layout_path.clone(), e, line!(), file!().into()))?;
+    assert_eq!(read.lines(), layout.lines());
+
+    let path = std::env::temp_dir().join(format!("bears_table_{}.csv", std::process::id()));
+    view.to_csv(&path)?;
+    let csv = std::fs::read_to_string(&path)
+        .map_err(|e| IoError::new(path.clone(), e, line!(), file!().into()))?;
+    std::fs::remove_file(&path)
+        .map_err(|e| IoError::new(path.clone(), e, line!(), file!().into()))?;
+    assert!(csv.starts_with("Line,T10105,2022,2023"));
+
+    // exports are named after the table and frequency, in the format chosen
+    let folder = std::env::temp_dir().join(format!("bears_export_{}", uuid::Uuid::new_v4()));
+    let path = view.export(ExportFormat::Csv, &folder)?;
+    assert_eq!(path, folder.join("T10105_A.csv"));
+    let path = view.export(ExportFormat::Json, &folder)?;
+    let contents =
+        std::fs::read(&path).map_err(|e| IoError::new(path.clone(), e, line!(), file!().into()))?;
+    let read: TableView = serde_json::from_slice(&contents)
+        .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+    assert_eq!(read.table_name(), view.table_name());
+    assert_eq!(read.periods(), view.periods());
+    assert_eq!(read.lines().len(), view.lines().len());
+    std::fs::remove_dir_all(&folder)
+        .map_err(|e| IoError::new(folder.clone(), e, line!(), file!().into()))?;
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use crate::{
+    App, Backend, BeaErr, FsStorage, Options, Overrides, RateLimits, Request, ReqwestError,
+    Settings, Tracker,
+};
+
+/// Time allowed to establish a connection to the BEA server.
+pub const CONNECT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Time allowed for a complete response, including the body.  Large GetData responses can take
+/// several minutes to arrive.
+pub const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// The `BeaClient` struct holds the state shared by every request to the BEA API: the user API
+/// key, the BEA url, a pooled HTTP client with timeouts and compression, the storage root and the
+/// rate limiter.
+///
+/// Create one client per program from the resolved [`Settings`], the same configuration the
+/// command line uses, or with [`BeaClient::new`], then hand
+/// out lightweight [`App`] request descriptions with [`BeaClient::request`].  Clones are cheap
+/// and share the same connection pool and rate limiter, so concurrent downloads stay within the
+/// BEA limits.
+///
+/// ```
+/// use bears::{BeaClient, Method, Request};
+///
+/// let url = url::Url::parse("https://apps.bea.gov/api/data").unwrap();
+/// let client = BeaClient::new("key".into(), url, "data".into()).unwrap();
+/// let app = client.request(Request::Dataset);
+/// assert_eq!(app.options().method(), &Some(Method::GetDataSetList));
+/// ```
+#[derive(Debug, Clone)]
+pub struct BeaClient {
+    key: String,
+    url: url::Url,
+    http: reqwest::Client,
+    root: std::path::PathBuf,
+    tracker: std::sync::Arc<tokio::sync::Mutex<Tracker>>,
+    storage: Backend,
+}
+
+/// Builds an HTTP client with the timeouts and compression used for calls to the BEA server at
+/// `url`.
+fn http_client(url: &url::Url) -> Result<reqwest::Client, BeaErr> {
+    let http = reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(REQUEST_TIMEOUT)
+        .gzip(true)
+        .build()
+        .map_err(|source| {
+            ReqwestError::new(
+                url.to_string(),
+                "build".to_string(),
+                source,
+                line!(),
+                file!().to_string(),
+            )
+        })?;
+    Ok(http)
+}
+
+/// The HTTP client shared by requests sent without a [`BeaClient`], such as [`App::get`], so
+/// that they reuse one connection pool rather than building a client per call.
+pub(crate) fn shared_http(url: &url::Url) -> &'static reqwest::Client {
+    static HTTP: std::sync::OnceLock<reqwest::Client> = std::sync::OnceLock::new();
+    HTTP.get_or_init(|| {
+        http_client(url).unwrap_or_else(|source| {
+            tracing::warn!("Using the default HTTP client: {source}");
+            reqwest::Client::new()
+        })
+    })
+}
+
+impl BeaClient {
+    /// Creates a client using the API key `key`, the BEA url `url` and the storage root `root`.
+    /// Fails if the HTTP client cannot be built.
+    pub fn new(key: String, url: url::Url, root: std::path::PathBuf) -> Result<Self, BeaErr> {
+        let http = http_client(&url)?;
+        let tracker = std::sync::Arc::new(tokio::sync::Mutex::new(Tracker::default()));
+        let storage = Backend::from(FsStorage::new(root.clone()));
+        Ok(Self {
+            key,
+            url,
+            http,
+            root,
+            tracker,
+            storage,
+        })
+    }
+
+    /// Replaces the rate limiter with one enforcing `limits`.  Call before cloning the client, as
+    /// clones made earlier keep the previous limiter.
+    pub fn with_limits(mut self, limits: RateLimits) -> Self {
+        self.tracker = std::sync::Arc::new(tokio::sync::Mutex::new(Tracker::with_limits(limits)));
+        self
+    }
+
+    /// Saves downloads to `storage` rather than the files under the storage root.  Histories and
+    /// parameter value caches stay under the storage root.
+    pub fn with_storage<S: Into<Backend>>(mut self, storage: S) -> Self {
+        self.storage = storage.into();
+        self
+    }
+
+    /// Creates a client from the default [`Settings`]: the `API_KEY`, `BEA_URL` and `BEA_DATA`
+    /// environmental variables, reading the .env file if present, then the configuration profile.
+    /// Equivalent to `Settings::resolve(&Overrides::default())?.client()`.
+    pub fn from_env() -> Result<Self, BeaErr> {
+        Settings::resolve(&Overrides::default())?.client()
+    }
+
+    /// Creates an [`App`] describing a call to the method of `request`, with the key, url and
+    /// storage root of the client.  Add the dataset and parameters to the returned `App` before sending it.
+    pub fn request(&self, request: Request) -> App {
+        let mut options = Options::default();
+        options.with_method(request.method());
+        App::new(
+            self.key.clone(),
+            options,
+            self.url.clone(),
+            self.root.clone(),
+        )
+    }
+
+    /// Sends the request `app` using the connection pool of the client.
+    pub async fn get(&self, app: &App) -> Result<reqwest::Response, ReqwestError> {
+        app.get_with(&self.http).await
+    }
+
+    /// The user API key.
+    pub fn key(&self) -> &String {
+        &self.key
+    }
+
+    /// The url of the BEA REST server.
+    pub fn url(&self) -> &url::Url {
+        &self.url
+    }
+
+    /// The pooled HTTP client.  Clones share the pool.
+    pub fn http(&self) -> &reqwest::Client {
+        &self.http
+    }
+
+    /// The storage root, under which downloads are saved.
+    pub fn root(&self) -> &std::path::PathBuf {
+        &self.root
+    }
+
+    /// The rate limiter, tracking calls, errors and download size for every request sent
+    /// through the client.
+    pub fn tracker(&self) -> &std::sync::Arc<tokio::sync::Mutex<Tracker>> {
+        &self.tracker
+    }
+
+    /// The storage backend receiving downloads, the files under the storage root by default.
+    pub fn storage(&self) -> &Backend {
+        &self.storage
+    }
+}
@@ -7,6 +7,7 @@ use nom::IResult;
 pub enum Action {
     Checklist,
     Download,
+    Search,
     #[default]
     Unknown,
 }
@@ -17,6 +18,7 @@ impl std::str::FromStr for Action {
         let action = match s {
             "checklist" => Self::Checklist,
             "download" => Self::Download,
+            "search" => Self::Search,
             _ => Self::Unknown,
         };
         if action == Self::Unknown {
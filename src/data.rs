@@ -1,7 +1,7 @@
 use crate::{
-    date_by_period, map_to_float, map_to_int, map_to_string, parse_year, AnnotatedInteger, BeaErr,
-    BeaResponse, DatasetMissing, IoError, JsonParseError, JsonParseErrorKind, KeyMissing, Naics,
-    NotArray, NotObject, RowCode, SerdeJson, VariantMissing,
+    map_to_float, map_to_int, map_to_optional_float, map_to_string, AnnotatedInteger, BeaErr,
+    DataStream, Dataset, JsonParseError, JsonParseErrorKind, KeyMissing, Measure, Naics, NotArray,
+    NotObject, Period, RowCode, Unit,
 };
 #[derive(
     Clone, Debug, PartialEq, PartialOrd, serde::Deserialize, serde::Serialize, derive_more::From,
@@ -16,26 +16,54 @@ pub enum Data {
     MneDi(MneDiData),
 }
 
-#[derive(Clone, Debug, Default, PartialEq, PartialOrd, serde::Deserialize, serde::Serialize)]
+/// The `Datum` enum holds a single record from the `Data` array of a GetData response.  Variants
+/// mirror those of [`Data`], and serve as the item type when streaming a response of unknown
+/// dataset, such as from [`App::stream`](crate::App::stream).
+#[derive(
+    Clone, Debug, PartialEq, PartialOrd, serde::Deserialize, serde::Serialize, derive_more::From,
+)]
+pub enum Datum {
+    #[from(NipaDatum)]
+    Nipa(NipaDatum),
+    #[from(FixedAssetDatum)]
+    FixedAssets(FixedAssetDatum),
+    #[from(MneDiDatum)]
+    MneDi(MneDiDatum),
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    PartialOrd,
+    serde::Deserialize,
+    serde::Serialize,
+    derive_getters::Getters,
+)]
 pub struct NipaDatum {
     cl_unit: String,
-    data_value: f64,
+    /// Reported value, or `None` if the value is suppressed.
+    data_value: Option<f64>,
     line_description: String,
     line_number: i64,
     metric_name: String,
     note_ref: String,
     series_code: String,
     table_name: String,
-    time_period: jiff::civil::Date,
+    time_period: Period,
     unit_mult: Option<i64>,
 }
 
 impl NipaDatum {
+    /// Datasets publishing NIPA records.
+    pub const DATASETS: [Dataset; 2] = [Dataset::Nipa, Dataset::NIUnderlyingDetail];
+
     pub fn read_json(m: &serde_json::Map<String, serde_json::Value>) -> Result<Self, BeaErr> {
         let cl_unit = map_to_string("CL_UNIT", m)?;
         tracing::trace!("cl_unit is {cl_unit}.");
-        let data_value = map_to_float("DataValue", m)?;
-        tracing::trace!("data_value is {data_value}.");
+        let data_value = map_to_optional_float("DataValue", m)?;
+        tracing::trace!("data_value is {data_value:?}.");
         let line_description = map_to_string("LineDescription", m)?;
         tracing::trace!("line_description is {line_description}.");
         let line_number = map_to_int("LineNumber", m)?;
@@ -49,7 +77,7 @@ impl NipaDatum {
         let table_name = map_to_string("TableName", m)?;
         tracing::trace!("table_name is {table_name}.");
         let time_period = map_to_string("TimePeriod", m)?;
-        let time_period = date_by_period(&time_period)?;
+        let time_period = time_period.parse::<Period>()?;
         tracing::trace!("time_period is {time_period}.");
         let unit_mult = map_to_int("UNIT_MULT", m)?;
         let unit_mult = match unit_mult {
@@ -70,6 +98,26 @@ impl NipaDatum {
             unit_mult,
         })
     }
+
+    /// Parses the `CL_UNIT` and `METRIC_NAME` fields into a typed [`Unit`].
+    pub fn unit(&self) -> Unit {
+        Unit::from_parts(&self.cl_unit, &self.metric_name)
+    }
+
+    /// Returns the data value as a [`Measure`], carrying the unit and the `UNIT_MULT` multiplier.
+    /// Returns `None` if the value is suppressed.
+    pub fn measure(&self) -> Option<Measure> {
+        self.data_value
+            .map(|value| Measure::new(value, self.unit(), self.unit_mult.unwrap_or_default()))
+    }
+
+    /// Streams records from the GetData response stored at `path`, reading one record at a time
+    /// rather than loading the whole file into memory.
+    /// Fails with [`DatasetMissing`](crate::DatasetMissing) if the response is not from the
+    /// `NIPA` or `NIUnderlyingDetail` datasets.
+    pub fn stream<P: AsRef<std::path::Path>>(path: P) -> Result<DataStream<Self>, BeaErr> {
+        DataStream::from_path_for(path, &Self::DATASETS, Self::read_json)
+    }
 }
 
 impl TryFrom<serde_json::Value> for NipaDatum {
@@ -110,42 +158,11 @@ pub struct NipaData(Vec<NipaDatum>);
 impl TryFrom<&std::path::PathBuf> for NipaData {
     type Error = BeaErr;
 
+    /// Reads the GetData response at `value` record by record using [`NipaDatum::stream`].
     fn try_from(value: &std::path::PathBuf) -> Result<Self, Self::Error> {
-        let file = std::fs::File::open(value)
-            .map_err(|e| IoError::new(value.into(), e, line!(), file!().into()))?;
-        let rdr = std::io::BufReader::new(file);
-        let res: serde_json::Value = serde_json::from_reader(rdr)
-            .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
-        let data = BeaResponse::try_from(&res)?;
-        tracing::info!("Response read.");
-        tracing::trace!("Response: {data:#?}");
-        let results = data.results();
-        if let Some(data) = results.into_data() {
-            match data {
-                Data::Nipa(nipa) => {
-                    tracing::info!("{} Nipa records read.", nipa.len());
-                    Ok(nipa)
-                }
-                _ => {
-                    tracing::warn!("Not Nipa variant.");
-                    let error = DatasetMissing::new(
-                        "Nipa variant needed".to_string(),
-                        line!(),
-                        file!().to_string(),
-                    );
-                    Err(error.into())
-                }
-            }
-        } else {
-            tracing::warn!("Data variant missing.");
-            let error = VariantMissing::new(
-                "Data variant missing".to_string(),
-                "Results".to_string(),
-                line!(),
-                file!().to_string(),
-            );
-            Err(error.into())
-        }
+        let data = NipaDatum::stream(value)?.collect::<Result<Vec<NipaDatum>, BeaErr>>()?;
+        tracing::info!("{} Nipa records read.", data.len());
+        Ok(Self(data))
     }
 }
 
@@ -205,30 +222,43 @@ impl TryFrom<&serde_json::Value> for NipaData {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq, PartialOrd, serde::Deserialize, serde::Serialize)]
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    PartialOrd,
+    serde::Deserialize,
+    serde::Serialize,
+    derive_getters::Getters,
+)]
 pub struct FixedAssetDatum {
     cl_unit: String,
-    data_value: f64,
+    /// Reported value, or `None` if the value is suppressed.
+    data_value: Option<f64>,
     line_description: String,
     line_number: i64,
     metric_name: String,
     series_code: String,
     table_name: String,
-    time_period: jiff::civil::Date,
+    time_period: Period,
     unit_mult: Option<i64>,
 }
 
 impl FixedAssetDatum {
+    /// Datasets publishing fixed asset records.
+    pub const DATASETS: [Dataset; 1] = [Dataset::FixedAssets];
+
     pub fn read_json(m: &serde_json::Map<String, serde_json::Value>) -> Result<Self, BeaErr> {
         let cl_unit = map_to_string("CL_UNIT", m)?;
-        let data_value = map_to_float("DataValue", m)?;
+        let data_value = map_to_optional_float("DataValue", m)?;
         let line_description = map_to_string("LineDescription", m)?;
         let line_number = map_to_int("LineNumber", m)?;
         let metric_name = map_to_string("METRIC_NAME", m)?;
         let series_code = map_to_string("SeriesCode", m)?;
         let table_name = map_to_string("TableName", m)?;
         let time_period = map_to_string("TimePeriod", m)?;
-        let time_period = date_by_period(&time_period)?;
+        let time_period = time_period.parse::<Period>()?;
         let unit_mult = map_to_int("UNIT_MULT", m)?;
         let unit_mult = match unit_mult {
             0 => None,
@@ -246,6 +276,26 @@ impl FixedAssetDatum {
             unit_mult,
         })
     }
+
+    /// Parses the `CL_UNIT` and `METRIC_NAME` fields into a typed [`Unit`].
+    pub fn unit(&self) -> Unit {
+        Unit::from_parts(&self.cl_unit, &self.metric_name)
+    }
+
+    /// Returns the data value as a [`Measure`], carrying the unit and the `UNIT_MULT` multiplier.
+    /// Returns `None` if the value is suppressed.
+    pub fn measure(&self) -> Option<Measure> {
+        self.data_value
+            .map(|value| Measure::new(value, self.unit(), self.unit_mult.unwrap_or_default()))
+    }
+
+    /// Streams records from the GetData response stored at `path`, reading one record at a time
+    /// rather than loading the whole file into memory.
+    /// Fails with [`DatasetMissing`](crate::DatasetMissing) if the response is not from the
+    /// `FixedAssets` dataset.
+    pub fn stream<P: AsRef<std::path::Path>>(path: P) -> Result<DataStream<Self>, BeaErr> {
+        DataStream::from_path_for(path, &Self::DATASETS, Self::read_json)
+    }
 }
 
 impl TryFrom<serde_json::Value> for FixedAssetDatum {
@@ -286,41 +336,12 @@ pub struct FixedAssetData(Vec<FixedAssetDatum>);
 impl TryFrom<&std::path::PathBuf> for FixedAssetData {
     type Error = BeaErr;
 
+    /// Reads the GetData response at `value` record by record using [`FixedAssetDatum::stream`].
     fn try_from(value: &std::path::PathBuf) -> Result<Self, Self::Error> {
-        let file = std::fs::File::open(value)
-            .map_err(|e| IoError::new(value.into(), e, line!(), file!().into()))?;
-        let rdr = std::io::BufReader::new(file);
-        let res: serde_json::Value = serde_json::from_reader(rdr)
-            .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
-        let data = BeaResponse::try_from(&res)?;
-        tracing::info!("Response read.");
-        tracing::trace!("Response: {data:#?}");
-        let results = data.results();
-        if let Some(data) = results.into_data() {
-            match data {
-                Data::FixedAssets(value) => {
-                    tracing::info!("{} FixedAsset records read.", value.len());
-                    Ok(value)
-                }
-                _ => {
-                    let error = DatasetMissing::new(
-                        "FixedAssets".to_string(),
-                        line!(),
-                        file!().to_string(),
-                    );
-                    Err(error.into())
-                }
-            }
-        } else {
-            tracing::warn!("Data variant missing.");
-            let error = VariantMissing::new(
-                "Data variant missing".to_string(),
-                "Results".to_string(),
-                line!(),
-                file!().to_string(),
-            );
-            Err(error.into())
-        }
+        let data =
+            FixedAssetDatum::stream(value)?.collect::<Result<Vec<FixedAssetDatum>, BeaErr>>()?;
+        tracing::info!("{} FixedAsset records read.", data.len());
+        Ok(Self(data))
     }
 }
 
@@ -380,7 +401,16 @@ impl TryFrom<&serde_json::Value> for FixedAssetData {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq, PartialOrd, serde::Deserialize, serde::Serialize)]
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    PartialOrd,
+    serde::Deserialize,
+    serde::Serialize,
+    derive_getters::Getters,
+)]
 pub struct MneDiDatum {
     column: String,
     column_code: i64,
@@ -397,10 +427,13 @@ pub struct MneDiDatum {
     table_column_display_order: f64,
     table_row_display_order: f64,
     table_scale: String,
-    year: jiff::civil::Date,
+    year: Period,
 }
 
 impl MneDiDatum {
+    /// Datasets publishing MNE direct investment records.
+    pub const DATASETS: [Dataset; 1] = [Dataset::Mne];
+
     pub fn read_json(
         m: &serde_json::Map<String, serde_json::Value>,
         naics: &Naics,
@@ -434,7 +467,7 @@ impl MneDiDatum {
         let table_row_display_order = map_to_float("TableRowDisplayOrder", m)?;
         let table_scale = map_to_string("TableScale", m)?;
         let year = map_to_string("Year", m)?;
-        let year = parse_year(&year)?;
+        let year = year.parse::<Period>()?;
         Ok(Self {
             column,
             column_code,
@@ -454,6 +487,16 @@ impl MneDiDatum {
             year,
         })
     }
+
+    /// Streams records from the GetData response stored at `path`, reading one record at a time
+    /// rather than loading the whole file into memory.  The `naics` codes fill in row codes
+    /// missing from the response.
+    pub fn stream<P: AsRef<std::path::Path>>(
+        path: P,
+        naics: Naics,
+    ) -> Result<DataStream<Self>, BeaErr> {
+        DataStream::from_path_for(path, &Self::DATASETS, move |m| Self::read_json(m, &naics))
+    }
 }
 
 #[derive(
@@ -474,38 +517,14 @@ pub struct MneDiData(Vec<MneDiDatum>);
 impl TryFrom<&std::path::PathBuf> for MneDiData {
     type Error = BeaErr;
 
+    /// Reads the GetData response at `value` record by record using [`MneDiDatum::stream`].
     fn try_from(value: &std::path::PathBuf) -> Result<Self, Self::Error> {
-        let file = std::fs::File::open(value)
-            .map_err(|e| IoError::new(value.into(), e, line!(), file!().into()))?;
-        let rdr = std::io::BufReader::new(file);
-        let res: serde_json::Value = serde_json::from_reader(rdr)
-            .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
-        let data = BeaResponse::try_from(&res)?;
-        tracing::info!("Response read.");
-        tracing::trace!("Response: {data:#?}");
-        let results = data.results();
-        if let Some(data) = results.into_data() {
-            match data {
-                Data::MneDi(value) => {
-                    tracing::info!("{} MneDi records read.", value.len());
-                    Ok(value)
-                }
-                _ => {
-                    let error =
-                        DatasetMissing::new("MneDi".to_string(), line!(), file!().to_string());
-                    Err(error.into())
-                }
-            }
-        } else {
-            tracing::warn!("Data variant missing.");
-            let error = VariantMissing::new(
-                "Data variant missing".to_string(),
-                "Results".to_string(),
-                line!(),
-                file!().to_string(),
-            );
-            Err(error.into())
-        }
+        // use naics code to determine missing row codes from the row title
+        let naics = Naics::from_csv("data/naics_codes.csv")?;
+        let data =
+            MneDiDatum::stream(value, naics)?.collect::<Result<Vec<MneDiDatum>, BeaErr>>()?;
+        tracing::info!("{} MneDi records read.", data.len());
+        Ok(Self(data))
     }
 }
 
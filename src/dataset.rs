@@ -1,8 +1,9 @@
 use crate::{
-    bea_data, map_to_string, ApiMetadata, App, BeaErr, BeaResponse, DatasetMissing, FixedAssets,
-    GdpByIndustry, Iip, InputOutput, IntlServSta, IntlServTrade, IoError, Ita, JsonParseError,
-    JsonParseErrorKind, KeyMissing, Mne, NiUnderlyingDetail, Nipa, NotObject, ParameterName, Queue,
-    Regional, Request, ReqwestError, Results, SerdeJson, UnderlyingGdpByIndustry, VariantMissing,
+    map_to_string, ApiMetadata, App, BeaClient, BeaErr, BeaResponse, Catalog, DatasetMissing,
+    FixedAssets, FsStorage, GdpByIndustry, Iip, InputOutput, IntlServSta, IntlServTrade, Ita,
+    JsonParseError, JsonParseErrorKind, KeyMissing, Mne, NiUnderlyingDetail, Nipa, NotObject,
+    Overrides, ParameterName, Queue, Regional, Request, ReqwestError, Results, SerdeJson, Settings,
+    Storage, StorageKey, UnderlyingGdpByIndustry, Validate, VariantMissing,
 };
 use convert_case::Casing;
 use serde::{Deserialize, Serialize};
@@ -66,9 +67,8 @@ impl Dataset {
     ///
     /// We match values for each variant manually against the responses from the
     /// [`Method::GetParameterList`](crate::Method::GetParameterList) call for each [`Dataset`]
-    /// variant.
-    ///
-    /// TODO: Match the output against the responses in a unit test to detect changes or additions.
+    /// variant.  [`Rules::check_names`](crate::Rules::check_names) compares the output against
+    /// the saved responses to detect changes or additions.
     pub fn names(&self) -> Vec<ParameterName> {
         match self {
             Self::Nipa => {
@@ -178,16 +178,25 @@ impl Dataset {
     pub async fn get() -> Result<(), BeaErr> {
         let req = Request::Dataset;
         let app = req.init()?;
+        Self::get_app(&app).await
+    }
+
+    /// Download the BEA dataset parameter values into the storage root of `client`.
+    pub async fn get_with(client: &BeaClient) -> Result<(), BeaErr> {
+        let app = client.request(Request::Dataset);
+        Self::get_app(&app).await
+    }
+
+    /// Sends the request `app` for the dataset list, saving the response under the storage root
+    /// of `app`.
+    async fn get_app(app: &App) -> Result<(), BeaErr> {
         let data = app.get().await?;
         match data.json::<serde_json::Value>().await {
             Ok(json) => {
                 let contents = serde_json::to_vec(&json)
                     .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
-                dotenvy::dotenv().ok();
-                let path = bea_data()?;
-                let path = path.join("datasets.json");
-                std::fs::write(&path, contents)
-                    .map_err(|e| IoError::new(path, e, line!(), file!().into()).into())
+                let key = StorageKey::new("datasets.json");
+                app.root_storage().put(&key, contents).await
             }
             Err(source) => {
                 let url = app.url().to_string();
@@ -203,15 +212,15 @@ impl Dataset {
     /// used to test internal parsing of responses
     #[tracing::instrument]
     pub fn load() -> Result<BeaResponse, BeaErr> {
-        dotenvy::dotenv().ok();
-        let path = bea_data()?;
-        let path = path.join("datasets.json");
-        let file = std::fs::File::open(&path)
-            .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
-        tracing::info!("File opened.");
-        let rdr = std::io::BufReader::new(file);
-        let json: serde_json::Value = serde_json::from_reader(rdr)
-            .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+        let path = Settings::resolve(&Overrides::default())?.data().clone();
+        Self::load_from(&path)
+    }
+
+    /// Reads the dataset list saved under the storage root `root`.
+    #[tracing::instrument]
+    pub fn load_from(root: &std::path::Path) -> Result<BeaResponse, BeaErr> {
+        let storage = FsStorage::new(root.into());
+        let json = storage.read_json(&StorageKey::new("datasets.json"))?;
         let bea = BeaResponse::try_from(&json)?;
         tracing::info!("Response: {bea:#?}");
         Ok(bea)
@@ -219,7 +228,7 @@ impl Dataset {
 
     /// Reads response to json using serde_json.
     /// Prints the output to the terminal.
-    /// Saves the result to the `BEA_DATA` directory.
+    /// Saves the result to the storage root of `app`.
     #[tracing::instrument(skip_all)]
     pub async fn parameter(&self, app: &mut App) -> Result<(), BeaErr> {
         app.with_dataset(*self);
@@ -228,18 +237,8 @@ impl Dataset {
             Ok(json) => {
                 let contents = serde_json::to_vec(&json)
                     .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
-                dotenvy::dotenv().ok();
-                let path = bea_data()?;
-                let path = path.join("parameters");
-                if !path.exists() {
-                    std::fs::DirBuilder::new()
-                        .create(&path)
-                        .map_err(|e| IoError::new(path.clone(), e, line!(), file!().into()))?;
-                    tracing::info!("Target directory for Parameters created.");
-                }
-                let path = path.join(format!("{self}_parameters.json"));
-                std::fs::write(&path, contents)
-                    .map_err(|e| IoError::new(path, e, line!(), file!().into()).into())
+                let key = StorageKey::new(format!("parameters/{self}_parameters.json"));
+                app.root_storage().put(&key, contents).await
             }
             Err(source) => {
                 let url = app.url().to_string();
@@ -258,7 +257,18 @@ impl Dataset {
     #[tracing::instrument]
     pub async fn parameters() -> Result<(), BeaErr> {
         let req = Request::Parameter;
-        let mut app = req.init()?;
+        let app = req.init()?;
+        Self::parameters_app(app).await
+    }
+
+    /// For each variant of [`Dataset`], request the parameters, saving the results under the
+    /// storage root of `client`.
+    pub async fn parameters_with(client: &BeaClient) -> Result<(), BeaErr> {
+        let app = client.request(Request::Parameter);
+        Self::parameters_app(app).await
+    }
+
+    async fn parameters_app(mut app: App) -> Result<(), BeaErr> {
         let datasets: Vec<Self> = Self::iter().collect();
         for dataset in datasets {
             dataset.parameter(&mut app).await?;
@@ -277,18 +287,10 @@ impl Dataset {
             Ok(json) => {
                 let contents = serde_json::to_vec(&json)
                     .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
-                dotenvy::dotenv().ok();
-                let path = bea_data()?;
-                let path = path.join("parameter_values");
-                if !path.exists() {
-                    std::fs::DirBuilder::new()
-                        .create(&path)
-                        .map_err(|e| IoError::new(path.clone(), e, line!(), file!().into()))?;
-                    tracing::info!("Target directory for Parameter Values created.");
-                }
-                let path = path.join(format!("{self}_{name}_parameter_values.json"));
-                std::fs::write(&path, contents)
-                    .map_err(|e| IoError::new(path, e, line!(), file!().into()).into())
+                let key = StorageKey::new(format!(
+                    "parameter_values/{self}_{name}_parameter_values.json"
+                ));
+                app.root_storage().put(&key, contents).await
             }
             Err(source) => {
                 let url = app.url().to_string();
@@ -317,7 +319,18 @@ impl Dataset {
     #[tracing::instrument]
     pub async fn parameter_values() -> Result<(), BeaErr> {
         let req = Request::ParameterValue;
-        let mut app = req.init()?;
+        let app = req.init()?;
+        Self::parameter_values_app(app).await
+    }
+
+    /// For each variant of [`Dataset`], request the values of each parameter, saving the results
+    /// under the storage root of `client`.
+    pub async fn parameter_values_with(client: &BeaClient) -> Result<(), BeaErr> {
+        let app = client.request(Request::ParameterValue);
+        Self::parameter_values_app(app).await
+    }
+
+    async fn parameter_values_app(mut app: App) -> Result<(), BeaErr> {
         let datasets: Vec<Self> = Self::iter().collect();
         for dataset in datasets {
             let names = dataset.names();
@@ -347,18 +360,9 @@ impl Dataset {
                     Results::ParameterValues(_) => {
                         let contents = serde_json::to_vec(&json)
                             .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
-                        dotenvy::dotenv().ok();
-                        let path = bea_data()?;
-                        let path = path.join("parameter_values");
-                        if !path.exists() {
-                            std::fs::DirBuilder::new().create(&path).map_err(|e| {
-                                IoError::new(path.clone(), e, line!(), file!().into())
-                            })?;
-                            tracing::info!("Target directory for Parameter Values created.");
-                        }
-                        let path = path.join(format!("{self}_{name}_values.json"));
-                        std::fs::write(&path, contents)
-                            .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
+                        let key =
+                            StorageKey::new(format!("parameter_values/{self}_{name}_values.json"));
+                        app.root_storage().put(&key, contents).await?;
                     }
                     unexpected => {
                         tracing::warn!("Unexpected type {unexpected:#?}");
@@ -386,7 +390,18 @@ impl Dataset {
     #[tracing::instrument]
     pub async fn values() -> Result<(), BeaErr> {
         let req = Request::ParameterValueFilter;
-        let mut app = req.init()?;
+        let app = req.init()?;
+        Self::values_app(app).await
+    }
+
+    /// For each variant of [`Dataset`], request the valid range of values for each parameter
+    /// name, saving the results under the storage root of `client`.
+    pub async fn values_with(client: &BeaClient) -> Result<(), BeaErr> {
+        let app = client.request(Request::ParameterValueFilter);
+        Self::values_app(app).await
+    }
+
+    async fn values_app(mut app: App) -> Result<(), BeaErr> {
         let datasets: Vec<Dataset> = Dataset::iter().collect();
         for dataset in &datasets {
             let names = dataset.names();
@@ -423,8 +438,8 @@ impl Dataset {
     }
 
     pub async fn value_gdp(self, app: &mut App, name: ParameterName) -> Result<(), BeaErr> {
-        dotenvy::dotenv().ok();
-        let bea_data = bea_data()?;
+        let bea_data = app.storage_root().clone();
+        let storage = app.root_storage();
         let table_id = match self {
             Self::GDPbyIndustry => GdpByIndustry::read_table_id(&bea_data)?,
             Self::UnderlyingGDPbyIndustry => UnderlyingGdpByIndustry::read_table_id(&bea_data)?,
@@ -444,20 +459,6 @@ impl Dataset {
         options.with_target(name);
         match name {
             ParameterName::Industry => {
-                let path = bea_data.join("parameter_values");
-                if !path.exists() {
-                    std::fs::DirBuilder::new()
-                        .create(&path)
-                        .map_err(|e| IoError::new(path.clone(), e, line!(), file!().into()))?;
-                    tracing::info!("Target directory for Parameter Values created.");
-                }
-                let path = path.join(format!("{self}_{name}"));
-                if !path.exists() {
-                    std::fs::DirBuilder::new()
-                        .create(&path)
-                        .map_err(|e| IoError::new(path.clone(), e, line!(), file!().into()))?;
-                    tracing::info!("Target directory for Parameter Values created.");
-                }
                 for id in table_id {
                     options.with_table_id(*id.value());
                     app.with_options(options.clone());
@@ -467,13 +468,12 @@ impl Dataset {
                         Ok(json) => {
                             let contents = serde_json::to_vec(&json)
                                 .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
-                            let path = path.join(format!(
-                                "{self}_{name}_byTableId_{}_values.json",
+                            let key = StorageKey::new(format!(
+                                "parameter_values/{self}_{name}/{self}_{name}_byTableId_{}_values.json",
                                 id.value()
                             ));
-                            tracing::info!("Current target path: {path:?}");
-                            std::fs::write(&path, contents)
-                                .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
+                            tracing::info!("Current target key: {key}");
+                            storage.put(&key, contents).await?;
                         }
                         Err(source) => {
                             let url = app.url().to_string();
@@ -493,20 +493,6 @@ impl Dataset {
                 }
             }
             ParameterName::Year => {
-                let path = bea_data.join("parameter_values");
-                if !path.exists() {
-                    std::fs::DirBuilder::new()
-                        .create(&path)
-                        .map_err(|e| IoError::new(path.clone(), e, line!(), file!().into()))?;
-                    tracing::info!("Target directory for Parameter Values created.");
-                }
-                let path = path.join(format!("{self}_{name}"));
-                if !path.exists() {
-                    std::fs::DirBuilder::new()
-                        .create(&path)
-                        .map_err(|e| IoError::new(path.clone(), e, line!(), file!().into()))?;
-                    tracing::info!("Target directory for Parameter Values created.");
-                }
                 for id in table_id {
                     options.with_table_id(*id.value());
                     app.with_options(options.clone());
@@ -517,13 +503,12 @@ impl Dataset {
                             // tracing::info!("{json:#?}");
                             let contents = serde_json::to_vec(&json)
                                 .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
-                            let path = path.join(format!(
-                                "{self}_{name}_byTableId_{}_values.json",
+                            let key = StorageKey::new(format!(
+                                "parameter_values/{self}_{name}/{self}_{name}_byTableId_{}_values.json",
                                 id.value()
                             ));
-                            tracing::info!("Current target path: {path:?}");
-                            std::fs::write(&path, contents)
-                                .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
+                            tracing::info!("Current target key: {key}");
+                            storage.put(&key, contents).await?;
                         }
                         Err(source) => {
                             let url = app.url().to_string();
@@ -595,17 +580,37 @@ impl Dataset {
             Self::NIUnderlyingDetail => NiUnderlyingDetail::queue(),
             Self::FixedAssets => FixedAssets::queue(),
             Self::Mne => Mne::queue(),
-            _ => {
-                let error = DatasetMissing::new(
-                    "Nipa, NIUnderlyingDetail, FixedAssets or Mne variants required".to_string(),
-                    line!(),
-                    file!().to_string(),
-                );
-                Err(error.into())
-            }
+            _ => Err(self.queue_missing().into()),
         }
     }
 
+    /// Builds the download queue for the dataset using the key, url and storage root of
+    /// `client`.  Requests with values absent from the cached parameter values are removed, see
+    /// [`Queue::validate`], and the rest are completed and checked against the [`Catalog`] of
+    /// parameter rules.
+    pub fn queue_with(&self, client: &BeaClient) -> Result<Queue, BeaErr> {
+        let mut queue = match self {
+            Self::Nipa => Nipa::queue_with(client)?,
+            Self::NIUnderlyingDetail => NiUnderlyingDetail::queue_with(client)?,
+            Self::FixedAssets => FixedAssets::queue_with(client)?,
+            Self::Mne => Mne::queue_with(client)?,
+            _ => return Err(self.queue_missing().into()),
+        };
+        let validate = Validate::from_path(client.root(), &[*self])?;
+        queue.validate(&validate)?;
+        Catalog::from_path(client.root())?.enforce_queue(&mut queue)?;
+        Ok(queue)
+    }
+
+    /// The error for datasets without a download queue.
+    fn queue_missing(&self) -> DatasetMissing {
+        DatasetMissing::new(
+            "Nipa, NIUnderlyingDetail, FixedAssets or Mne variants required".to_string(),
+            line!(),
+            file!().to_string(),
+        )
+    }
+
     #[tracing::instrument]
     pub fn value_set<P: AsRef<std::path::Path> + std::fmt::Debug>(
         self,
@@ -763,8 +768,7 @@ impl Dataset {
 
     #[tracing::instrument]
     pub fn value_sets() -> Result<(), BeaErr> {
-        dotenvy::dotenv().ok();
-        let path = bea_data()?;
+        let path = Settings::resolve(&Overrides::default())?.data().clone();
         let datasets: Vec<Dataset> = Dataset::iter().collect();
         for dataset in &datasets {
             dataset.value_set(&path)?;
@@ -0,0 +1,219 @@
+use crate::{
+    Aggregation, App, BTreeKeyMissing, BeaClient, BeaErr, Dataset, Frequency, FrequencyMismatch,
+    IndexKind, MillionsOptions, Period, Request, Series, SeriesCollection, Unit, UnitMismatch,
+    VariantMissing,
+};
+
+/// The `Deflator` enum names the price indexes commonly used to convert nominal values to real
+/// terms.  Each variant refers to a line of a NIPA table in the storage of a [`BeaClient`].
+///
+/// Only an implicit price deflator, the ratio of current to chained dollars, recovers the
+/// published chained-dollar values exactly.  The PCE price index is a Fisher index, and because
+/// chained dollars are not additive, dividing by a Fisher index only approximates the chained
+/// dollars the BEA publishes.  The approximation drifts further from the published values the
+/// further a period lies from the reference year.
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    strum::EnumIter,
+    derive_more::Display,
+)]
+pub enum Deflator {
+    /// Implicit price deflator for gross domestic product, Table 1.1.9, line 1.
+    #[display("GDP implicit price deflator")]
+    Gdp,
+    /// Fisher price index for personal consumption expenditures, Table 2.3.4, line 1.  Deflating
+    /// by it approximates chained dollars, see [`Deflator`].
+    #[display("PCE price index")]
+    Pce,
+}
+
+impl Deflator {
+    /// The NIPA table publishing the price index.
+    pub fn table_name(&self) -> &'static str {
+        match self {
+            Self::Gdp => "T10109",
+            Self::Pce => "T20304",
+        }
+    }
+
+    /// The line of the table holding the price index.
+    pub fn line_number(&self) -> i64 {
+        match self {
+            Self::Gdp | Self::Pce => 1,
+        }
+    }
+
+    /// The request for the table publishing the price index, with the key, url and storage root
+    /// of `client`.  Locate the response with [`App::storage_key`] or
+    /// [`App::destination`].
+    pub fn app(&self, client: &BeaClient) -> App {
+        let mut app = client.request(Request::Data);
+        app.with_dataset(Dataset::Nipa);
+        let mut params = std::collections::BTreeMap::new();
+        params.insert("TableName".to_string(), self.table_name().to_string());
+        params.insert("ShowMillions".to_string(), MillionsOptions::No.value());
+        app.with_params(params);
+        app
+    }
+
+    /// Reads the price index at `frequency` from the storage of `client`.
+    pub async fn load_from(
+        &self,
+        client: &BeaClient,
+        frequency: Frequency,
+    ) -> Result<Series, BeaErr> {
+        let app = self.app(client);
+        let response = app.load_from(client.storage()).await?;
+        let Some(data) = response.results().into_data() else {
+            let error = VariantMissing::new(
+                "Data variant missing".to_string(),
+                self.table_name().to_string(),
+                line!(),
+                file!().to_string(),
+            );
+            return Err(error.into());
+        };
+        let collection = SeriesCollection::from(&data);
+        match collection.by_line(self.table_name(), self.line_number(), frequency) {
+            Some(series) => Ok(series.clone()),
+            None => {
+                let error = BTreeKeyMissing::new(
+                    format!(
+                        "{} line {} at frequency {}",
+                        self.table_name(),
+                        self.line_number(),
+                        frequency.value()
+                    ),
+                    line!(),
+                    file!().to_string(),
+                );
+                Err(error.into())
+            }
+        }
+    }
+
+    /// Reads the price index at `frequency` using a client configured from the environment, see
+    /// [`BeaClient::from_env`].
+    pub async fn load(&self, frequency: Frequency) -> Result<Series, BeaErr> {
+        let client = BeaClient::from_env()?;
+        self.load_from(&client, frequency).await
+    }
+}
+
+impl Series {
+    /// Converts a current-dollar series to real terms using the price index `deflator`,
+    /// expressed in the dollars of the `base` year.
+    ///
+    /// The deflator is rescaled so that its average over the `base` year equals one, and each
+    /// nominal value is divided by the rescaled deflator in the same period.  Periods where the
+    /// deflator is missing or suppressed yield `None`.  Deflating by a chain-type price index or
+    /// implicit price deflator yields chained dollars; any other index yields constant dollars.
+    /// The result matches the published chained dollars only for an implicit price deflator; a
+    /// Fisher price index gives an approximation, see [`Deflator`].
+    ///
+    /// Fails if the series is not in current dollars, the deflator is not an index, the two
+    /// differ in frequency, or the deflator has no values for the `base` year.
+    ///
+    /// ```
+    /// use bears::{IndexKind, Period, Series, Unit};
+    ///
+    /// let mut nominal = Series::default();
+    /// nominal.with_unit(Unit::CurrentDollars);
+    /// nominal.insert(Period::Year(2017), Some(100.0));
+    /// nominal.insert(Period::Year(2023), Some(150.0));
+    /// let mut prices = Series::default();
+    /// prices.with_unit(Unit::Index(IndexKind::ImplicitPriceDeflator));
+    /// prices.insert(Period::Year(2017), Some(100.0));
+    /// prices.insert(Period::Year(2023), Some(125.0));
+    /// let real = nominal.deflate(&prices, 2017).unwrap();
+    /// assert_eq!(real.unit(), &Unit::ChainedDollars);
+    /// assert_eq!(real.get(&Period::Year(2023)), Some(120.0));
+    /// assert_eq!(real.reference(), &Some((Period::Year(2017), Period::Year(2017))));
+    /// ```
+    pub fn deflate(&self, deflator: &Series, base: i16) -> Result<Self, BeaErr> {
+        if self.unit() != &Unit::CurrentDollars {
+            let error = UnitMismatch::new(
+                self.unit().to_string(),
+                Unit::CurrentDollars.to_string(),
+                "deflate".to_string(),
+                line!(),
+                file!().to_string(),
+            );
+            return Err(error.into());
+        }
+        let unit = match deflator.unit() {
+            Unit::Index(IndexKind::Price | IndexKind::ImplicitPriceDeflator) => {
+                Unit::ChainedDollars
+            }
+            Unit::Index(_) => Unit::ConstantDollars,
+            other => {
+                let error = UnitMismatch::new(
+                    other.to_string(),
+                    Unit::Index(IndexKind::Price).to_string(),
+                    "deflate by".to_string(),
+                    line!(),
+                    file!().to_string(),
+                );
+                return Err(error.into());
+            }
+        };
+        if let (Some(left), Some(right)) = (self.frequency(), deflator.frequency())
+            && left != right
+        {
+            let error =
+                FrequencyMismatch::new(left.value(), right.value(), line!(), file!().to_string());
+            return Err(error.into());
+        }
+        let annual = deflator.aggregate(Frequency::Annual, Aggregation::Average)?;
+        let level = match annual.get(&Period::Year(base)) {
+            Some(level) if level != 0.0 => level,
+            _ => {
+                let error = BTreeKeyMissing::new(
+                    format!("{} for base year {base}", deflator.code()),
+                    line!(),
+                    file!().to_string(),
+                );
+                return Err(error.into());
+            }
+        };
+        let mut real = self.clone();
+        for (period, value) in self.observations() {
+            let price = deflator.get(period).filter(|price| *price != 0.0);
+            let value = match (value, price) {
+                (Some(value), Some(price)) => Some(value / (price / level)),
+                _ => None,
+            };
+            real.insert(*period, value);
+        }
+        let metric_name = match unit {
+            Unit::ChainedDollars => format!("Chained ({base}) Dollars"),
+            _ => format!("Constant ({base}) Dollars"),
+        };
+        real.with_unit(unit)
+            .with_metric_name(metric_name)
+            .with_reference((Period::Year(base), Period::Year(base)));
+        Ok(real)
+    }
+
+    /// Converts a current-dollar series to real terms using the named default `deflator` from
+    /// the storage of `client`, in the dollars of the `base` year.  See [`Series::deflate`].
+    pub async fn deflate_by(
+        &self,
+        client: &BeaClient,
+        deflator: Deflator,
+        base: i16,
+    ) -> Result<Self, BeaErr> {
+        let frequency = self.frequency().unwrap_or(Frequency::Annual);
+        let prices = deflator.load_from(client, frequency).await?;
+        self.deflate(&prices, base)
+    }
+}
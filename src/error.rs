@@ -15,6 +15,87 @@ impl std::error::Error for BeaErr {
     }
 }
 
+impl BeaErr {
+    /// The broad class of the error.  See [`BeaErrorKind::class`].
+    pub fn class(&self) -> ErrorClass {
+        self.kind.class()
+    }
+
+    /// Indicates `true` if repeating the operation that failed may succeed.  See
+    /// [`ErrorClass::is_retryable`].
+    pub fn is_retryable(&self) -> bool {
+        self.class().is_retryable()
+    }
+
+    /// Logs the error with its class, at warning level if retryable and error level otherwise.
+    /// Events carry the target `alert`, so that a subscriber can route failures to an alerting
+    /// channel without parsing the message.
+    pub fn alert(&self) {
+        let class = self.class();
+        if class.is_retryable() {
+            tracing::warn!(target: "alert", %class, "{self}");
+        } else {
+            tracing::error!(target: "alert", %class, "{self}");
+        }
+    }
+}
+
+/// The `ErrorClass` enum sorts the variants of [`BeaErrorKind`] by what a caller can do about
+/// them.  Only network and server errors are worth retrying.  Calls exceeding the rate limit
+/// extend the lockout imposed by the BEA, so a rate limit error stops the work instead.
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    strum::EnumIter,
+    derive_more::Display,
+)]
+pub enum ErrorClass {
+    /// A transient failure to reach the BEA server, such as a timeout or refused connection.
+    Network,
+    /// The BEA server rejected the call for exceeding its rate limits.
+    RateLimit,
+    /// The BEA server failed to process a valid request.
+    Server,
+    /// The request is invalid, or asks for data the BEA does not publish.
+    Request,
+    /// Reading or writing local files failed.
+    Io,
+    /// A response or cached file does not have the expected shape, such as after the BEA changes
+    /// its schema, or holds data from an unexpected dataset.
+    Parse,
+    /// The environment or configuration is missing or invalid, such as an unset `BEA_DATA`.
+    Config,
+}
+
+impl ErrorClass {
+    /// Indicates `true` if repeating the operation may succeed without changes by the caller.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Network | Self::Server)
+    }
+
+    /// Process exit code reported by the command line interface for errors of this class.
+    /// Codes follow the conventions of `sysexits.h`.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::Request => 65,
+            Self::Parse => 65,
+            Self::Network => 69,
+            Self::Server => 69,
+            Self::Io => 74,
+            Self::RateLimit => 75,
+            Self::Config => 78,
+        }
+    }
+}
+
 impl From<BeaErrorKind> for BeaErr {
     fn from(value: BeaErrorKind) -> Self {
         let kind = Box::new(value);
@@ -40,29 +121,43 @@ macro_rules! impl_bea_err {
 
 impl_bea_err!(
     AnnotationMissing,
+    ApiFailure,
+    BaseSuppressed,
     BincodeError,
     BoolInvalid,
     BTreeKeyMissing,
     Check,
+    ConfigMissing,
     Csv,
     DatasetMissing,
     DeriveFromStr,
     EnvError,
+    FrequencyMismatch,
     IntegerInvalid,
     InvestmentInvalid,
     IoError,
     Jiff,
     JsonParseError,
+    MultipleValues,
+    NamesMismatch,
     Nom,
     OwnershipInvalid,
+    ParameterMissing,
     ParameterValueTableVariant,
+    ParamsInvalid,
     ParseInt,
+    PeriodInvalid,
+    ProfileMissing,
     RateLimit,
     ReqwestError,
     RowCodeMissing,
     SerdeJson,
     Set,
+    StorageStatus,
+    Toml,
+    UnitMismatch,
     UrlParseError,
+    ValueInvalid,
     VariantMissing,
     YearInvalid,
 );
@@ -71,6 +166,10 @@ impl_bea_err!(
 pub enum BeaErrorKind {
     #[from(AnnotationMissing)]
     AnnotationMissing(AnnotationMissing),
+    #[from(ApiFailure)]
+    ApiFailure(ApiFailure),
+    #[from(BaseSuppressed)]
+    BaseSuppressed(BaseSuppressed),
     #[from(BincodeError)]
     Bincode(BincodeError),
     #[from(BoolInvalid)]
@@ -79,6 +178,8 @@ pub enum BeaErrorKind {
     BTreeKeyMissing(BTreeKeyMissing),
     #[from(Check)]
     Check(Check),
+    #[from(ConfigMissing)]
+    ConfigMissing(ConfigMissing),
     #[from(Csv)]
     Csv(Csv),
     #[from(DatasetMissing)]
@@ -87,6 +188,8 @@ pub enum BeaErrorKind {
     DeriveFromStr(DeriveFromStr),
     #[from(EnvError)]
     Env(EnvError),
+    #[from(FrequencyMismatch)]
+    FrequencyMismatch(FrequencyMismatch),
     #[from(IntegerInvalid)]
     IntegerInvalid(IntegerInvalid),
     #[from(InvestmentInvalid)]
@@ -97,14 +200,26 @@ pub enum BeaErrorKind {
     Jiff(Jiff),
     #[from(JsonParseError)]
     JsonParse(JsonParseError),
+    #[from(MultipleValues)]
+    MultipleValues(MultipleValues),
+    #[from(NamesMismatch)]
+    NamesMismatch(NamesMismatch),
     #[from(Nom)]
     Nom(Nom),
     #[from(OwnershipInvalid)]
     OwnershipInvalid(OwnershipInvalid),
+    #[from(ParameterMissing)]
+    ParameterMissing(ParameterMissing),
     #[from(ParameterValueTableVariant)]
     ParameterValueTableVariant(ParameterValueTableVariant),
+    #[from(ParamsInvalid)]
+    ParamsInvalid(ParamsInvalid),
     #[from(ParseInt)]
     ParseInt(ParseInt),
+    #[from(PeriodInvalid)]
+    PeriodInvalid(PeriodInvalid),
+    #[from(ProfileMissing)]
+    ProfileMissing(ProfileMissing),
     #[from(RateLimit)]
     RateLimit(RateLimit),
     #[from(ReqwestError)]
@@ -115,8 +230,16 @@ pub enum BeaErrorKind {
     Set(Set),
     #[from(SerdeJson)]
     SerdeJson(SerdeJson),
+    #[from(StorageStatus)]
+    StorageStatus(StorageStatus),
+    #[from(Toml)]
+    Toml(Toml),
+    #[from(UnitMismatch)]
+    UnitMismatch(UnitMismatch),
     #[from(UrlParseError)]
     UrlParse(UrlParseError),
+    #[from(ValueInvalid)]
+    ValueInvalid(ValueInvalid),
     #[from(VariantMissing)]
     VariantMissing(VariantMissing),
     #[from(YearInvalid)]
@@ -129,6 +252,12 @@ impl std::fmt::Display for BeaErrorKind {
             Self::AnnotationMissing(e) => {
                 write!(f, "{e}")
             }
+            Self::ApiFailure(e) => {
+                write!(f, "{e}")
+            }
+            Self::BaseSuppressed(e) => {
+                write!(f, "{e}")
+            }
             Self::Bincode(e) => {
                 write!(f, "{e}")
             }
@@ -141,6 +270,9 @@ impl std::fmt::Display for BeaErrorKind {
             Self::Check(e) => {
                 write!(f, "{e}")
             }
+            Self::ConfigMissing(e) => {
+                write!(f, "{e}")
+            }
             Self::Csv(e) => {
                 write!(f, "{e}")
             }
@@ -153,6 +285,9 @@ impl std::fmt::Display for BeaErrorKind {
             Self::Env(e) => {
                 write!(f, "{e}")
             }
+            Self::FrequencyMismatch(e) => {
+                write!(f, "{e}")
+            }
             Self::IntegerInvalid(e) => {
                 write!(f, "{e}")
             }
@@ -168,18 +303,36 @@ impl std::fmt::Display for BeaErrorKind {
             Self::JsonParse(e) => {
                 write!(f, "{e}")
             }
+            Self::MultipleValues(e) => {
+                write!(f, "{e}")
+            }
+            Self::NamesMismatch(e) => {
+                write!(f, "{e}")
+            }
             Self::Nom(e) => {
                 write!(f, "{e}")
             }
             Self::OwnershipInvalid(e) => {
                 write!(f, "{e}")
             }
+            Self::ParameterMissing(e) => {
+                write!(f, "{e}")
+            }
             Self::ParameterValueTableVariant(e) => {
                 write!(f, "{e}")
             }
+            Self::ParamsInvalid(e) => {
+                write!(f, "{e}")
+            }
             Self::ParseInt(e) => {
                 write!(f, "{e}")
             }
+            Self::PeriodInvalid(e) => {
+                write!(f, "{e}")
+            }
+            Self::ProfileMissing(e) => {
+                write!(f, "{e}")
+            }
             Self::RateLimit(e) => {
                 write!(f, "{e}")
             }
@@ -195,9 +348,21 @@ impl std::fmt::Display for BeaErrorKind {
             Self::SerdeJson(e) => {
                 write!(f, "{e}")
             }
+            Self::StorageStatus(e) => {
+                write!(f, "{e}")
+            }
+            Self::Toml(e) => {
+                write!(f, "{e}")
+            }
+            Self::UnitMismatch(e) => {
+                write!(f, "{e}")
+            }
             Self::UrlParse(e) => {
                 write!(f, "{e}")
             }
+            Self::ValueInvalid(e) => {
+                write!(f, "{e}")
+            }
             Self::VariantMissing(e) => {
                 write!(f, "{e}")
             }
@@ -208,33 +373,121 @@ impl std::fmt::Display for BeaErrorKind {
     }
 }
 
+impl BeaErrorKind {
+    /// The broad class of the error, deciding whether it is worth retrying.
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            Self::ApiFailure(e) => {
+                if e.kind().is_server_error() {
+                    match e.kind() {
+                        crate::ApiErrorKind::RequestsExceeded => ErrorClass::RateLimit,
+                        _ => ErrorClass::Server,
+                    }
+                } else if *e.kind() == crate::ApiErrorKind::InvalidKey {
+                    ErrorClass::Config
+                } else {
+                    ErrorClass::Request
+                }
+            }
+            Self::RateLimit(_) => ErrorClass::RateLimit,
+            Self::Reqwest(e) => {
+                let source = &e.source;
+                match source.status() {
+                    Some(status) if status.as_u16() == 429 => ErrorClass::RateLimit,
+                    Some(status) if status.is_server_error() => ErrorClass::Server,
+                    Some(_) => ErrorClass::Request,
+                    None if source.is_decode() => ErrorClass::Parse,
+                    None if source.is_builder() => ErrorClass::Config,
+                    None => ErrorClass::Network,
+                }
+            }
+            Self::Io(_) => ErrorClass::Io,
+            Self::StorageStatus(e) => match e.status {
+                429 | 503 => ErrorClass::RateLimit,
+                500.. => ErrorClass::Server,
+                401 | 403 => ErrorClass::Config,
+                _ => ErrorClass::Io,
+            },
+            Self::ConfigMissing(_)
+            | Self::Env(_)
+            | Self::ProfileMissing(_)
+            | Self::Toml(_)
+            | Self::UrlParse(_) => ErrorClass::Config,
+            Self::BaseSuppressed(_)
+            | Self::BTreeKeyMissing(_)
+            | Self::FrequencyMismatch(_)
+            | Self::MultipleValues(_)
+            | Self::ParameterMissing(_)
+            | Self::ParamsInvalid(_)
+            | Self::UnitMismatch(_)
+            | Self::ValueInvalid(_) => ErrorClass::Request,
+            Self::AnnotationMissing(_)
+            | Self::Bincode(_)
+            | Self::BoolInvalid(_)
+            | Self::Check(_)
+            | Self::Csv(_)
+            | Self::DatasetMissing(_)
+            | Self::DeriveFromStr(_)
+            | Self::IntegerInvalid(_)
+            | Self::InvestmentInvalid(_)
+            | Self::Jiff(_)
+            | Self::JsonParse(_)
+            | Self::NamesMismatch(_)
+            | Self::Nom(_)
+            | Self::OwnershipInvalid(_)
+            | Self::ParameterValueTableVariant(_)
+            | Self::ParseInt(_)
+            | Self::PeriodInvalid(_)
+            | Self::RowCodeMissing(_)
+            | Self::Set(_)
+            | Self::SerdeJson(_)
+            | Self::VariantMissing(_)
+            | Self::YearInvalid(_) => ErrorClass::Parse,
+        }
+    }
+}
+
 impl std::error::Error for BeaErrorKind {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             Self::AnnotationMissing(e) => e.source(),
+            Self::ApiFailure(e) => e.source(),
+            Self::BaseSuppressed(e) => e.source(),
             Self::Bincode(e) => Some(e.source()),
             Self::BoolInvalid(e) => e.source(),
             Self::BTreeKeyMissing(e) => e.source(),
             Self::Check(e) => e.source(),
+            Self::ConfigMissing(e) => e.source(),
             Self::Csv(e) => e.source(),
             Self::DatasetMissing(e) => e.source(),
             Self::DeriveFromStr(e) => e.source(),
             Self::Env(e) => Some(e.source()),
+            Self::FrequencyMismatch(e) => e.source(),
             Self::IntegerInvalid(e) => e.source(),
             Self::InvestmentInvalid(e) => e.source(),
             Self::Io(e) => Some(e.source()),
             Self::Jiff(e) => e.source(),
             Self::JsonParse(e) => e.source(),
+            Self::MultipleValues(e) => e.source(),
+            Self::NamesMismatch(e) => e.source(),
             Self::Nom(e) => e.source(),
             Self::OwnershipInvalid(e) => e.source(),
+            Self::ParameterMissing(e) => e.source(),
             Self::ParameterValueTableVariant(e) => e.source(),
+            Self::ParamsInvalid(e) => e.source(),
             Self::ParseInt(e) => Some(e.source()),
+            Self::PeriodInvalid(e) => e.source(),
+            Self::ProfileMissing(e) => e.source(),
             Self::RateLimit(e) => e.source(),
             Self::Reqwest(e) => Some(e.source()),
             Self::RowCodeMissing(e) => e.source(),
             Self::Set(e) => e.source(),
             Self::SerdeJson(e) => Some(e.source()),
+            Self::StorageStatus(e) => e.source(),
+            Self::Toml(e) => e.source(),
+            Self::UnitMismatch(e) => e.source(),
             Self::UrlParse(e) => Some(e.source()),
+            Self::ValueInvalid(e) => e.source(),
             Self::VariantMissing(e) => e.source(),
             Self::YearInvalid(e) => e.source(),
         }
@@ -633,6 +886,24 @@ impl std::error::Error for Set {
     }
 }
 
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, derive_more::Display, derive_new::new,
+)]
+#[display("cannot {operation} {left} and {right} at line {line} in file {file}")]
+pub struct UnitMismatch {
+    left: String,
+    right: String,
+    operation: String,
+    line: u32,
+    file: String,
+}
+
+impl std::error::Error for UnitMismatch {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
 #[derive(
     Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, derive_more::Display, derive_new::new,
 )]
@@ -922,3 +1193,325 @@ impl std::error::Error for BTreeKeyMissing {
         None
     }
 }
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, derive_more::Display, derive_new::new,
+)]
+#[display(
+    "{series} has a suppressed value in the reference period {reference} at line {line} in file {file}"
+)]
+pub struct BaseSuppressed {
+    series: String,
+    reference: String,
+    line: u32,
+    file: String,
+}
+
+impl std::error::Error for BaseSuppressed {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, derive_more::Display, derive_new::new,
+)]
+#[display("{input} is not a valid Period at line {line} in file {file}")]
+pub struct PeriodInvalid {
+    input: String,
+    line: u32,
+    file: String,
+}
+
+impl std::error::Error for PeriodInvalid {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, derive_more::Display, derive_new::new,
+)]
+#[display("frequency {left} does not match {right} at line {line} in file {file}")]
+pub struct FrequencyMismatch {
+    left: String,
+    right: String,
+    line: u32,
+    file: String,
+}
+
+impl std::error::Error for FrequencyMismatch {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    derive_more::Display,
+    derive_new::new,
+    derive_getters::Getters,
+)]
+#[display(
+    "{value} is not a valid {parameter} for {dataset}, closest valid values are [{}] at line {line} in file {file}",
+    suggestions.join(", ")
+)]
+pub struct ValueInvalid {
+    dataset: String,
+    parameter: String,
+    value: String,
+    suggestions: Vec<String>,
+    line: u32,
+    file: String,
+}
+
+impl std::error::Error for ValueInvalid {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    derive_more::Display,
+    derive_new::new,
+    derive_getters::Getters,
+)]
+#[display("required parameter {parameter} missing for {dataset} at line {line} in file {file}")]
+pub struct ParameterMissing {
+    dataset: String,
+    parameter: String,
+    line: u32,
+    file: String,
+}
+
+impl std::error::Error for ParameterMissing {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    derive_more::Display,
+    derive_new::new,
+    derive_getters::Getters,
+)]
+#[display(
+    "{parameter} accepts a single value for {dataset}, found {value} at line {line} in file {file}"
+)]
+pub struct MultipleValues {
+    dataset: String,
+    parameter: String,
+    value: String,
+    line: u32,
+    file: String,
+}
+
+impl std::error::Error for MultipleValues {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    derive_more::Display,
+    derive_new::new,
+    derive_getters::Getters,
+)]
+#[display(
+    "parameter names for {dataset} are [{}], but the BEA lists [{}] at line {line} in file {file}",
+    expected.join(", "),
+    found.join(", ")
+)]
+pub struct NamesMismatch {
+    dataset: String,
+    expected: Vec<String>,
+    found: Vec<String>,
+    line: u32,
+    file: String,
+}
+
+impl std::error::Error for NamesMismatch {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    derive_more::Display,
+    derive_new::new,
+    derive_getters::Getters,
+)]
+#[display("BEA API returned {kind} error {code}: {description} at line {line} in file {file}")]
+pub struct ApiFailure {
+    kind: crate::ApiErrorKind,
+    code: i32,
+    description: String,
+    line: u32,
+    file: String,
+}
+
+impl std::error::Error for ApiFailure {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+/// The `Toml` struct contains error information associated with the `toml` crate.
+#[derive(Debug, derive_more::Display, derive_more::Error, derive_new::new)]
+#[display("toml error at path {path:?} in line {line} of {file}")]
+pub struct Toml {
+    path: std::path::PathBuf,
+    source: toml::de::Error,
+    line: u32,
+    file: String,
+}
+
+/// The `ConfigMissing` struct reports a setting that is absent from the command line, the
+/// environment and the configuration profile, and has no default.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    derive_more::Display,
+    derive_new::new,
+    derive_getters::Getters,
+)]
+#[display("{setting} not set by flag, environment or profile at line {line} in {file}")]
+pub struct ConfigMissing {
+    setting: String,
+    line: u32,
+    file: String,
+}
+
+impl std::error::Error for ConfigMissing {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+/// The `ProfileMissing` struct reports a named profile absent from the configuration file.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    derive_more::Display,
+    derive_new::new,
+    derive_getters::Getters,
+)]
+#[display("profile {profile} not found in {path:?} at line {line} in {file}")]
+pub struct ProfileMissing {
+    profile: String,
+    path: std::path::PathBuf,
+    line: u32,
+    file: String,
+}
+
+impl std::error::Error for ProfileMissing {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+/// The `StorageStatus` struct reports an unsuccessful HTTP status from an object store, such as
+/// a missing bucket or rejected credentials.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    derive_more::Display,
+    derive_new::new,
+    derive_getters::Getters,
+)]
+#[display("object store returned status {status} for {object} at line {line} in {file}")]
+pub struct StorageStatus {
+    object: String,
+    status: u16,
+    line: u32,
+    file: String,
+}
+
+impl std::error::Error for StorageStatus {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+/// Reports every invalid or missing parameter of a request, rather than only the first.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    derive_more::Display,
+    derive_new::new,
+    derive_getters::Getters,
+)]
+#[display(
+    "request to {dataset} is missing [{}] and has invalid values [{}] at line {line} in file {file}",
+    missing.join(", "),
+    errors.iter().map(|e| format!("{}={}", e.parameter(), e.value())).collect::<Vec<String>>().join(", ")
+)]
+pub struct ParamsInvalid {
+    dataset: String,
+    /// Required parameters left unset.
+    missing: Vec<String>,
+    /// One error for each parameter holding an invalid value.
+    errors: Vec<ValueInvalid>,
+    line: u32,
+    file: String,
+}
+
+impl std::error::Error for ParamsInvalid {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
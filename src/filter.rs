@@ -0,0 +1,475 @@
+use crate::{
+    ApiErrorKind, ApiFailure, App, BeaClient, BeaErr, BeaResponse, Dataset, FsStorage,
+    ParameterName, ParameterValueTable, ParameterValues, Request, ReqwestError, Results, SerdeJson,
+    Set, Storage, StorageKey,
+};
+use std::collections::BTreeMap;
+
+/// The `ValueFilter` struct describes a call to
+/// [`Method::GetParameterValuesFiltered`](crate::Method::GetParameterValuesFiltered), asking which
+/// values of the `target` parameter of a dataset remain valid once other parameters are fixed.
+///
+/// Responses are cached on disk under `parameter_values/filtered`, one file per combination of
+/// dataset, target and filters, so each question is put to the BEA only once.  A response
+/// reporting that no data exists for the filters is cached as well, and read back as an empty set
+/// of values.
+///
+/// ```
+/// use bears::{Dataset, ParameterName, ValueFilter};
+///
+/// // Which line codes are valid for Regional table CAINC5N?
+/// let filter = ValueFilter::new(Dataset::Regional, ParameterName::LineCode)
+///     .with(ParameterName::TableName, "CAINC5N");
+/// let path = filter.path("/data");
+/// assert!(path.ends_with("parameter_values/filtered/Regional_LineCode_TableName-CAINC5N.json"));
+/// ```
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_getters::Getters,
+)]
+pub struct ValueFilter {
+    dataset: Dataset,
+    target: ParameterName,
+    filters: BTreeMap<ParameterName, String>,
+}
+
+impl ValueFilter {
+    /// Asks for the values of `target` in `dataset`, before any filters are added.
+    pub fn new(dataset: Dataset, target: ParameterName) -> Self {
+        Self {
+            dataset,
+            target,
+            filters: BTreeMap::new(),
+        }
+    }
+
+    /// Restricts the values of the target to those valid when parameter `name` is `value`.
+    pub fn with<S: ToString>(mut self, name: ParameterName, value: S) -> Self {
+        self.filters.insert(name, value.to_string());
+        self
+    }
+
+    /// The query parameters of the request, other than the dataset and method.
+    pub fn params(&self) -> BTreeMap<String, String> {
+        let mut params = self
+            .filters
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect::<BTreeMap<String, String>>();
+        params.insert("TargetParameter".to_string(), self.target.to_string());
+        params
+    }
+
+    /// Location of the cached response under the `BEA_DATA` directory `path`.
+    pub fn path<P: AsRef<std::path::Path>>(&self, path: P) -> std::path::PathBuf {
+        FsStorage::new(path.as_ref().into()).path(&self.key())
+    }
+
+    /// Key of the cached response in the storage root.  Bytes other than letters and digits in
+    /// filter values are percent-encoded, so that every value yields a valid file name and
+    /// distinct filters never share a file.
+    pub fn key(&self) -> StorageKey {
+        let mut name = format!("{}_{}", self.dataset, self.target);
+        for (key, value) in &self.filters {
+            let value = value
+                .bytes()
+                .map(|b| match b.is_ascii_alphanumeric() {
+                    true => (b as char).to_string(),
+                    false => format!("%{b:02X}"),
+                })
+                .collect::<String>();
+            name.push_str(&format!("_{key}-{value}"));
+        }
+        StorageKey::new(format!("parameter_values/filtered/{name}.json"))
+    }
+
+    /// Reads the cached response from the `BEA_DATA` directory `path`, returning `None` if the
+    /// filter has not been requested before.
+    pub fn cached<P: AsRef<std::path::Path>>(
+        &self,
+        path: P,
+    ) -> Result<Option<ParameterValues>, BeaErr> {
+        let storage = FsStorage::new(path.as_ref().into());
+        storage
+            .read(&self.key())?
+            .map(|contents| self.read_cache(&contents))
+            .transpose()
+    }
+
+    /// Parses the cached response `contents`.
+    fn read_cache(&self, contents: &[u8]) -> Result<ParameterValues, BeaErr> {
+        let json: serde_json::Value = serde_json::from_slice(contents)
+            .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+        let values = Self::parameter_values(&json)?;
+        tracing::trace!("Read {} cached values for {}.", values.len(), self.key());
+        Ok(values)
+    }
+
+    /// Parses the parameter values from a response, failing if the BEA returned anything else.
+    /// A response reporting that no data exists yields an empty set of values.
+    fn parameter_values(json: &serde_json::Value) -> Result<ParameterValues, BeaErr> {
+        let response = BeaResponse::try_from(json)?;
+        let failure = |kind, code, description: &String| {
+            ApiFailure::new(
+                kind,
+                code,
+                description.clone(),
+                line!(),
+                file!().to_string(),
+            )
+        };
+        match response.results() {
+            Results::ApiError(e) if e.kind() == ApiErrorKind::NoData => {
+                return Ok(ParameterValues::default());
+            }
+            Results::MneError(e) if e.kind() == ApiErrorKind::NoData => {
+                return Ok(ParameterValues::default());
+            }
+            Results::ApiError(e) => {
+                return Err(failure(e.kind(), *e.code(), e.description()).into())
+            }
+            Results::MneError(e) => return Err(failure(e.kind(), *e.number(), e.error()).into()),
+            Results::RequestsExceeded(e) => {
+                return Err(failure(e.kind(), *e.code(), e.description()).into());
+            }
+            _ => {}
+        }
+        match response.parameter_values() {
+            Some(values) => Ok(values),
+            None => {
+                tracing::warn!(
+                    "Results must be of type ParameterValues, found {:#?}",
+                    response.results()
+                );
+                Err(Set::ParameterValuesMissing.into())
+            }
+        }
+    }
+
+    /// Returns the values from the cache under the `BEA_DATA` directory `path`, requesting them
+    /// from the BEA using `app` and caching the response if absent.
+    pub async fn fetch_with<P: AsRef<std::path::Path>>(
+        &self,
+        app: &mut App,
+        path: P,
+    ) -> Result<ParameterValues, BeaErr> {
+        let storage = FsStorage::new(path.as_ref().into());
+        if let Some(contents) = storage.get(&self.key()).await? {
+            return self.read_cache(&contents);
+        }
+        app.with_dataset(self.dataset);
+        app.with_params(self.params());
+        let data = app.get().await?;
+        let json = match data.json::<serde_json::Value>().await {
+            Ok(json) => json,
+            Err(source) => {
+                let url = app.url().to_string();
+                let method = "get".to_string();
+                let body = app.params().into_iter().collect::<Vec<(String, String)>>();
+                let mut error =
+                    ReqwestError::new(url, method, source, line!(), file!().to_string());
+                error.with_body(body);
+                return Err(error.into());
+            }
+        };
+        let values = Self::parameter_values(&json)?;
+        let contents = serde_json::to_vec(&json)
+            .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+        storage.put(&self.key(), contents).await?;
+        Ok(values)
+    }
+
+    /// Returns the values from the cache in the `BEA_DATA` directory, requesting them from the
+    /// BEA if absent.  See [`ValueFilter::fetch_from`].
+    pub async fn fetch(&self) -> Result<ParameterValues, BeaErr> {
+        self.fetch_from(&BeaClient::from_env()?).await
+    }
+
+    /// Returns the values from the cache under the storage root of `client`, requesting them
+    /// from the BEA using `client` if absent.
+    pub async fn fetch_from(&self, client: &BeaClient) -> Result<ParameterValues, BeaErr> {
+        let mut app = client.request(Request::ParameterValueFilter);
+        self.fetch_with(&mut app, client.root()).await
+    }
+
+    /// Returns the values converted to type `T`, such as [`Integer`](crate::Integer),
+    /// [`Year`](crate::Year) or [`TableName`](crate::TableName), requesting them if absent from
+    /// the cache in the `BEA_DATA` directory.
+    ///
+    /// ```no_run
+    /// use bears::{Dataset, Integer, ParameterName, ValueFilter};
+    ///
+    /// # async fn run() -> Result<(), bears::BeaErr> {
+    /// let filter = ValueFilter::new(Dataset::Regional, ParameterName::LineCode)
+    ///     .with(ParameterName::TableName, "CAINC5N");
+    /// let line_codes = filter.values::<Integer>().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn values<T>(&self) -> Result<Vec<T>, BeaErr>
+    where
+        T: for<'a> TryFrom<&'a ParameterValueTable, Error = BeaErr>,
+    {
+        let values = self.fetch().await?;
+        Self::convert(&values)
+    }
+
+    /// Returns the cached values under the `BEA_DATA` directory `path` converted to type `T`,
+    /// without calling the BEA.  Returns `None` if the filter has not been requested before.
+    pub fn cached_values<T, P>(&self, path: P) -> Result<Option<Vec<T>>, BeaErr>
+    where
+        T: for<'a> TryFrom<&'a ParameterValueTable, Error = BeaErr>,
+        P: AsRef<std::path::Path>,
+    {
+        match self.cached(path)? {
+            Some(values) => Ok(Some(Self::convert(&values)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Converts each parameter value to type `T`.
+    pub fn convert<T>(values: &ParameterValues) -> Result<Vec<T>, BeaErr>
+    where
+        T: for<'a> TryFrom<&'a ParameterValueTable, Error = BeaErr>,
+    {
+        values.iter().map(T::try_from).collect()
+    }
+}
+
+/// Parameters of `dataset` whose legal values depend on the values of other parameters, paired
+/// with the parameters they depend on.  Used by [`Prune`] to decide which filtered parameter
+/// values to consult.
+///
+/// The NIPA datasets have none, because [`NipaIterator`](crate::NipaIterator) already pairs each
+/// table with the frequencies and years published for it.
+pub fn value_dependencies(
+    dataset: Dataset,
+) -> &'static [(ParameterName, &'static [ParameterName])] {
+    use ParameterName as pn;
+    match dataset {
+        Dataset::Mne => &[
+            (
+                pn::SeriesID,
+                &[pn::DirectionOfInvestment, pn::Classification],
+            ),
+            (
+                pn::Industry,
+                &[pn::DirectionOfInvestment, pn::Classification],
+            ),
+            (
+                pn::Country,
+                &[pn::DirectionOfInvestment, pn::Classification],
+            ),
+        ],
+        Dataset::GDPbyIndustry | Dataset::UnderlyingGDPbyIndustry => {
+            &[(pn::Industry, &[pn::TableID]), (pn::Year, &[pn::TableID])]
+        }
+        Dataset::Regional => &[
+            (pn::LineCode, &[pn::TableName]),
+            (pn::GeoFips, &[pn::TableName]),
+            (pn::Year, &[pn::TableName]),
+        ],
+        _ => &[],
+    }
+}
+
+/// The `Prune` struct screens the parameters produced by a data iterator, such as
+/// [`NipaIterator`](crate::NipaIterator) or the iterator from [`Mne::iter`](crate::Mne::iter), against
+/// filtered parameter values cached by [`ValueFilter`].  Combinations that the BEA reports cannot
+/// exist are skipped before they reach the [`Queue`](crate::Queue), so that a fresh mirror does
+/// not spend its error budget discovering them.
+///
+/// Only the cache is consulted.  Where no filtered values are cached for a combination, the
+/// parameters pass unchanged, so against a cold cache nothing is pruned.  Call [`Prune::warm`]
+/// first to request the missing filtered values from the BEA.
+#[derive(Debug, Clone, PartialEq, Eq, derive_getters::Getters)]
+pub struct Prune {
+    path: std::path::PathBuf,
+    dataset: Dataset,
+    dependencies: Vec<(ParameterName, Vec<ParameterName>)>,
+}
+
+impl Prune {
+    /// Prunes parameters for `dataset` using the filtered values cached under the `BEA_DATA`
+    /// directory `path`, following the default [`value_dependencies`] of the dataset.
+    pub fn new<P: AsRef<std::path::Path>>(path: P, dataset: Dataset) -> Self {
+        let dependencies = value_dependencies(dataset)
+            .iter()
+            .map(|(target, filters)| (*target, filters.to_vec()))
+            .collect();
+        Self {
+            path: path.as_ref().to_path_buf(),
+            dataset,
+            dependencies,
+        }
+    }
+
+    /// Also checks values of `target` against the values cached for the filter formed from the
+    /// parameters `filters`.
+    pub fn with_dependency(mut self, target: ParameterName, filters: &[ParameterName]) -> Self {
+        self.dependencies.push((target, filters.to_vec()));
+        self
+    }
+
+    /// The filter answering which values of `target` are legal given `params`.  Returns `None`
+    /// if a filtering parameter is absent, or holds a keyword or list rather than a single value.
+    fn filter(
+        &self,
+        target: ParameterName,
+        filters: &[ParameterName],
+        params: &BTreeMap<String, String>,
+    ) -> Option<ValueFilter> {
+        let mut filter = ValueFilter::new(self.dataset, target);
+        for name in filters {
+            let value = params.get(&name.to_string())?;
+            let keyword = crate::validate::keywords(self.dataset, *name)
+                .iter()
+                .any(|k| k.eq_ignore_ascii_case(value));
+            if keyword || value.contains(',') {
+                return None;
+            }
+            filter = filter.with(*name, value);
+        }
+        Some(filter)
+    }
+
+    /// Requests from the BEA, using `client`, each filtered value set that `iter` would consult
+    /// but that is missing from the cache, returning the number requested.  Each response is
+    /// cached, so a following call to [`Prune::prune`] can rule out the combinations it reports
+    /// cannot exist.  Warming costs one call per distinct filter, so it is left to the caller.
+    pub async fn warm<I>(&self, client: &BeaClient, iter: I) -> Result<usize, BeaErr>
+    where
+        I: Iterator<Item = BTreeMap<String, String>>,
+    {
+        let storage = FsStorage::new(self.path.clone());
+        let mut missing = std::collections::BTreeSet::new();
+        for params in iter {
+            for (target, filters) in &self.dependencies {
+                if !params.contains_key(&target.to_string()) {
+                    continue;
+                }
+                if let Some(filter) = self.filter(*target, filters, &params)
+                    && !missing.contains(&filter)
+                    && !storage.exists(&filter.key()).await?
+                {
+                    missing.insert(filter);
+                }
+            }
+        }
+        tracing::info!("Requesting {} filtered value sets.", missing.len());
+        for filter in &missing {
+            let mut app = client.request(Request::ParameterValueFilter);
+            filter.fetch_with(&mut app, &self.path).await?;
+        }
+        Ok(missing.len())
+    }
+
+    /// Wraps `iter`, skipping parameters with values absent from the cached filtered values.
+    pub fn prune<I>(&self, iter: I) -> Pruned<'_, I>
+    where
+        I: Iterator<Item = BTreeMap<String, String>>,
+    {
+        Pruned {
+            prune: self,
+            iter,
+            known: std::collections::HashMap::new(),
+            skipped: 0,
+        }
+    }
+}
+
+/// Iterator adapter returned by [`Prune::prune`].
+#[derive(Debug, Clone)]
+pub struct Pruned<'a, I> {
+    prune: &'a Prune,
+    iter: I,
+    // cached legal values by filter, `None` where no response is cached
+    known: std::collections::HashMap<ValueFilter, Option<Vec<String>>>,
+    skipped: usize,
+}
+
+impl<I> Pruned<'_, I> {
+    /// Number of parameter combinations skipped so far.
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// Legal values of the target of `filter`, read from the cache on first use.  Unreadable
+    /// cache files are logged and treated as absent, as are values without keys, which describe
+    /// ranges and cannot rule anything out.  A cached "no data" response leaves no legal values.
+    fn legal(&mut self, filter: ValueFilter) -> Option<&Vec<String>> {
+        let path = &self.prune.path;
+        self.known
+            .entry(filter)
+            .or_insert_with_key(|filter| match filter.cached(path) {
+                Ok(values) => values.and_then(|values| {
+                    let keys = values
+                        .iter()
+                        .filter_map(|v| v.key())
+                        .collect::<Vec<String>>();
+                    match keys.is_empty() && !values.is_empty() {
+                        true => None,
+                        false => Some(keys),
+                    }
+                }),
+                Err(source) => {
+                    tracing::warn!("Ignoring cached values for {filter:?}: {source}");
+                    None
+                }
+            })
+            .as_ref()
+    }
+
+    /// Indicates `true` unless the cache shows a value in `params` cannot exist.
+    fn allowed(&mut self, params: &BTreeMap<String, String>) -> bool {
+        let prune = self.prune;
+        for (target, filters) in &prune.dependencies {
+            let Some(value) = params.get(&target.to_string()) else {
+                continue;
+            };
+            let Some(filter) = prune.filter(*target, filters, params) else {
+                continue;
+            };
+            let Some(legal) = self.legal(filter) else {
+                continue;
+            };
+            let keywords = crate::validate::keywords(prune.dataset, *target);
+            let missing = value.split(',').map(str::trim).find(|item| {
+                !keywords.iter().any(|k| k.eq_ignore_ascii_case(item))
+                    && !legal
+                        .iter()
+                        .any(|legal| crate::validate::matches(item, legal))
+            });
+            if let Some(item) = missing {
+                tracing::trace!("Skipping {target} {item}, not published for {filters:?}.");
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl<I> Iterator for Pruned<'_, I>
+where
+    I: Iterator<Item = BTreeMap<String, String>>,
+{
+    type Item = BTreeMap<String, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(params) = self.iter.next() {
+            if self.allowed(&params) {
+                return Some(params);
+            }
+            self.skipped += 1;
+        }
+        None
+    }
+}
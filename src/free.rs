@@ -1,6 +1,6 @@
 use crate::{
     App, BeaErr, Csv, EnvError, FromStrError, IoError, JsonParseError, JsonParseErrorKind,
-    KeyMissing, NotFloat, NotInteger, Options, ParseFloat, ParseInteger, UrlParseError,
+    KeyMissing, NotFloat, NotInteger, Options, Overrides, ParseFloat, ParseInteger, Settings,
 };
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, Layer};
 
@@ -17,6 +17,13 @@ pub fn bea_data() -> Result<std::path::PathBuf, EnvError> {
 pub fn trace_init() -> Result<(), BeaErr> {
     dotenvy::dotenv().ok();
     let path = bea_data()?;
+    trace_init_at(&path)
+}
+
+/// Initiates a subscriber for the tracing library, writing the download history under the data
+/// root `path` rather than the `BEA_DATA` directory.
+#[tracing::instrument]
+pub fn trace_init_at(path: &std::path::Path) -> Result<(), BeaErr> {
     let path = path.join("history");
     if !path.exists() {
         std::fs::DirBuilder::new()
@@ -58,23 +65,20 @@ pub fn trace_init() -> Result<(), BeaErr> {
 
 /// Helper function
 /// Initiates logging
-/// Reads environmental variables from .env
+/// Resolves the [`Settings`](crate::Settings) from the environment and configuration file
 /// Creates an instance of App
 #[tracing::instrument]
 pub fn init() -> Result<App, BeaErr> {
-    trace_init()?;
+    let settings = Settings::resolve(&Overrides::default())?;
+    trace_init_at(settings.data())?;
     tracing::info!("Test logging initialized.");
-    dotenvy::dotenv().ok();
-    let url = "BEA_URL".to_string();
-    let url = std::env::var(&url)
-        .map_err(|source| EnvError::new(url, source, line!(), file!().into()))?;
-    let url = url::Url::parse(&url)
-        .map_err(|source| UrlParseError::new(url, source, line!(), file!().into()))?;
-    let key = "API_KEY".to_string();
-    let key = std::env::var(&key)
-        .map_err(|source| EnvError::new(key, source, line!(), file!().into()))?;
-    let options = Options::default();
-    let app = App::new(key, options, url);
+    let client = settings.client()?;
+    let app = App::new(
+        client.key().clone(),
+        Options::default(),
+        client.url().clone(),
+        client.root().clone(),
+    );
     Ok(app)
 }
 
@@ -262,6 +266,26 @@ pub fn map_to_float(
     }
 }
 
+/// Markers the BEA reports in place of a data value that is suppressed or not available.
+pub const SUPPRESSED: [&str; 8] = ["(NA)", "(D)", "(S)", "(X)", "(*)", "---", "...", "n.a."];
+
+/// Convenience function for when we expect a float stored as a JSON string, but the value may be
+/// suppressed.  Returns `None` if the value is empty or one of the [`SUPPRESSED`] markers.
+#[tracing::instrument(skip(m))]
+pub fn map_to_optional_float(
+    key: &str,
+    m: &serde_json::Map<String, serde_json::Value>,
+) -> Result<Option<f64>, JsonParseError> {
+    if let Some(serde_json::Value::String(s)) = m.get(key) {
+        let s = s.trim();
+        if s.is_empty() || SUPPRESSED.contains(&s) {
+            tracing::trace!("Suppressed value: {s}");
+            return Ok(None);
+        }
+    }
+    Ok(Some(map_to_float(key, m)?))
+}
+
 /// Convenience function for when we expect an integer stored as a JSON string.
 #[tracing::instrument(skip(m))]
 pub fn map_to_int(
@@ -381,3 +405,51 @@ pub fn file_size<P: AsRef<std::path::Path>>(path: P) -> Option<u64> {
         None
     }
 }
+
+/// Number of single-character insertions, deletions or substitutions needed to turn `a` into
+/// `b`, ignoring case.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.to_lowercase().chars().collect::<Vec<char>>();
+    let b = b.to_lowercase().chars().collect::<Vec<char>>();
+    let mut row = (0..=b.len()).collect::<Vec<usize>>();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// Returns up to `count` members of `candidates` nearest to `value` by edit distance, nearest
+/// first.  Candidates that begin with `value` rank ahead of the rest, so that a truncated code
+/// suggests its completions.
+///
+/// ```
+/// let candidates = ["T10101", "T10105", "T20100"].map(String::from);
+/// let closest = bears::closest("T1010", &candidates, 2);
+/// assert_eq!(closest, vec!["T10101", "T10105"]);
+/// ```
+pub fn closest(value: &str, candidates: &[String], count: usize) -> Vec<String> {
+    let prefix = value.to_lowercase();
+    let mut ranked = candidates
+        .iter()
+        .map(|c| {
+            let partial = !c.to_lowercase().starts_with(&prefix);
+            ((partial, edit_distance(value, c)), c)
+        })
+        .collect::<Vec<((bool, usize), &String)>>();
+    ranked.sort_by_key(|(rank, _)| *rank);
+    ranked
+        .into_iter()
+        .take(count)
+        .map(|(_, c)| c.clone())
+        .collect()
+}
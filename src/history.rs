@@ -1,5 +1,6 @@
 use crate::{
-    bea_data, App, BeaErr, Data, Dataset, Event, IoError, Mode, Queue, ResultStatus, SerdeJson,
+    ApiErrorKind, App, BeaErr, Data, Dataset, Event, FsStorage, IoError, Mode, Queue, ResultStatus,
+    SerdeJson, Storage, StorageKey,
 };
 
 #[derive(
@@ -15,11 +16,43 @@ use crate::{
 pub struct History(std::collections::BTreeMap<std::path::PathBuf, Event>);
 
 impl History {
-    pub fn from_env() -> Result<Self, BeaErr> {
-        dotenvy::dotenv().ok();
-        let path = bea_data()?;
-        let path = path.join("history");
-        Self::try_from(&path)
+    /// Reads the download history that [`trace_init_at`](crate::trace_init_at) logs under the
+    /// storage root `root`.
+    pub fn from_root<P: AsRef<std::path::Path>>(root: P) -> Result<Self, BeaErr> {
+        let storage = FsStorage::new(root.as_ref().into());
+        Self::from_key(&storage, &StorageKey::new("history/history.log"))
+    }
+
+    /// Reads the history saved at `key` in `storage`, failing if absent.
+    fn from_key(storage: &FsStorage, key: &StorageKey) -> Result<Self, BeaErr> {
+        match storage.read(key)? {
+            Some(contents) => Self::parse(&String::from_utf8_lossy(&contents)),
+            None => {
+                let error = std::io::Error::from(std::io::ErrorKind::NotFound);
+                Err(IoError::new(storage.path(key), error, line!(), file!().into()).into())
+            }
+        }
+    }
+
+    /// Reads the download history from `storage`, under the key used by
+    /// [`History::from_root`].  Returns an empty history if the key is absent.
+    pub async fn from_storage<S: Storage>(storage: &S) -> Result<Self, BeaErr> {
+        let key = StorageKey::new("history/history.log");
+        match storage.get(&key).await? {
+            Some(contents) => Self::parse(&String::from_utf8_lossy(&contents)),
+            None => Ok(Self(Default::default())),
+        }
+    }
+
+    /// Reads the history of `mode` for `dataset` kept under the storage root `root`.
+    pub fn from_dataset<P: AsRef<std::path::Path>>(
+        root: P,
+        dataset: Dataset,
+        mode: Mode,
+    ) -> Result<Self, BeaErr> {
+        let storage = FsStorage::new(root.as_ref().into());
+        let key = StorageKey::new(format!("history/history_{dataset}_{mode}.log"));
+        Self::from_key(&storage, &key)
     }
 
     pub fn contains(&self, path: &std::path::PathBuf) -> bool {
@@ -42,7 +75,7 @@ impl History {
         let path = app.destination(false)?;
         if let Some(event) = self.get(&path) {
             match event.status() {
-                ResultStatus::Error(_) => Ok(Some(true)),
+                ResultStatus::Error(_, _) => Ok(Some(true)),
                 _ => Ok(Some(false)),
             }
         } else {
@@ -50,6 +83,15 @@ impl History {
         }
     }
 
+    /// The kind of error the BEA reported for the last request by `app`.  Returns `None` if the
+    /// request has no recorded error, or failed without a response from the BEA API.  Use it to
+    /// tell a selection with no published data apart from a malformed request or a server
+    /// problem.
+    pub fn error_kind(&self, app: &App) -> Result<Option<ApiErrorKind>, BeaErr> {
+        let path = app.destination(false)?;
+        Ok(self.get(&path).and_then(|event| event.error_kind()))
+    }
+
     /// The `with_mode` method filters entries by the provided `mode`, returning the subset of
     /// entries where the mode of the event matches `mode`.
     pub fn with_mode(&self, mode: Mode) -> Self {
@@ -61,6 +103,9 @@ impl History {
     pub fn summary(&self) {
         let mut success = 0;
         let mut error = 0;
+        let mut no_data = 0;
+        let mut request = 0;
+        let mut server = 0;
         let mut total_size = 0;
         for value in self.values() {
             match value.status() {
@@ -70,12 +115,21 @@ impl History {
                         total_size += size;
                     }
                 }
-                ResultStatus::Error(_) => error += 1,
+                ResultStatus::Error(_, kind) => {
+                    error += 1;
+                    match kind {
+                        Some(kind) if kind.is_no_data() => no_data += 1,
+                        Some(kind) if kind.is_request_error() => request += 1,
+                        Some(kind) if kind.is_server_error() => server += 1,
+                        _ => {}
+                    }
+                }
                 _ => {}
             }
         }
         tracing::info!("Successes: {success}");
         tracing::info!("Errors: {error}");
+        tracing::info!("No data: {no_data}, invalid request: {request}, server: {server}");
         tracing::info!("Total Size: {}", bytesize::ByteSize::b(total_size));
     }
 
@@ -130,11 +184,28 @@ impl From<Vec<Event>> for History {
 impl TryFrom<&std::path::PathBuf> for History {
     type Error = BeaErr;
 
+    /// Reads the history logged in the file at `path`.  Fails if `path` does not name a file,
+    /// such as `..` or `/`.
     fn try_from(path: &std::path::PathBuf) -> Result<Self, Self::Error> {
+        let Some(name) = path.file_name() else {
+            let error = std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "history path does not name a file",
+            );
+            return Err(IoError::new(path.clone(), error, line!(), file!().into()).into());
+        };
+        let root = path.parent().unwrap_or(std::path::Path::new(""));
+        let storage = FsStorage::new(root.into());
+        Self::from_key(&storage, &StorageKey::from_path(name))
+    }
+}
+
+impl History {
+    /// Parses the JSON events logged one per line in `contents`, keeping the last event for each
+    /// destination.
+    pub fn parse(contents: &str) -> Result<Self, BeaErr> {
         let mut events = std::collections::BTreeMap::new();
-        let file = std::fs::read_to_string(path)
-            .map_err(|e| IoError::new(path.into(), e, line!(), file!().into()))?;
-        for line in file.lines() {
+        for line in contents.lines() {
             tracing::trace!("String: {line}");
             let json: serde_json::Value = serde_json::from_str(line)
                 .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
@@ -146,19 +217,6 @@ impl TryFrom<&std::path::PathBuf> for History {
     }
 }
 
-impl TryFrom<(Dataset, Mode)> for History {
-    type Error = BeaErr;
-
-    fn try_from(ctx: (Dataset, Mode)) -> Result<Self, Self::Error> {
-        dotenvy::dotenv().ok();
-        let (dataset, mode) = ctx;
-        let path = bea_data()?;
-        let path = path.join("history");
-        let path = path.join(format!("history_{dataset}_{mode}.log"));
-        Self::try_from(&path)
-    }
-}
-
 pub struct BucketIter {
     start: usize,
     end: usize,
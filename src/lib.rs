@@ -1,42 +1,69 @@
 #![doc(html_playground_url = "https://play.rust-lang.org/")]
 mod app;
+mod builder;
+#[cfg(feature = "check")]
 pub mod check;
+mod client;
 pub mod command;
 mod config;
 mod data;
 mod dataset;
+mod deflate;
 mod error;
+mod filter;
 mod free;
 mod history;
 mod json;
+mod measure;
 mod method;
 mod naics;
 mod parameter;
 mod parameter_value;
+mod plan;
+mod profile;
+mod progress;
 mod queue;
+mod rebase;
 mod request;
 mod results;
-mod validate;
+mod rules;
+mod search;
+mod series;
+mod storage;
+mod stream;
+mod table;
+pub mod validate;
 mod value;
 
 pub use app::{App, ResultStatus};
+pub use builder::{MneRequest, NipaRequest, RegionalRequest};
+pub use client::{BeaClient, CONNECT_TIMEOUT, REQUEST_TIMEOUT};
 pub use config::{Options, ParameterKind};
-pub use data::{Data, NipaData};
+pub use data::{
+    Data, Datum, FixedAssetData, FixedAssetDatum, MneDiData, MneDiDatum, NipaData, NipaDatum,
+};
 pub use dataset::{Dataset, Datasets};
+pub use deflate::Deflator;
 pub use error::{
-    AnnotationMissing, BTreeKeyMissing, BeaErr, BeaErrorKind, BincodeError, BoolInvalid, Check,
-    Csv, DatasetMissing, DeriveFromStr, EnvError, FromStrError, IntegerInvalid, InvestmentInvalid,
-    IoError, Jiff, JsonParseError, JsonParseErrorKind, KeyMissing, Nom, NotArray, NotFloat,
-    NotInteger, NotObject, NotParameterName, NotQuarter, OwnershipInvalid,
-    ParameterValueTableVariant, ParseFloat, ParseInt, ParseInteger, RateLimit, ReqwestError,
-    RowCodeMissing, SerdeJson, Set, UrlParseError, VariantMissing, YearInvalid,
+    AnnotationMissing, ApiFailure, BTreeKeyMissing, BaseSuppressed, BeaErr, BeaErrorKind,
+    BincodeError, BoolInvalid, Check, ConfigMissing, Csv, DatasetMissing, DeriveFromStr, EnvError,
+    ErrorClass, FrequencyMismatch, FromStrError, IntegerInvalid, InvestmentInvalid, IoError, Jiff,
+    JsonParseError, JsonParseErrorKind, KeyMissing, MultipleValues, NamesMismatch, Nom, NotArray,
+    NotFloat, NotInteger, NotObject, NotParameterName, NotQuarter, OwnershipInvalid,
+    ParameterMissing, ParameterValueTableVariant, ParamsInvalid, ParseFloat, ParseInt,
+    ParseInteger, PeriodInvalid, ProfileMissing, RateLimit, ReqwestError, RowCodeMissing,
+    SerdeJson, Set, StorageStatus, Toml, UnitMismatch, UrlParseError, ValueInvalid, VariantMissing,
+    YearInvalid,
 };
+pub use filter::{value_dependencies, Prune, Pruned, ValueFilter};
 pub use free::{
-    bea_data, file_size, from_csv, init, json_bool, json_str, map_to_bool, map_to_float,
-    map_to_int, map_to_string, to_csv, trace_init,
+    bea_data, closest, file_size, from_csv, init, json_bool, json_str, map_to_bool, map_to_float,
+    map_to_int, map_to_optional_float, map_to_string, to_csv, trace_init, trace_init_at,
+    SUPPRESSED,
 };
 pub use history::History;
 pub use json::Json;
+pub use measure::{IndexKind, Measure, Unit};
 pub use method::Method;
 pub use naics::Naics;
 pub use parameter::{deserialize_bool, Parameter, ParameterName, Parameters};
@@ -44,15 +71,32 @@ pub use parameter_value::{
     Metadata, MneDoi, NipaFrequency, NipaShowMillions, NipaTable, NipaYear, ParameterFields,
     ParameterValueTable, ParameterValues,
 };
-pub use queue::{Event, Mode, Queue, Tracker};
+pub use plan::{Action, Plan, Step};
+pub use profile::{
+    ExportFormat, ExportOptions, Overrides, Profile, Profiles, Settings, BEA_URL, CONFIG_FILE,
+};
+pub use progress::{Progress, Reporter};
+pub use queue::{Event, Mode, Queue, RateLimits, Tracker};
 pub use request::{Request, RequestParameter, RequestParameters};
-pub use results::{BeaResponse, Results};
+pub use results::{ApiError, ApiErrorKind, BeaResponse, MneError, RequestsExceeded, Results};
+pub use rules::{Catalog, Rule, Rules};
+pub use search::{Entry, Hit, Location, SearchIndex};
+pub use series::{Aggregation, Discrepancy, Measurement, Series, SeriesCollection};
+pub use storage::{
+    Backend, FsStorage, MemoryStorage, S3Storage, Staged, Storage, StorageKey, EMPTY_SHA256,
+};
+pub use stream::{outline, DataStream, Record, STREAM_BUFFER};
+pub use table::{Hierarchy, Residual, TableLayout, TableLine, TableView, ADDITIVITY_TOLERANCE};
+/// Token stopping a queue download or load, re-exported from `tokio_util`.
+pub use tokio_util::sync::CancellationToken;
+pub use validate::{Invalid, Validate};
 pub use value::{
     date_by_period, parse_year, AffiliateKind, AffiliateLevel, AnnotatedInteger, Annotation,
     ApiMetadata, BoolOptions, DirectionOfInvestment, FixedAssets, Footnotes, Frequencies,
     Frequency, FrequencyOptions, GdpByIndustry, Iip, InputOutput, Integer, IntegerKind,
     IntegerOptions, IntlServSta, IntlServTrade, InvestmentKind, Ita, Millions, MillionsOptions,
     Mne, NiUnderlyingDetail, Nipa, NipaIterator, NipaRange, NipaRangeIterator, NipaRanges,
-    OwnershipKind, OwnershipLevel, Regional, RowCode, SelectionKind, State, StateKind, TableName,
-    UnderlyingGdpByIndustry, ValueSet, ValueSets, Year, YearKind, YearOptions, YearRange,
+    OwnershipKind, OwnershipLevel, Period, PeriodRange, Regional, RowCode, SelectionKind, State,
+    StateKind, TableName, UnderlyingGdpByIndustry, ValueSet, ValueSets, Year, YearKind,
+    YearOptions, YearRange,
 };
@@ -1,6 +1,8 @@
-use bears::{trace_init, BeaErr};
+use bears::{
+    trace_init_at, BeaErr, BeaErrorKind, CancellationToken, ExportFormat, History, NipaData,
+    Overrides, Reporter, SearchIndex, SeriesCollection, Settings, TableView,
+};
 use clap::Parser;
-// use indicatif::ProgressBar;
 use tracing::{info, trace};
 
 #[derive(Parser)]
@@ -10,26 +12,177 @@ struct Cli {
     command: String,
     #[arg(short = 's', long, help = "Source of file.")]
     source: Option<std::path::PathBuf>,
+    #[arg(short = 'q', long, help = "Keywords or series code to search for.")]
+    query: Option<String>,
+    #[arg(
+        short = 'l',
+        long,
+        default_value_t = 20,
+        help = "Maximum number of search results."
+    )]
+    limit: usize,
+    #[arg(
+        long,
+        help = "Configuration file, in place of BEA_CONFIG or bears.toml."
+    )]
+    config: Option<std::path::PathBuf>,
+    #[arg(
+        short = 'p',
+        long,
+        help = "Configuration profile, in place of BEA_PROFILE."
+    )]
+    profile: Option<String>,
+    #[arg(long, help = "API key, in place of API_KEY.")]
+    key: Option<String>,
+    #[arg(long, help = "BEA url, in place of BEA_URL.")]
+    url: Option<url::Url>,
+    #[arg(long, help = "Data root, in place of BEA_DATA.")]
+    data: Option<std::path::PathBuf>,
+    #[arg(
+        long,
+        help = "Format of exported tables, csv or json, in place of BEA_EXPORT_FORMAT."
+    )]
+    export_format: Option<ExportFormat>,
+    #[arg(long, help = "Folder receiving exports, in place of BEA_EXPORT_DIR.")]
+    export_dir: Option<std::path::PathBuf>,
+    #[arg(long, help = "Replace files already downloaded.")]
+    overwrite: bool,
+    #[arg(
+        long,
+        help = "Print the download plan and time estimate without sending requests."
+    )]
+    dry_run: bool,
+}
+
+impl Cli {
+    /// The settings given on the command line.
+    fn overrides(&self) -> Overrides {
+        Overrides::new(
+            self.config.clone(),
+            self.profile.clone(),
+            self.key.clone(),
+            self.url.clone(),
+            self.data.clone(),
+            self.export_format,
+            self.export_dir.clone(),
+        )
+    }
 }
 
 #[tokio::main]
-async fn main() -> Result<(), BeaErr> {
-    trace_init()?;
-    dotenvy::dotenv().ok();
-    trace!("Environmental variables loaded.");
-
-    // let url = EnvError::from_env("BEA_URL")?;
-    // let url = UrlParseError::into_url(&url)?;
-    // let key = EnvError::from_env("API_KEY")?;
-    //
-    // let style = indicatif::ProgressStyle::with_template(
-    //     "[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {'Downloading data.'}",
-    // )
-    // .unwrap();
+async fn main() -> std::process::ExitCode {
+    match run().await {
+        Ok(()) => std::process::ExitCode::SUCCESS,
+        Err(source) => {
+            source.alert();
+            eprintln!("{source}");
+            std::process::ExitCode::from(source.class().exit_code())
+        }
+    }
+}
+
+/// Executes the command selected on the command line.
+async fn run() -> Result<(), BeaErr> {
     let cli = Cli::parse();
+    let settings = Settings::resolve(&cli.overrides())?;
+    trace_init_at(settings.data())?;
+    trace!("Settings resolved.");
+
     match &cli.command as &str {
-        "download" => tracing::info!("Download not implemented."),
+        "download" => download(&settings, cli.overwrite, cli.dry_run).await?,
+        "search" => match &cli.query {
+            Some(query) => search(query, cli.limit, cli.source.as_ref(), &settings)?,
+            None => info!("Search requires a query."),
+        },
+        "export" => match &cli.source {
+            Some(source) => export(source, &settings)?,
+            None => info!("Export requires a source file."),
+        },
         _ => info!("Command not recognized."),
     };
     Ok(())
 }
+
+/// Downloads each dataset of the profile in `settings` that has a download queue, drawing a
+/// progress bar for each.  Requests with an error the BEA would repeat are skipped, as planned by
+/// [`Queue::plan`](bears::Queue::plan), and `dry_run` prints the plan instead of downloading.
+/// Pressing Ctrl-C stops the download gracefully, recording the requests in flight as cancelled
+/// in the history.
+async fn download(settings: &Settings, overwrite: bool, dry_run: bool) -> Result<(), BeaErr> {
+    let client = settings.client()?;
+    let history = History::from_root(client.root())?;
+    let cancel = CancellationToken::new();
+    let interrupt = cancel.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            eprintln!("Stopping download, cancelling requests in flight.");
+            interrupt.cancel();
+        }
+    });
+    for dataset in settings.datasets() {
+        if cancel.is_cancelled() {
+            break;
+        }
+        let queue = match dataset.queue_with(&client) {
+            Ok(queue) => queue,
+            Err(source) if matches!(&**source, BeaErrorKind::DatasetMissing(_)) => {
+                info!("No download queue for {dataset}.");
+                continue;
+            }
+            Err(source) => return Err(source),
+        };
+        let plan = queue.plan(&client, &history, None, overwrite).await?;
+        if dry_run {
+            println!("{dataset}\n{plan}\n");
+            continue;
+        }
+        let reporter = Reporter::bar(dataset.to_string());
+        let progress = queue
+            .download_planned(&client, &plan, &reporter, &cancel)
+            .await?;
+        println!("{dataset} {progress}");
+    }
+    Ok(())
+}
+
+/// Searches the offline catalog under `source`, or the data root of `settings` if unset, printing
+/// where `query` is published if it names a series, followed by the tables and lines ranked by
+/// relevance to its keywords.
+fn search(
+    query: &str,
+    limit: usize,
+    source: Option<&std::path::PathBuf>,
+    settings: &Settings,
+) -> Result<(), BeaErr> {
+    let path = source.unwrap_or(settings.data());
+    let index = SearchIndex::load(path)?;
+    trace!("{} entries indexed.", index.len());
+    let locations = index.locate(query.trim());
+    if !locations.is_empty() {
+        println!("Series {} is published in:", query.trim());
+        for location in &locations {
+            println!("  {location}");
+        }
+    }
+    let hits = index.search(query, limit);
+    if hits.is_empty() && locations.is_empty() {
+        println!("No matches for {query}.");
+    }
+    for hit in &hits {
+        println!("{:>4}  {}", hit.score(), hit.entry());
+    }
+    Ok(())
+}
+
+/// Exports each table and frequency in the NIPA data file `source` as a [`TableView`], in the
+/// export format and folder of `settings`, printing the path of each file written.
+fn export(source: &std::path::PathBuf, settings: &Settings) -> Result<(), BeaErr> {
+    let data = NipaData::try_from(source)?;
+    let collection = SeriesCollection::from(&data);
+    let directory = settings.export_directory();
+    for view in TableView::tables(&collection) {
+        let path = view.export(*settings.export().format(), &directory)?;
+        println!("{}", path.display());
+    }
+    Ok(())
+}
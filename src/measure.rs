@@ -0,0 +1,329 @@
+use crate::UnitMismatch;
+
+/// The `IndexKind` enum distinguishes the different index numbers published by the BEA.
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    strum::EnumIter,
+    derive_more::Display,
+)]
+pub enum IndexKind {
+    /// Chain-type (Fisher) quantity index.
+    #[display("Quantity Index")]
+    Quantity,
+    /// Chain-type (Fisher) price index.
+    #[display("Price Index")]
+    Price,
+    /// Ratio of current-dollar to chained-dollar values, times 100.
+    #[display("Implicit Price Deflator")]
+    ImplicitPriceDeflator,
+    /// Any other index number.
+    #[display("Index")]
+    Other,
+}
+
+/// The `Unit` enum is the typed reading of the `CL_UNIT` and `METRIC_NAME` fields of a BEA
+/// response.
+///
+/// The BEA reports dollar values as a `CL_UNIT` of "Level", leaving the `METRIC_NAME` to
+/// distinguish current from chained dollars, so both fields inform the parse.  Values that do not
+/// match a known category are preserved in the `Other` variant.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_more::Display,
+)]
+pub enum Unit {
+    #[display("Current Dollars")]
+    CurrentDollars,
+    #[display("Chained Dollars")]
+    ChainedDollars,
+    #[display("Constant Dollars")]
+    ConstantDollars,
+    #[display("{_0}")]
+    Index(IndexKind),
+    #[display("Percent")]
+    Percent,
+    #[display("Percent change")]
+    PercentChange,
+    #[display("Percent change, annual rate")]
+    PercentChangeAnnualRate,
+    #[display("Percentage points")]
+    PercentagePoints,
+    #[display("Persons")]
+    Persons,
+    #[display("Hours")]
+    Hours,
+    #[display("{_0}")]
+    Other(String),
+}
+
+impl Unit {
+    /// Parses the `CL_UNIT` and `METRIC_NAME` fields of a data record into a `Unit`.
+    ///
+    /// ```
+    /// use bears::{IndexKind, Unit};
+    ///
+    /// assert_eq!(Unit::from_parts("Level", "Current Dollars"), Unit::CurrentDollars);
+    /// assert_eq!(Unit::from_parts("Level", "Chained Dollars"), Unit::ChainedDollars);
+    /// assert_eq!(
+    ///     Unit::from_parts("Fisher Price Index", "Fisher Price Index"),
+    ///     Unit::Index(IndexKind::Price)
+    /// );
+    /// assert_eq!(
+    ///     Unit::from_parts("Percent change, annual rate", "Fisher Quantity Index"),
+    ///     Unit::PercentChangeAnnualRate
+    /// );
+    /// ```
+    pub fn from_parts(cl_unit: &str, metric_name: &str) -> Self {
+        let unit = cl_unit.to_lowercase();
+        let metric = metric_name.to_lowercase();
+        // The unit of measure takes precedence when it names a rate or an index.
+        if unit.contains("percent change") {
+            if unit.contains("annual rate") {
+                return Self::PercentChangeAnnualRate;
+            }
+            return Self::PercentChange;
+        }
+        if unit.contains("percentage point") {
+            return Self::PercentagePoints;
+        }
+        if unit.contains("percent") {
+            return Self::Percent;
+        }
+        if let Some(kind) = Self::index_kind(&unit) {
+            return Self::Index(kind);
+        }
+        // A "Level" unit defers to the metric name.
+        if metric.contains("chained") {
+            Self::ChainedDollars
+        } else if metric.contains("constant") {
+            Self::ConstantDollars
+        } else if metric.contains("dollar") {
+            Self::CurrentDollars
+        } else if let Some(kind) = Self::index_kind(&metric) {
+            Self::Index(kind)
+        } else if metric.contains("percent") {
+            Self::Percent
+        } else if metric.contains("person") || metric.contains("employ") {
+            Self::Persons
+        } else if metric.contains("hour") {
+            Self::Hours
+        } else if metric_name.is_empty() {
+            Self::Other(cl_unit.to_string())
+        } else {
+            Self::Other(metric_name.to_string())
+        }
+    }
+
+    fn index_kind(value: &str) -> Option<IndexKind> {
+        if value.contains("deflator") {
+            Some(IndexKind::ImplicitPriceDeflator)
+        } else if value.contains("quantity index") {
+            Some(IndexKind::Quantity)
+        } else if value.contains("price index") {
+            Some(IndexKind::Price)
+        } else if value.contains("index") {
+            Some(IndexKind::Other)
+        } else {
+            None
+        }
+    }
+
+    /// Indicates `true` if the unit denominates a dollar amount.
+    pub fn is_dollars(&self) -> bool {
+        matches!(
+            self,
+            Self::CurrentDollars | Self::ChainedDollars | Self::ConstantDollars
+        )
+    }
+
+    /// Indicates `true` if the unit is an index number.
+    pub fn is_index(&self) -> bool {
+        matches!(self, Self::Index(_))
+    }
+
+    /// Indicates `true` if the unit is a percent or rate of change.
+    pub fn is_rate(&self) -> bool {
+        matches!(
+            self,
+            Self::Percent
+                | Self::PercentChange
+                | Self::PercentChangeAnnualRate
+                | Self::PercentagePoints
+        )
+    }
+
+    /// Indicates `true` if values in this unit can be summed.  Chained dollars are excluded
+    /// because the BEA does not publish them as additive, and the sum of two indexes or two rates
+    /// has no meaning.
+    pub fn is_additive(&self) -> bool {
+        matches!(
+            self,
+            Self::CurrentDollars | Self::ConstantDollars | Self::Persons | Self::Hours
+        )
+    }
+}
+
+impl Default for Unit {
+    fn default() -> Self {
+        Self::Other(String::new())
+    }
+}
+
+/// The `Measure` struct pairs an observed value with its [`Unit`] and unit multiplier.
+///
+/// The BEA reports values scaled by a power of ten given in the `UNIT_MULT` field, so that a
+/// `value` of 1.5 with a `multiplier` of 6 represents 1,500,000.  Arithmetic and comparison
+/// methods rescale to a common multiplier before operating, and refuse to combine measures of
+/// different units, so you can never confuse units in millions with thousands or single dollars.
+///
+/// ```
+/// use bears::{Measure, Unit};
+///
+/// let millions = Measure::new(1.5, Unit::CurrentDollars, 6);
+/// let thousands = Measure::new(500.0, Unit::CurrentDollars, 3);
+/// let sum = millions.checked_add(&thousands).unwrap();
+/// assert_eq!(sum.scaled(), 2_000_000.0);
+///
+/// let index = Measure::new(100.0, Unit::Index(bears::IndexKind::Price), 0);
+/// assert!(millions.checked_add(&index).is_err());
+/// assert!(millions.partial_cmp(&index).is_none());
+/// ```
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_new::new,
+    derive_getters::Getters,
+)]
+pub struct Measure {
+    /// Value as reported, before applying the multiplier.
+    value: f64,
+    unit: Unit,
+    /// Power of ten applied to `value`, from the `UNIT_MULT` field.
+    multiplier: i64,
+}
+
+impl Measure {
+    /// Returns the value with the unit multiplier applied.
+    pub fn scaled(&self) -> f64 {
+        self.value * Self::factor(self.multiplier)
+    }
+
+    /// Expresses the measure in terms of a different `multiplier`, preserving the scaled value.
+    pub fn with_multiplier(&self, multiplier: i64) -> Self {
+        let value = self.scaled() / Self::factor(multiplier);
+        Self::new(value, self.unit.clone(), multiplier)
+    }
+
+    fn factor(multiplier: i64) -> f64 {
+        10_f64.powi(multiplier as i32)
+    }
+
+    /// Returns an error unless `self` and `other` share the same [`Unit`].
+    pub fn check_unit(&self, other: &Self, operation: &str) -> Result<(), UnitMismatch> {
+        if self.unit == other.unit {
+            Ok(())
+        } else {
+            let error = UnitMismatch::new(
+                self.unit.to_string(),
+                other.unit.to_string(),
+                operation.to_string(),
+                line!(),
+                file!().to_string(),
+            );
+            Err(error)
+        }
+    }
+
+    fn check_additive(&self, other: &Self, operation: &str) -> Result<(), UnitMismatch> {
+        self.check_unit(other, operation)?;
+        if self.unit.is_additive() {
+            Ok(())
+        } else {
+            let error = UnitMismatch::new(
+                self.unit.to_string(),
+                other.unit.to_string(),
+                format!("{operation} (unit is not additive)"),
+                line!(),
+                file!().to_string(),
+            );
+            Err(error)
+        }
+    }
+
+    /// Adds `other` to `self`, expressed in the multiplier of `self`.  Fails if the units differ
+    /// or if the unit is not additive.
+    pub fn checked_add(&self, other: &Self) -> Result<Self, UnitMismatch> {
+        self.check_additive(other, "add")?;
+        let other = other.with_multiplier(self.multiplier);
+        Ok(Self::new(
+            self.value + other.value,
+            self.unit.clone(),
+            self.multiplier,
+        ))
+    }
+
+    /// Subtracts `other` from `self`, expressed in the multiplier of `self`.  Fails if the units
+    /// differ or if the unit is not additive.
+    pub fn checked_sub(&self, other: &Self) -> Result<Self, UnitMismatch> {
+        self.check_additive(other, "subtract")?;
+        let other = other.with_multiplier(self.multiplier);
+        Ok(Self::new(
+            self.value - other.value,
+            self.unit.clone(),
+            self.multiplier,
+        ))
+    }
+
+    /// Compares the scaled values of `self` and `other`.  Fails if the units differ.
+    pub fn try_cmp(&self, other: &Self) -> Result<std::cmp::Ordering, UnitMismatch> {
+        self.check_unit(other, "compare")?;
+        Ok(self.scaled().total_cmp(&other.scaled()))
+    }
+}
+
+impl PartialEq for Measure {
+    /// Measures are equal when they share a unit and their scaled values match.
+    fn eq(&self, other: &Self) -> bool {
+        self.unit == other.unit && self.scaled() == other.scaled()
+    }
+}
+
+impl PartialOrd for Measure {
+    /// Measures of different units have no ordering.
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        if self.unit == other.unit {
+            self.scaled().partial_cmp(&other.scaled())
+        } else {
+            None
+        }
+    }
+}
+
+impl std::fmt::Display for Measure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.multiplier {
+            0 => write!(f, "{} {}", self.value, self.unit),
+            mult => write!(f, "{} x 10^{} {}", self.value, mult, self.unit),
+        }
+    }
+}
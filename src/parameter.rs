@@ -6,7 +6,19 @@ use serde::de::Deserializer;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Deserialize,
+    Serialize,
+    derive_getters::Getters,
+)]
 #[serde(rename_all = "PascalCase")]
 pub struct Parameter {
     #[serde(skip_serializing_if = "Option::is_none")]
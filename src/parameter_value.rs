@@ -628,6 +628,21 @@ impl ParameterValueTable {
         let error = JsonParseErrorKind::from(error);
         Err(error.into())
     }
+
+    /// The value to pass for the parameter in a request, or `None` for tables that describe a
+    /// range of values, such as [`NipaYear`].
+    pub fn key(&self) -> Option<String> {
+        match self {
+            Self::Metadata(t) => Some(t.dataset().to_string()),
+            Self::MneDoi(t) => Some(t.key().clone()),
+            Self::NipaFrequency(t) => Some(t.frequency_id().clone()),
+            Self::NipaShowMillions(t) => Some(t.show_millions_id().clone()),
+            Self::NipaTable(t) => Some(t.table_name().clone()),
+            Self::NipaTableNumber(t) => Some(t.table_number.clone()),
+            Self::NipaYear(_) => None,
+            Self::ParameterFields(t) => Some(t.key().clone()),
+        }
+    }
 }
 
 /// Thin wrapper around a vector of type [`ParameterValueTable`].
@@ -0,0 +1,200 @@
+use crate::{ApiErrorKind, App, BeaErr, RateLimits};
+
+/// Query parameters left out of the request description of a [`Step`], as every request in a
+/// queue shares them, and the key must not appear in a saved plan.
+const SHARED_PARAMS: [&str; 4] = ["USERID", "METHOD", "DATASETNAME", "RESULTFORMAT"];
+
+/// What a download of the [`Queue`](crate::Queue) would do with a request, as decided by
+/// [`Queue::plan`](crate::Queue::plan).
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    strum::EnumIter,
+    derive_more::Display,
+)]
+pub enum Action {
+    /// The request would be sent.
+    Download,
+    /// The file exists and overwrite is off.
+    Exists,
+    /// The history records an error the BEA would repeat, such as no published data or an
+    /// invalid parameter.  Server errors and failed connections are downloaded again.
+    KnownError,
+    /// The exclusion history passed to [`Queue::plan`](crate::Queue::plan) holds the destination.
+    Excluded,
+}
+
+impl Action {
+    /// Indicates `true` if the request would not be sent.
+    pub fn is_skip(&self) -> bool {
+        !matches!(self, Self::Download)
+    }
+}
+
+/// The `Step` struct is one request of a [`Plan`].
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_getters::Getters,
+)]
+pub struct Step {
+    /// The query parameters distinguishing the request, such as `TableName=T10101`.
+    request: String,
+    /// The destination of the response, see [`App::destination`].
+    path: std::path::PathBuf,
+    action: Action,
+    /// The kind of the recorded error, for [`Action::KnownError`].
+    error: Option<ApiErrorKind>,
+    /// Size of the last successful download of the request in the history, if any.
+    size: Option<u64>,
+}
+
+impl Step {
+    /// Describes the request `app` with `action`, the recorded `error` and the expected `size`.
+    pub fn new(
+        app: &App,
+        action: Action,
+        error: Option<ApiErrorKind>,
+        size: Option<u64>,
+    ) -> Result<Self, BeaErr> {
+        let request = app
+            .query()
+            .iter()
+            .filter(|(key, _)| !SHARED_PARAMS.contains(&key.to_uppercase().as_str()))
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect::<Vec<String>>()
+            .join(" ");
+        Ok(Self {
+            request,
+            path: app.destination(false)?,
+            action,
+            error,
+            size,
+        })
+    }
+}
+
+/// The `Plan` struct lists what a download of a [`Queue`](crate::Queue) would do, without sending
+/// any request: the destination of each request, whether it would be skipped and why, its
+/// expected size, and the time the download would take under the rate limits.  Create one with
+/// [`Queue::plan`](crate::Queue::plan).
+///
+/// The plan serializes, so it can be saved for review, and displays as a table.  Requests
+/// without a recorded size count at the median size of the successful downloads in the history.
+#[derive(
+    Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, derive_getters::Getters,
+)]
+pub struct Plan {
+    steps: Vec<Step>,
+    /// Size assumed for requests without a recorded size.
+    typical_size: u64,
+    limits: RateLimits,
+    overwrite: bool,
+}
+
+impl Plan {
+    /// Creates a plan for `steps` under `limits`, assuming `typical_size` for requests without a
+    /// recorded size.
+    pub fn new(steps: Vec<Step>, typical_size: u64, limits: RateLimits, overwrite: bool) -> Self {
+        Self {
+            steps,
+            typical_size,
+            limits,
+            overwrite,
+        }
+    }
+
+    /// The steps that would send a request.
+    pub fn downloads(&self) -> impl Iterator<Item = &Step> {
+        self.steps
+            .iter()
+            .filter(|step| step.action == Action::Download)
+    }
+
+    /// Number of steps taking `action`.
+    pub fn count(&self, action: Action) -> usize {
+        self.steps
+            .iter()
+            .filter(|step| step.action == action)
+            .count()
+    }
+
+    /// The destinations the plan would download to, for looking up requests by path.
+    pub fn download_paths(&self) -> std::collections::HashSet<&std::path::Path> {
+        self.downloads().map(|step| step.path.as_path()).collect()
+    }
+
+    /// Expected size of a step, the recorded size or else the typical size.
+    pub fn expected_size(&self, step: &Step) -> u64 {
+        step.size.unwrap_or(self.typical_size)
+    }
+
+    /// Expected total size of the downloads.
+    pub fn total_size(&self) -> u64 {
+        self.downloads().map(|step| self.expected_size(step)).sum()
+    }
+
+    /// Estimated wall-clock time of the download.  The [`Tracker`](crate::Tracker) admits up to
+    /// the call and byte limits in each minute, so the estimate is the number of minutes needed to
+    /// send every call and receive every byte, whichever is greater.
+    pub fn estimate(&self) -> std::time::Duration {
+        let calls = self.downloads().count();
+        if calls == 0 {
+            return std::time::Duration::ZERO;
+        }
+        let by_calls = calls.div_ceil((*self.limits.calls()).max(1)) as u64;
+        let by_bytes = self.total_size().div_ceil((*self.limits.bytes()).max(1));
+        std::time::Duration::from_secs(60 * by_calls.max(by_bytes))
+    }
+}
+
+impl std::fmt::Display for Plan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:<10} {:>10}  {:<40}  Path",
+            "Action", "Size", "Request"
+        )?;
+        for step in &self.steps {
+            let action = match step.error {
+                Some(kind) => format!("{}({kind})", step.action),
+                None => step.action.to_string(),
+            };
+            let size = match step.size {
+                Some(size) => indicatif::HumanBytes(size).to_string(),
+                None => "?".to_string(),
+            };
+            writeln!(
+                f,
+                "{action:<10} {size:>10}  {:<40}  {}",
+                step.request,
+                step.path.display()
+            )?;
+        }
+        write!(
+            f,
+            "{} to download ({} expected, about {}), {} existing, {} known errors, {} excluded",
+            self.count(Action::Download),
+            indicatif::HumanBytes(self.total_size()),
+            indicatif::HumanDuration(self.estimate()),
+            self.count(Action::Exists),
+            self.count(Action::KnownError),
+            self.count(Action::Excluded)
+        )
+    }
+}
@@ -0,0 +1,408 @@
+use crate::{
+    BeaClient, BeaErr, ConfigMissing, Dataset, DeriveFromStr, IoError, ProfileMissing, RateLimits,
+    Toml, UrlParseError,
+};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+
+/// Url of the BEA REST server, used when no flag, variable or profile sets one.
+pub const BEA_URL: &str = "https://apps.bea.gov/api/data";
+
+/// Name of the configuration file read from the working directory when neither the `--config`
+/// flag nor the `BEA_CONFIG` variable names one.
+pub const CONFIG_FILE: &str = "bears.toml";
+
+/// Format of files written when exporting series and tables.
+#[derive(
+    Debug,
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_more::Display,
+    derive_more::FromStr,
+    strum::EnumIter,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    #[display("csv")]
+    Csv,
+    #[display("json")]
+    Json,
+}
+
+impl ExportFormat {
+    /// Extension of the files written in this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// The `ExportOptions` struct holds the defaults for exporting series and tables.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_new::new,
+    derive_getters::Getters,
+)]
+#[serde(default)]
+pub struct ExportOptions {
+    format: ExportFormat,
+    /// Folder receiving exports, relative to the data root unless absolute.  Defaults to the
+    /// `export` folder.
+    directory: Option<std::path::PathBuf>,
+}
+
+/// The `Profile` struct is a named set of settings in the configuration file.  Every field is
+/// optional, and settings from the command line or the environment take precedence.
+///
+/// ```toml
+/// [profile.staging]
+/// key_file = "/etc/bears/staging.key"
+/// url = "https://staging.example.com/api/data"
+/// data = "/srv/bea/staging"
+/// datasets = ["Nipa", "Regional"]
+///
+/// [profile.staging.limits]
+/// calls = 50
+///
+/// [profile.staging.export]
+/// format = "json"
+/// ```
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_getters::Getters,
+)]
+#[serde(default)]
+pub struct Profile {
+    /// User API key granted by the BEA.
+    key: Option<String>,
+    /// File holding the API key, read if `key` is unset.  Keeps the key out of the
+    /// configuration file.
+    key_file: Option<std::path::PathBuf>,
+    url: Option<url::Url>,
+    /// Data root, holding the downloads and caches otherwise found under `BEA_DATA`.
+    data: Option<std::path::PathBuf>,
+    limits: Option<RateLimits>,
+    /// Datasets mirrored by default.  Empty for every dataset.
+    datasets: Vec<Dataset>,
+    export: Option<ExportOptions>,
+}
+
+/// The `Profiles` struct is the contents of the TOML configuration file, a set of named
+/// [`Profile`] sections, and the name of the profile used when none is selected.
+///
+/// ```
+/// use bears::Profiles;
+///
+/// let contents = r#"
+/// default = "local"
+///
+/// [profile.local]
+/// url = "http://localhost:8080/api/data"
+/// data = "mock"
+/// "#;
+/// let profiles = Profiles::parse(contents, "bears.toml").unwrap();
+/// let local = profiles.profile(None).unwrap().unwrap();
+/// assert_eq!(local.url().as_ref().unwrap().port(), Some(8080));
+/// ```
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_getters::Getters,
+)]
+#[serde(default)]
+pub struct Profiles {
+    /// Name of the profile used when neither the `--profile` flag nor the `BEA_PROFILE`
+    /// variable selects one.
+    default: Option<String>,
+    #[serde(rename = "profile")]
+    profiles: BTreeMap<String, Profile>,
+    /// File the profiles were read from.
+    #[serde(skip)]
+    path: std::path::PathBuf,
+}
+
+impl Profiles {
+    /// Parses the TOML `contents` of the configuration file at `path`.
+    pub fn parse<P: AsRef<std::path::Path>>(contents: &str, path: P) -> Result<Self, BeaErr> {
+        let path = path.as_ref().to_path_buf();
+        let mut profiles: Self = toml::from_str(contents)
+            .map_err(|e| Toml::new(path.clone(), e, line!(), file!().to_string()))?;
+        profiles.path = path;
+        Ok(profiles)
+    }
+
+    /// Reads the configuration file at `path`.
+    pub fn from_path<P: AsRef<std::path::Path>>(path: P) -> Result<Self, BeaErr> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| IoError::new(path.into(), e, line!(), file!().into()))?;
+        Self::parse(&contents, path)
+    }
+
+    /// The profile called `name`, or the default profile if `name` is `None`.  Returns `None`
+    /// if no name is given and the file sets no default, and fails if the named profile is
+    /// absent.
+    pub fn profile(&self, name: Option<&str>) -> Result<Option<&Profile>, ProfileMissing> {
+        let Some(name) = name.or(self.default.as_deref()) else {
+            return Ok(None);
+        };
+        match self.profiles.get(name) {
+            Some(profile) => Ok(Some(profile)),
+            None => Err(ProfileMissing::new(
+                name.to_string(),
+                self.path.clone(),
+                line!(),
+                file!().to_string(),
+            )),
+        }
+    }
+}
+
+/// The `Overrides` struct holds the settings given on the command line, which take precedence
+/// over the environment and the configuration profile.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    PartialEq,
+    Eq,
+    derive_new::new,
+    derive_getters::Getters,
+    derive_setters::Setters,
+)]
+#[setters(prefix = "with_", into, strip_option, borrow_self)]
+pub struct Overrides {
+    /// Configuration file, in place of `BEA_CONFIG` or `bears.toml`.
+    config: Option<std::path::PathBuf>,
+    /// Profile name, in place of `BEA_PROFILE` or the default profile.
+    profile: Option<String>,
+    key: Option<String>,
+    url: Option<url::Url>,
+    data: Option<std::path::PathBuf>,
+    export_format: Option<ExportFormat>,
+    /// Folder receiving exports, in place of `BEA_EXPORT_DIR` or the profile.
+    export_dir: Option<std::path::PathBuf>,
+}
+
+/// The `Settings` struct is the configuration in effect, resolved from each source in order:
+/// command line flags, then environmental variables, then the selected [`Profile`], then the
+/// defaults.
+///
+/// | Setting       | Flag              | Variable            | Profile             | Default       |
+/// |---------------|-------------------|---------------------|---------------------|---------------|
+/// | key           | `--key`           | `API_KEY`           | `key` or `key_file` | none          |
+/// | url           | `--url`           | `BEA_URL`           | `url`               | [`BEA_URL`]   |
+/// | data          | `--data`          | `BEA_DATA`          | `data`              | none          |
+/// | limits        |                   |                     | `limits`            | BEA limits    |
+/// | datasets      |                   |                     | `datasets`          | every dataset |
+/// | export format | `--export-format` | `BEA_EXPORT_FORMAT` | `export.format`     | csv           |
+/// | export folder | `--export-dir`    | `BEA_EXPORT_DIR`    | `export.directory`  | `export`      |
+///
+/// The profile comes from the file named by `--config`, `BEA_CONFIG` or `bears.toml` in the
+/// working directory, selected by `--profile`, `BEA_PROFILE` or the `default` key of the file.
+///
+/// Library code resolves the same settings as the command line, and builds its client from them:
+///
+/// ```no_run
+/// use bears::{Overrides, Request, Settings};
+///
+/// # fn main() -> Result<(), bears::BeaErr> {
+/// let client = Settings::resolve(&Overrides::default())?.client()?;
+/// let app = client.request(Request::Dataset);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, derive_getters::Getters)]
+pub struct Settings {
+    /// Name of the profile applied, if any.
+    profile: Option<String>,
+    /// API key given by flag, variable or profile, see [`Settings::api_key`].
+    #[getter(skip)]
+    key: Option<String>,
+    /// File holding the API key, from the profile, read only if no key is given.
+    #[getter(skip)]
+    key_file: Option<std::path::PathBuf>,
+    url: url::Url,
+    data: std::path::PathBuf,
+    limits: RateLimits,
+    datasets: Vec<Dataset>,
+    export: ExportOptions,
+}
+
+impl Settings {
+    /// Resolves the settings from `overrides`, the environment and the configuration file,
+    /// reading the .env file if present.
+    pub fn resolve(overrides: &Overrides) -> Result<Self, BeaErr> {
+        dotenvy::dotenv().ok();
+        let env = |name: &str| std::env::var(name).ok();
+        let path = overrides
+            .config
+            .clone()
+            .or_else(|| env("BEA_CONFIG").map(std::path::PathBuf::from));
+        let name = overrides.profile.clone().or_else(|| env("BEA_PROFILE"));
+        let profiles = match path {
+            Some(path) => Some(Profiles::from_path(path)?),
+            None => {
+                let path = std::path::PathBuf::from(CONFIG_FILE);
+                match path.exists() {
+                    true => Some(Profiles::from_path(path)?),
+                    false => None,
+                }
+            }
+        };
+        let profile = match &profiles {
+            Some(profiles) => {
+                let name = name.as_deref().or(profiles.default.as_deref());
+                profiles
+                    .profile(name)?
+                    .map(|profile| (name.unwrap_or_default().to_string(), profile))
+            }
+            None => match name {
+                Some(name) => {
+                    let error =
+                        ProfileMissing::new(name, CONFIG_FILE.into(), line!(), file!().to_string());
+                    return Err(error.into());
+                }
+                None => None,
+            },
+        };
+        Self::from_sources(overrides, env, profile)
+    }
+
+    /// Resolves the settings from `overrides`, the variables returned by `env` and the named
+    /// `profile`, in that order of precedence.
+    pub fn from_sources<F: Fn(&str) -> Option<String>>(
+        overrides: &Overrides,
+        env: F,
+        profile: Option<(String, &Profile)>,
+    ) -> Result<Self, BeaErr> {
+        let (name, profile) = match profile {
+            Some((name, profile)) => (Some(name), profile.clone()),
+            None => (None, Profile::default()),
+        };
+        if let Some(name) = &name {
+            tracing::info!("Using profile {name}.");
+        }
+        let key = overrides
+            .key
+            .clone()
+            .or_else(|| env("API_KEY"))
+            .or(profile.key);
+        let url = match overrides.url.clone() {
+            Some(url) => url,
+            None => match env("BEA_URL") {
+                Some(url) => url::Url::parse(&url)
+                    .map_err(|source| UrlParseError::new(url, source, line!(), file!().into()))?,
+                None => match profile.url {
+                    Some(url) => url,
+                    None => url::Url::parse(BEA_URL).map_err(|source| {
+                        UrlParseError::new(BEA_URL.into(), source, line!(), file!().into())
+                    })?,
+                },
+            },
+        };
+        let data = overrides
+            .data
+            .clone()
+            .or_else(|| env("BEA_DATA").map(std::path::PathBuf::from))
+            .or(profile.data)
+            .ok_or_else(|| {
+                ConfigMissing::new("BEA_DATA".to_string(), line!(), file!().to_string())
+            })?;
+        let profile_export = profile.export.unwrap_or_default();
+        let format = match overrides.export_format {
+            Some(format) => format,
+            None => match env("BEA_EXPORT_FORMAT") {
+                Some(format) => ExportFormat::from_str(&format).map_err(|source| {
+                    DeriveFromStr::new(format, source, line!(), file!().into())
+                })?,
+                None => profile_export.format,
+            },
+        };
+        let directory = overrides
+            .export_dir
+            .clone()
+            .or_else(|| env("BEA_EXPORT_DIR").map(std::path::PathBuf::from))
+            .or(profile_export.directory);
+        let datasets = match profile.datasets.is_empty() {
+            true => Dataset::iter().collect(),
+            false => profile.datasets,
+        };
+        Ok(Self {
+            profile: name,
+            key,
+            key_file: profile.key_file,
+            url,
+            data,
+            limits: profile.limits.unwrap_or_default(),
+            datasets,
+            export: ExportOptions::new(format, directory),
+        })
+    }
+
+    /// Folder receiving exports, resolved against the data root.
+    pub fn export_directory(&self) -> std::path::PathBuf {
+        match &self.export.directory {
+            Some(directory) => self.data.join(directory),
+            None => self.data.join("export"),
+        }
+    }
+
+    /// The API key, read from the `key_file` of the profile if no flag, variable or profile sets
+    /// the key.  The file is read here rather than on resolving, so settings used only for the
+    /// data root never touch it.
+    pub fn api_key(&self) -> Result<Option<String>, BeaErr> {
+        if let Some(key) = &self.key {
+            return Ok(Some(key.clone()));
+        }
+        match &self.key_file {
+            Some(path) => {
+                let key = std::fs::read_to_string(path)
+                    .map_err(|e| IoError::new(path.clone(), e, line!(), file!().into()))?;
+                Ok(Some(key.trim().to_string()))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Creates a [`BeaClient`] using the settings.  Fails if no API key is set.
+    pub fn client(&self) -> Result<BeaClient, BeaErr> {
+        let key = self.api_key()?.ok_or_else(|| {
+            ConfigMissing::new("API_KEY".to_string(), line!(), file!().to_string())
+        })?;
+        let client = BeaClient::new(key, self.url.clone(), self.data.clone())?;
+        Ok(client.with_limits(self.limits))
+    }
+}
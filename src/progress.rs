@@ -0,0 +1,221 @@
+use crate::{Mode, ResultStatus};
+
+/// Template of the progress bar drawn by [`Reporter::bar`].
+const TEMPLATE: &str = "{prefix} [{elapsed_precise}] {bar:40.cyan/blue} {pos:>5}/{len:5} {msg}";
+
+/// The `Progress` struct is a snapshot of a running [`Queue`](crate::Queue) download or load,
+/// passed to the [`Reporter`] after each request completes.
+///
+/// Requests skipped because the file already exists count toward `finished` but not toward the
+/// rate or the time estimate, which only consider requests sent to the server.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, derive_getters::Getters)]
+pub struct Progress {
+    mode: Mode,
+    /// Number of requests in the queue.
+    total: usize,
+    /// Requests completed successfully.
+    done: usize,
+    /// Requests skipped, such as files already downloaded.
+    skipped: usize,
+    /// Requests failing with an error.
+    failed: usize,
+    /// Requests stopped by cancellation before they completed.
+    cancelled: usize,
+    /// Bytes downloaded or loaded by successful requests.
+    bytes: u64,
+    /// Calls available within the rate limit of the [`Tracker`](crate::Tracker), recorded for
+    /// downloads only.
+    slack: Option<usize>,
+    #[getter(skip)]
+    started: std::time::Instant,
+}
+
+impl Progress {
+    /// Creates the progress of a queue of `total` requests in `mode`, starting now.
+    pub fn new(mode: Mode, total: usize) -> Self {
+        Self {
+            mode,
+            total,
+            done: 0,
+            skipped: 0,
+            failed: 0,
+            cancelled: 0,
+            bytes: 0,
+            slack: None,
+            started: std::time::Instant::now(),
+        }
+    }
+
+    /// Counts the outcome `status` of a request.
+    pub fn update(&mut self, status: &ResultStatus) {
+        match status {
+            ResultStatus::Success(_, size) => {
+                self.done += 1;
+                self.bytes += size;
+            }
+            ResultStatus::Error(_, _) => self.failed += 1,
+            ResultStatus::Pass(_) => self.skipped += 1,
+            ResultStatus::Cancelled(_) => self.cancelled += 1,
+            ResultStatus::Pending | ResultStatus::Abort => {}
+        }
+    }
+
+    /// Records the calls available within the rate limit.
+    pub fn with_slack(&mut self, slack: usize) {
+        self.slack = Some(slack);
+    }
+
+    /// Requests completed, skipped, failed or cancelled.
+    pub fn finished(&self) -> usize {
+        self.done + self.skipped + self.failed + self.cancelled
+    }
+
+    /// Requests not sent or not completed because the queue was cancelled.
+    pub fn unfinished(&self) -> usize {
+        self.cancelled + self.remaining()
+    }
+
+    /// Requests yet to complete.
+    pub fn remaining(&self) -> usize {
+        self.total.saturating_sub(self.finished())
+    }
+
+    /// Time since the queue started.
+    pub fn elapsed(&self) -> std::time::Duration {
+        self.started.elapsed()
+    }
+
+    /// Bytes per second received by successful requests.
+    pub fn rate(&self) -> f64 {
+        let seconds = self.elapsed().as_secs_f64();
+        match seconds > 0.0 {
+            true => self.bytes as f64 / seconds,
+            false => 0.0,
+        }
+    }
+
+    /// Estimated time remaining, assuming the remaining requests take as long on average as the
+    /// requests sent so far.  `None` until a request completes.
+    pub fn eta(&self) -> Option<std::time::Duration> {
+        let sent = self.done + self.failed;
+        if sent == 0 {
+            return None;
+        }
+        let remaining = self.remaining() as u32;
+        Some(self.elapsed() / sent as u32 * remaining)
+    }
+
+    /// Summary of the counts, rate, slack and estimate, used as the message of the progress bar.
+    pub fn message(&self) -> String {
+        let mut message = format!(
+            "{} at {}/s, {} errors",
+            indicatif::HumanBytes(self.bytes),
+            indicatif::HumanBytes(self.rate() as u64),
+            self.failed
+        );
+        if let Some(slack) = self.slack {
+            message.push_str(&format!(", slack {slack}"));
+        }
+        if let Some(eta) = self.eta() {
+            message.push_str(&format!(", eta {}", indicatif::HumanDuration(eta)));
+        }
+        message
+    }
+}
+
+impl std::fmt::Display for Progress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} of {} finished, {} skipped, {}",
+            self.mode,
+            self.finished(),
+            self.total,
+            self.skipped,
+            self.message()
+        )?;
+        if self.cancelled > 0 {
+            write!(f, ", {} cancelled", self.cancelled)?;
+        }
+        Ok(())
+    }
+}
+
+/// The `Reporter` enum receives the [`Progress`] of a [`Queue`](crate::Queue) download or load
+/// after each request completes.
+///
+/// The CLI draws a terminal progress bar with [`Reporter::bar`].  Library users driving their
+/// own interface can pass a closure to [`Reporter::callback`].
+///
+/// ```
+/// use bears::{Mode, Progress, Reporter, ResultStatus};
+///
+/// let finished = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+/// let count = finished.clone();
+/// let reporter = Reporter::callback(move |progress: &Progress| {
+///     count.store(progress.finished(), std::sync::atomic::Ordering::Relaxed);
+/// });
+/// let mut progress = Progress::new(Mode::Download, 2);
+/// progress.update(&ResultStatus::Success(uuid::Uuid::new_v4(), 1024));
+/// reporter.report(&progress);
+/// assert_eq!(finished.load(std::sync::atomic::Ordering::Relaxed), 1);
+/// ```
+#[derive(Clone, Default)]
+pub enum Reporter {
+    /// Reports nothing.
+    #[default]
+    Silent,
+    /// Draws a progress bar to the terminal.
+    Bar(indicatif::ProgressBar),
+    /// Calls the closure with each update.
+    Callback(std::sync::Arc<dyn Fn(&Progress) + Send + Sync>),
+}
+
+impl Reporter {
+    /// Creates a progress bar labelled `prefix`, drawn to standard error if it is a terminal.
+    pub fn bar<S: Into<String>>(prefix: S) -> Self {
+        let style = indicatif::ProgressStyle::with_template(TEMPLATE)
+            .unwrap_or_else(|_| indicatif::ProgressStyle::default_bar());
+        let bar = indicatif::ProgressBar::new(0)
+            .with_style(style)
+            .with_prefix(prefix.into());
+        Self::Bar(bar)
+    }
+
+    /// Calls `callback` with the progress after each request completes.
+    pub fn callback<F: Fn(&Progress) + Send + Sync + 'static>(callback: F) -> Self {
+        Self::Callback(std::sync::Arc::new(callback))
+    }
+
+    /// Reports `progress`.
+    pub fn report(&self, progress: &Progress) {
+        match self {
+            Self::Silent => {}
+            Self::Bar(bar) => {
+                bar.set_length(*progress.total() as u64);
+                bar.set_position(progress.finished() as u64);
+                bar.set_message(progress.message());
+            }
+            Self::Callback(callback) => callback(progress),
+        }
+    }
+
+    /// Completes the progress bar with the final `progress` of the queue, leaving it on screen.
+    /// Callbacks have already received the final update.
+    pub fn finish(&self, progress: &Progress) {
+        if let Self::Bar(bar) = self {
+            self.report(progress);
+            bar.finish();
+        }
+    }
+}
+
+impl std::fmt::Debug for Reporter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Silent => write!(f, "Silent"),
+            Self::Bar(bar) => f.debug_tuple("Bar").field(bar).finish(),
+            Self::Callback(_) => write!(f, "Callback"),
+        }
+    }
+}
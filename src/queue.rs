@@ -1,9 +1,13 @@
 use crate::{
-    file_size, map_to_string, App, BeaErr, Data, DeriveFromStr, History, Jiff, JsonParseError,
-    JsonParseErrorKind, KeyMissing, NotObject, ParseInt, ResultStatus,
+    map_to_string, Action, ApiErrorKind, App, Backend, BeaClient, BeaErr, BeaErrorKind, Data,
+    DeriveFromStr, ErrorClass, FsStorage, History, Invalid, Jiff, JsonParseError,
+    JsonParseErrorKind, KeyMissing, NotObject, ParseInt, Plan, Progress, RateLimit, Reporter,
+    ResultStatus, Step, Storage, Validate,
 };
+
 use jiff::ToSpan;
 use std::str::FromStr;
+use tokio_util::sync::CancellationToken;
 
 // Cannot exceed 30 errors per minute.
 // Theory: calls may get ahead of tracker
@@ -14,6 +18,15 @@ pub const ERROR_CAP: usize = 10;
 // Cannot exceed 100 calls per minute.
 // 14 ahead of 85 = 99
 pub const CALL_CAP: usize = 10;
+// Cannot exceed 100MB of data per minute.
+pub const SIZE_CAP: u64 = 100_000_000;
+// Attempts made for a download failing with a retryable error, such as a timeout.
+pub const RETRIES: u32 = 3;
+// Pause before retrying a download, multiplied by the number of attempts so far.
+pub const RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+
+// Data read by the tasks of a queue load.
+type Loaded = std::sync::Arc<tokio::sync::Mutex<Vec<Data>>>;
 
 #[derive(
     Debug,
@@ -29,6 +42,12 @@ pub const CALL_CAP: usize = 10;
 pub struct Queue(Vec<App>);
 
 impl Queue {
+    /// The storage root of the requests, taken from the first request, or `None` if the queue is
+    /// empty.
+    pub fn storage_root(&self) -> Option<&std::path::PathBuf> {
+        self.first().map(|app| app.storage_root())
+    }
+
     #[tracing::instrument(skip_all)]
     /// Subset of queue that contains a success status.
     pub fn exclude(&mut self, history: &History) -> Result<(), BeaErr> {
@@ -40,7 +59,10 @@ impl Queue {
     #[tracing::instrument(skip_all)]
     /// Subset of queue that contains a success status.
     pub fn successes(&mut self, strict: bool) -> Result<(), BeaErr> {
-        let history = History::from_env()?;
+        let Some(root) = self.storage_root() else {
+            return Ok(());
+        };
+        let history = History::from_root(root)?;
         history.summary();
         self.retain(|app| history.is_success(app).unwrap_or(None).unwrap_or(!strict));
         Ok(())
@@ -55,7 +77,10 @@ impl Queue {
 
     #[tracing::instrument(skip_all)]
     pub fn active_subset(&mut self, strict: bool) -> Result<(), BeaErr> {
-        let history = History::from_env()?;
+        let Some(root) = self.storage_root() else {
+            return Ok(());
+        };
+        let history = History::from_root(root)?;
         history.summary();
         self.retain(|app| match history.is_error(app) {
             Ok(opt) => match opt {
@@ -70,6 +95,29 @@ impl Queue {
         Ok(())
     }
 
+    /// Removes each request with a parameter value absent from the cached values of `validate`,
+    /// returning the requests removed.  Requests bound to fail then do not spend the rate limit.
+    pub fn validate(&mut self, validate: &Validate) -> Result<Vec<Invalid>, BeaErr> {
+        let invalid = validate.queue(self)?;
+        if !invalid.is_empty() {
+            tracing::warn!(
+                "Removing {} invalid requests from the queue.",
+                invalid.len()
+            );
+            let indices = invalid
+                .iter()
+                .map(|i| *i.index())
+                .collect::<std::collections::BTreeSet<usize>>();
+            let mut index = 0;
+            self.retain(|_| {
+                let keep = !indices.contains(&index);
+                index += 1;
+                keep
+            });
+        }
+        Ok(invalid)
+    }
+
     /// Filters the `Queue` for members with a destination path matching the [`Event`] path in
     /// 'event'.
     pub fn with_event(&mut self, event: &Event) {
@@ -85,19 +133,166 @@ impl Queue {
     #[tracing::instrument(skip_all)]
     pub async fn download(&self, overwrite: bool) -> Result<(), BeaErr> {
         let tracker = std::sync::Arc::new(tokio::sync::Mutex::new(Tracker::default()));
+        let (Some(root), Some(app)) = (self.storage_root(), self.first()) else {
+            return Ok(());
+        };
+        // Clones share the connection pool of the client.
+        let http = crate::client::shared_http(app.url()).clone();
+        let storage = Backend::from(FsStorage::new(root.clone()));
+        let cancel = CancellationToken::new();
+        self.download_tracked(
+            http,
+            storage,
+            tracker,
+            overwrite,
+            &Reporter::default(),
+            &cancel,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Downloads the queue using the connection pool, storage and rate limiter of `client`.  Queues
+    /// downloaded with the same client share one rate limit, as the BEA server applies its limits
+    /// to the API key rather than to each queue.
+    #[tracing::instrument(skip_all)]
+    pub async fn download_with(&self, client: &BeaClient, overwrite: bool) -> Result<(), BeaErr> {
+        let cancel = CancellationToken::new();
+        self.download_reported(client, overwrite, &Reporter::default(), &cancel)
+            .await?;
+        Ok(())
+    }
+
+    /// Downloads the queue using `client`, passing the [`Progress`] to `reporter` after each
+    /// request completes.  Returns the final progress.
+    ///
+    /// Cancelling `cancel` stops the download gracefully: no further requests are sent, requests
+    /// in flight are dropped before their file is written, and every request sent reaches the
+    /// history, as [`ResultStatus::Cancelled`] if it did not complete, before the method returns.
+    #[tracing::instrument(skip_all)]
+    pub async fn download_reported(
+        &self,
+        client: &BeaClient,
+        overwrite: bool,
+        reporter: &Reporter,
+        cancel: &CancellationToken,
+    ) -> Result<Progress, BeaErr> {
+        self.download_tracked(
+            client.http().clone(),
+            client.storage().clone(),
+            client.tracker().clone(),
+            overwrite,
+            reporter,
+            cancel,
+        )
+        .await
+    }
+
+    async fn download_tracked(
+        &self,
+        http: reqwest::Client,
+        storage: Backend,
+        tracker: std::sync::Arc<tokio::sync::Mutex<Tracker>>,
+        overwrite: bool,
+        reporter: &Reporter,
+        cancel: &CancellationToken,
+    ) -> Result<Progress, BeaErr> {
         let (tx, mut rx) = tokio::sync::mpsc::channel(29);
-        let download = self.downloader(tx, tracker.clone(), overwrite);
-        let listen = Self::listen(&mut rx, tracker.clone(), Mode::Download);
+        // cancelled by the downloader on hitting the rate limit, without cancelling the caller
+        let abort = cancel.child_token();
+        let download =
+            self.downloader(http, storage, tx, tracker.clone(), overwrite, abort.clone());
+        let progress = Progress::new(Mode::Download, self.len());
+        let listen = Self::listen_reported(&mut rx, tracker.clone(), progress, reporter);
         let (download_res, listen_res) = tokio::join!(download, listen);
         // listen_res?;
         if let Err(blame) = download_res {
             tracing::warn!("Problem with call: {blame}");
         }
-        if let Err(blame) = listen_res {
-            tracing::warn!("Probelm with tracking: {blame}");
-            return Err(blame);
+        match listen_res {
+            Ok(_) if abort.is_cancelled() && !cancel.is_cancelled() => {
+                let error = RateLimit::new(
+                    "Download aborted on exceeding the BEA rate limit".to_string(),
+                    line!(),
+                    file!().to_string(),
+                );
+                Err(error.into())
+            }
+            Ok(progress) => Ok(progress),
+            Err(blame) => {
+                tracing::warn!("Probelm with tracking: {blame}");
+                Err(blame)
+            }
+        }
+    }
+
+    /// Downloads the requests of the queue that `plan` would send, as
+    /// [`Queue::download_reported`] with the overwrite setting of the plan.  Requests the plan
+    /// skips, or that are absent from the plan, are not sent.
+    #[tracing::instrument(skip_all)]
+    pub async fn download_planned(
+        &self,
+        client: &BeaClient,
+        plan: &Plan,
+        reporter: &Reporter,
+        cancel: &CancellationToken,
+    ) -> Result<Progress, BeaErr> {
+        let paths = plan.download_paths();
+        let mut queue = self.clone();
+        queue.retain(|app| {
+            app.destination(false)
+                .is_ok_and(|path| paths.contains(path.as_path()))
+        });
+        queue
+            .download_reported(client, *plan.overwrite(), reporter, cancel)
+            .await
+    }
+
+    /// Plans a download of the queue with `client` without sending any request, see [`Plan`].
+    /// Reads the recorded errors and sizes from `history`, and skips the destinations held in
+    /// `exclude`, as [`Queue::exclude`] would.  Existing files are skipped unless `overwrite` is
+    /// set, as in [`Queue::download`].
+    pub async fn plan(
+        &self,
+        client: &BeaClient,
+        history: &History,
+        exclude: Option<&History>,
+        overwrite: bool,
+    ) -> Result<Plan, BeaErr> {
+        let mut sizes = history
+            .values()
+            .filter(|event| matches!(event.status(), ResultStatus::Success(_, _)))
+            .filter_map(|event| *event.length())
+            .collect::<Vec<u64>>();
+        // the median recorded size, or zero without a record
+        let middle = sizes.len() / 2;
+        let typical_size = if sizes.is_empty() {
+            0
+        } else {
+            *sizes.select_nth_unstable(middle).1
+        };
+        let limits = *client.tracker().lock().await.limits();
+        let mut steps = Vec::new();
+        for app in self.iter() {
+            let path = app.destination(false)?;
+            let recorded = history.get(&path);
+            let size = recorded
+                .filter(|event| matches!(event.status(), ResultStatus::Success(_, _)))
+                .and_then(|event| *event.length());
+            let error = recorded.and_then(|event| event.error_kind());
+            let action = if exclude.is_some_and(|exclude| exclude.contains(&path)) {
+                Action::Excluded
+            } else if !overwrite && client.storage().exists(&app.storage_key()?).await? {
+                Action::Exists
+            } else if error.is_some_and(|kind| kind.is_no_data() || kind.is_request_error()) {
+                Action::KnownError
+            } else {
+                Action::Download
+            };
+            let error = error.filter(|_| action == Action::KnownError);
+            steps.push(Step::new(app, action, error, size)?);
         }
-        Ok(())
+        Ok(Plan::new(steps, typical_size, limits, overwrite))
     }
 
     #[tracing::instrument(skip_all)]
@@ -106,27 +301,52 @@ impl Queue {
         tracker: std::sync::Arc<tokio::sync::Mutex<Tracker>>,
         mode: Mode,
     ) -> Result<(), BeaErr> {
+        let progress = Progress::new(mode, 0);
+        Self::listen_reported(rx, tracker, progress, &Reporter::default()).await?;
+        Ok(())
+    }
+
+    /// Records each status received on `rx` in `tracker`, as [`Queue::listen`], and counts it in
+    /// `progress`, passing the update to `reporter`.  Downloads also report the slack remaining
+    /// in the rate limit.  Returns the final progress once every sender has dropped.
+    #[tracing::instrument(skip_all)]
+    pub async fn listen_reported(
+        rx: &mut tokio::sync::mpsc::Receiver<ResultStatus>,
+        tracker: std::sync::Arc<tokio::sync::Mutex<Tracker>>,
+        mut progress: Progress,
+        reporter: &Reporter,
+    ) -> Result<Progress, BeaErr> {
+        let mode = *progress.mode();
+        reporter.report(&progress);
         while let Some(status) = rx.recv().await {
+            progress.update(&status);
             match status {
-                ResultStatus::Success(_, _) | ResultStatus::Error(_) => {
+                ResultStatus::Success(_, _)
+                | ResultStatus::Error(_, _)
+                | ResultStatus::Cancelled(_) => {
                     let mut tracker = tracker.lock().await;
                     tracker.update_status(status, mode);
                     tracing::info!("Update: {status}.");
+                    if mode == Mode::Download {
+                        progress.with_slack(tracker.check_slack());
+                    }
+                    reporter.report(&progress);
                 }
-                ResultStatus::Pass(_) | ResultStatus::Pending => {}
+                ResultStatus::Pass(_) => reporter.report(&progress),
+                ResultStatus::Pending => {}
                 ResultStatus::Abort => {
                     tracing::info!("Abort detected.");
-                    // let error = RateLimit::new(
-                    //     "RequestsExceeded".to_string(),
-                    //     line!(),
-                    //     file!().to_string(),
-                    // );
-                    // return Err(error.into());
-                    panic!("Limit rate exceeded.")
+                    let error = RateLimit::new(
+                        "RequestsExceeded".to_string(),
+                        line!(),
+                        file!().to_string(),
+                    );
+                    return Err(error.into());
                 }
             }
         }
-        Ok(())
+        reporter.finish(&progress);
+        Ok(progress)
     }
 
     /// The download will move to idle if the user has exceeded a rate limiting condition from BEA:
@@ -134,19 +354,40 @@ impl Queue {
     /// * More than 100 requests per minute.
     /// * More than 100MB of data volume retrieved per minute.
     /// * More than 30 errors per minute.
+    ///
+    /// Downloads failing with a retryable error, such as a timeout, are attempted up to
+    /// [`RETRIES`] times.  See [`BeaErr::is_retryable`].  A download exceeding the rate limit is
+    /// never retried.  Instead it cancels `cancel`, aborting the rest of the queue, since further
+    /// calls only extend the lockout imposed by the BEA.
+    ///
+    /// Once `cancel` is cancelled, no further requests are sent, and tasks in flight stop at
+    /// their next await point, reporting [`ResultStatus::Cancelled`].  Files are only written
+    /// after the full response arrives, so a stopped task leaves no partial file.
     #[tracing::instrument(skip_all)]
     pub async fn downloader(
         &self,
+        http: reqwest::Client,
+        storage: Backend,
         tx: tokio::sync::mpsc::Sender<ResultStatus>,
         tracker: std::sync::Arc<tokio::sync::Mutex<Tracker>>,
         overwrite: bool,
+        cancel: CancellationToken,
     ) -> Result<Vec<tokio::task::JoinHandle<()>>, BeaErr> {
         let mut futures = Vec::new();
-        for app in self.iter() {
+        for (index, app) in self.iter().enumerate() {
+            if cancel.is_cancelled() {
+                tracing::info!(
+                    "Download cancelled, {} requests not sent.",
+                    self.len() - index
+                );
+                break;
+            }
             let app = app.clone();
+            let http = http.clone();
+            let storage = storage.clone();
             let tx = tx.clone();
             let path = app.destination(false)?;
-            let path_check = path.exists();
+            let path_check = storage.exists(&app.storage_key()?).await?;
             // tracing::info!("Exists: {path_check} - {path:?}");
             if !path_check || overwrite {
                 let event = Event::new(&path, Mode::Download);
@@ -165,7 +406,10 @@ impl Queue {
                     {
                         // Scoped to release lock before checking for slack
                         let tracker = tracker.lock().await;
-                        tracker.wait().await;
+                        tokio::select! {
+                            _ = tracker.wait() => {}
+                            _ = cancel.cancelled() => break,
+                        }
                     }
                     {
                         // Scoped to release lock before leaving the loop
@@ -174,6 +418,9 @@ impl Queue {
                         size_available = tracker.size_available();
                     }
                 }
+                if cancel.is_cancelled() {
+                    continue;
+                }
                 {
                     let mut tracker = tracker.lock().await;
                     // If the size is known, add it the size events
@@ -183,14 +430,44 @@ impl Queue {
                     tracker.calls.push(event);
                 }
 
+                let cancel = cancel.clone();
+                let abort = cancel.clone();
                 let fut = tokio::spawn(async move {
-                    let mut result = ResultStatus::Pass(id);
                     tracing::info!("Calling download for {path:#?}");
-                    if let Ok(status) = app.download(id).await {
-                        result = status;
-                    } else {
-                        tracing::error!("Request failure.");
-                    }
+                    let attempts = async {
+                        let mut attempt = 0;
+                        loop {
+                            match app.download_with(&http, &storage, id).await {
+                                Ok(status) => break status,
+                                Err(source) => {
+                                    source.alert();
+                                    if source.class() == ErrorClass::RateLimit {
+                                        tracing::error!("Rate limit exceeded, aborting queue.");
+                                        abort.cancel();
+                                        let kind = ApiErrorKind::RequestsExceeded;
+                                        break ResultStatus::Error(id, Some(kind));
+                                    }
+                                    attempt += 1;
+                                    if !source.is_retryable() || attempt >= RETRIES {
+                                        let kind = match &**source {
+                                            BeaErrorKind::ApiFailure(e) => Some(*e.kind()),
+                                            _ => None,
+                                        };
+                                        break ResultStatus::Error(id, kind);
+                                    }
+                                    tracing::info!("Retrying {path:?}, attempt {attempt}.");
+                                    tokio::time::sleep(RETRY_DELAY * attempt).await;
+                                }
+                            }
+                        }
+                    };
+                    let result = tokio::select! {
+                        status = attempts => status,
+                        _ = cancel.cancelled() => {
+                            tracing::info!("Cancelled download for {path:?}.");
+                            ResultStatus::Cancelled(id)
+                        }
+                    };
                     match tx.send(result).await {
                         Ok(_) => {}
                         Err(source) => {
@@ -199,22 +476,32 @@ impl Queue {
                     }
                 });
                 futures.push(fut);
+            } else if let Err(source) = tx.send(ResultStatus::Pass(uuid::Uuid::new_v4())).await {
+                tracing::error!("{source}");
             }
         }
         Ok(futures)
     }
 
+    /// Reads the response to each request from `storage`.  Once `cancel` is cancelled, no
+    /// further files are read.
     #[tracing::instrument(skip_all)]
     pub async fn loader(
         &self,
+        storage: Backend,
         data: std::sync::Arc<tokio::sync::Mutex<Vec<Data>>>,
         tx: tokio::sync::mpsc::Sender<ResultStatus>,
         tracker: std::sync::Arc<tokio::sync::Mutex<Tracker>>,
+        cancel: CancellationToken,
     ) -> Result<Vec<tokio::task::JoinHandle<()>>, BeaErr> {
         let mut handles = Vec::new();
-        for app in self.iter() {
+        for (index, app) in self.iter().enumerate() {
+            if cancel.is_cancelled() {
+                tracing::info!("Load cancelled, {} files not read.", self.len() - index);
+                break;
+            }
             let path = app.destination(false)?;
-            if path.exists() {
+            if storage.exists(&app.storage_key()?).await? {
                 let event = Event::new(&path, Mode::Load);
                 let id = *event.id();
                 {
@@ -226,11 +513,17 @@ impl Queue {
                 let data = data.clone();
                 let tx = tx.clone();
                 let app = app.clone();
+                let cancel = cancel.clone();
+                let storage = storage.clone();
                 let handle = tokio::spawn(async move {
                     tracing::info!("Calling load for {path:#?}");
                     let status;
-                    match app.load() {
-                        Ok(response) => {
+                    match app.load_sized(&storage).await {
+                        Ok(_) if cancel.is_cancelled() => {
+                            tracing::info!("Cancelled load for {path:?}.");
+                            status = ResultStatus::Cancelled(id);
+                        }
+                        Ok((response, size)) => {
                             if let Some(dataset) = response.data() {
                                 {
                                     // Scoped to release lock immediately after pushing update.
@@ -238,16 +531,15 @@ impl Queue {
                                     data.push(dataset);
                                     tracing::info!("Dataset loaded.");
                                 }
-                                let size = file_size(path).unwrap_or(0);
                                 status = ResultStatus::Success(id, size);
                             } else {
                                 tracing::error!("Load failure: Not Data type.");
-                                status = ResultStatus::Error(id);
+                                status = ResultStatus::Error(id, None);
                             }
                         }
                         Err(source) => {
-                            tracing::error!("{source}");
-                            status = ResultStatus::Error(id);
+                            source.alert();
+                            status = ResultStatus::Error(id, None);
                         }
                     }
                     match tx.send(status).await {
@@ -258,6 +550,8 @@ impl Queue {
                     }
                 });
                 handles.push(handle);
+            } else if let Err(source) = tx.send(ResultStatus::Pass(uuid::Uuid::new_v4())).await {
+                tracing::error!("{source}");
             }
         }
         Ok(handles)
@@ -265,20 +559,64 @@ impl Queue {
 
     #[tracing::instrument(skip_all)]
     pub async fn load(&self) -> Result<std::sync::Arc<tokio::sync::Mutex<Vec<Data>>>, BeaErr> {
+        let Some(root) = self.storage_root() else {
+            return Ok(Loaded::default());
+        };
+        let storage = Backend::from(FsStorage::new(root.clone()));
+        let cancel = CancellationToken::new();
+        let (data, _) = self
+            .load_reported(&storage, &Reporter::default(), &cancel)
+            .await?;
+        Ok(data)
+    }
+
+    /// Loads the queue from the storage of `client`, as saved by
+    /// [`Queue::download_with`].
+    #[tracing::instrument(skip_all)]
+    pub async fn load_with(
+        &self,
+        client: &BeaClient,
+    ) -> Result<std::sync::Arc<tokio::sync::Mutex<Vec<Data>>>, BeaErr> {
+        let cancel = CancellationToken::new();
+        let (data, _) = self
+            .load_reported(client.storage(), &Reporter::default(), &cancel)
+            .await?;
+        Ok(data)
+    }
+
+    /// Loads the queue from `storage`, passing the [`Progress`] to `reporter` after each file is read.  Returns
+    /// the data with the final progress.  Requests without a downloaded file count as skipped.
+    /// Cancelling `cancel` stops reading further files, returning the data read so far.
+    #[tracing::instrument(skip_all)]
+    pub async fn load_reported(
+        &self,
+        storage: &Backend,
+        reporter: &Reporter,
+        cancel: &CancellationToken,
+    ) -> Result<(Loaded, Progress), BeaErr> {
         let tracker = std::sync::Arc::new(tokio::sync::Mutex::new(Tracker::default()));
         let (tx, mut rx) = tokio::sync::mpsc::channel(100);
         let data = std::sync::Arc::new(tokio::sync::Mutex::new(Vec::new()));
-        let load = self.loader(data.clone(), tx, tracker.clone());
-        let listen = Self::listen(&mut rx, tracker.clone(), Mode::Load);
+        let load = self.loader(
+            storage.clone(),
+            data.clone(),
+            tx,
+            tracker.clone(),
+            cancel.clone(),
+        );
+        let progress = Progress::new(Mode::Load, self.len());
+        let listen = Self::listen_reported(&mut rx, tracker.clone(), progress, reporter);
         let (load_res, listen_res) = tokio::join!(load, listen);
         if let Err(blame) = load_res {
             tracing::warn!("Problem with load: {blame}");
         }
-        if let Err(blame) = listen_res {
-            tracing::warn!("Probelm with tracking: {blame}");
-            return Err(blame);
+        match listen_res {
+            Ok(progress) => Ok((data, progress)),
+            Err(blame) => {
+                tracing::warn!("Probelm with tracking: {blame}");
+                Err(blame)
+            }
         }
-        Ok(data)
     }
 }
 
@@ -337,6 +675,39 @@ impl From<u64> for SizeEvent {
     }
 }
 
+/// The `RateLimits` struct holds the caps observed by the [`Tracker`] over each minute of
+/// downloading.  The defaults keep below the limits of the BEA server, but a mock server or a
+/// staging key may allow different values.
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_new::new,
+    derive_getters::Getters,
+)]
+#[serde(default)]
+pub struct RateLimits {
+    /// Calls sent per minute.
+    calls: usize,
+    /// Errors received per minute.
+    errors: usize,
+    /// Bytes downloaded per minute.
+    bytes: u64,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self::new(CALL_CAP, ERROR_CAP, SIZE_CAP)
+    }
+}
+
 #[derive(
     Debug,
     Default,
@@ -357,9 +728,24 @@ pub struct Tracker {
     cache: Vec<Event>,
     // Total size field to track cumulative dowload size over the last minute.
     size: Vec<SizeEvent>,
+    #[serde(default)]
+    limits: RateLimits,
 }
 
 impl Tracker {
+    /// Creates a tracker enforcing the caps in `limits`.
+    pub fn with_limits(limits: RateLimits) -> Self {
+        Self {
+            limits,
+            ..Default::default()
+        }
+    }
+
+    /// The caps enforced by the tracker.
+    pub fn limits(&self) -> &RateLimits {
+        &self.limits
+    }
+
     #[tracing::instrument(skip_all)]
     pub fn update_status(&mut self, status: ResultStatus, mode: Mode) {
         match status {
@@ -380,7 +766,7 @@ impl Tracker {
                     }
                 }
             }
-            ResultStatus::Error(id) => {
+            ResultStatus::Error(id, _) => {
                 if let Some(event) = self.calls.iter_mut().find(|event| event.id == id) {
                     event.status = status;
                     match mode {
@@ -398,15 +784,30 @@ impl Tracker {
                     self.errors.push(event.clone());
                 }
             }
+            // Cancelled requests reach the history so they do not remain pending, but do not
+            // count against the error limit.
+            ResultStatus::Cancelled(id) => {
+                if let Some(event) = self
+                    .calls
+                    .iter_mut()
+                    .chain(self.cache.iter_mut())
+                    .find(|event| event.id == id)
+                {
+                    event.status = status;
+                    match mode {
+                        Mode::Download => tracing::info!(target: "download_history", "{event}"),
+                        Mode::Load => tracing::info!(target: "load_history", "{event}"),
+                    }
+                }
+            }
             ResultStatus::Pending | ResultStatus::Abort | ResultStatus::Pass(_) => {}
         }
     }
 
-    /// Remaining download capacity within the 100MB per minute rate limit set by the BEA server.
+    /// Remaining download capacity within the per minute rate limit, 100MB for the BEA server.
     #[tracing::instrument(skip_all)]
     pub fn size_available(&self) -> u64 {
-        // Download rate limit of 100MB.
-        100_000_000_u64.saturating_sub(self.total_size())
+        self.limits.bytes.saturating_sub(self.total_size())
     }
 
     #[tracing::instrument(skip_all)]
@@ -419,9 +820,9 @@ impl Tracker {
             .filter(|c| c.status == ResultStatus::Pending)
             .collect::<Vec<&Event>>()
             .len();
-        let pending_slack = ERROR_CAP.saturating_sub(pending);
-        let error_slack = ERROR_CAP.saturating_sub(self.errors.len());
-        let call_slack = CALL_CAP.saturating_sub(self.calls.len());
+        let pending_slack = self.limits.errors.saturating_sub(pending);
+        let error_slack = self.limits.errors.saturating_sub(self.errors.len());
+        let call_slack = self.limits.calls.saturating_sub(self.calls.len());
         let slack = error_slack.min(call_slack);
         let slack = slack.min(pending_slack);
         tracing::trace!("Pending slack {pending_slack}.");
@@ -574,6 +975,14 @@ impl Event {
         }
     }
 
+    /// The kind of error the BEA reported for the event, if the event failed with an API error.
+    pub fn error_kind(&self) -> Option<ApiErrorKind> {
+        match self.status {
+            ResultStatus::Error(_, kind) => kind,
+            _ => None,
+        }
+    }
+
     pub fn len_as_str(&self) -> String {
         match self.length {
             Some(num) => num.to_string(),
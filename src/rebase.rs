@@ -0,0 +1,81 @@
+use crate::{
+    BTreeKeyMissing, BaseSuppressed, BeaErr, IndexKind, Period, Series, Unit, UnitMismatch,
+};
+
+impl Series {
+    /// Rebases an index series so that it averages 100 over the `reference` period.  The reference
+    /// may be of a lower frequency than the series, so that a quarterly index can be rebased to a
+    /// reference year.  See [`Series::rebase_range`].
+    ///
+    /// ```
+    /// use bears::{IndexKind, Period, Series, Unit};
+    ///
+    /// let mut index = Series::default();
+    /// index.with_unit(Unit::Index(IndexKind::Quantity));
+    /// index.insert(Period::Year(2017), Some(100.0));
+    /// index.insert(Period::Year(2023), Some(120.0));
+    /// let rebased = index.rebase(Period::Year(2023)).unwrap();
+    /// assert_eq!(rebased.get(&Period::Year(2023)), Some(100.0));
+    /// assert_eq!(rebased.reference(), &Some((Period::Year(2023), Period::Year(2023))));
+    /// ```
+    pub fn rebase(&self, reference: Period) -> Result<Self, BeaErr> {
+        self.rebase_range(reference, reference)
+    }
+
+    /// Rebases an index series so that it averages 100 over the periods from `start` through
+    /// `end` inclusive, recording the new reference in the series metadata.
+    ///
+    /// Fails if the unit is not an index, if either end of the reference range is not a valid
+    /// period, if no observations fall within the reference range, or if any observation within
+    /// the range is suppressed.
+    pub fn rebase_range(&self, start: Period, end: Period) -> Result<Self, BeaErr> {
+        if !self.unit().is_index() {
+            let error = UnitMismatch::new(
+                self.unit().to_string(),
+                Unit::Index(IndexKind::Other).to_string(),
+                "rebase".to_string(),
+                line!(),
+                file!().to_string(),
+            );
+            return Err(error.into());
+        }
+        let (first, last) = (start.start()?, end.end()?);
+        let values = self
+            .observations()
+            .iter()
+            .filter(|(period, _)| match (period.start(), period.end()) {
+                (Ok(from), Ok(to)) => first <= from && to <= last,
+                _ => false,
+            })
+            .map(|(_, value)| *value)
+            .collect::<Option<Vec<f64>>>();
+        let Some(values) = values else {
+            let error = BaseSuppressed::new(
+                self.code().to_string(),
+                format!("{start} to {end}"),
+                line!(),
+                file!().to_string(),
+            );
+            return Err(error.into());
+        };
+        let level = match values.len() {
+            0 => 0.0,
+            len => values.iter().sum::<f64>() / len as f64,
+        };
+        if level == 0.0 {
+            let error = BTreeKeyMissing::new(
+                format!("{} for reference {start} to {end}", self.code()),
+                line!(),
+                file!().to_string(),
+            );
+            return Err(error.into());
+        }
+        let mut rebased = self.clone();
+        for (period, value) in self.observations() {
+            rebased.insert(*period, value.map(|value| value / level * 100.0));
+        }
+        rebased.with_reference((start, end));
+        tracing::trace!("Rebased {} to {start} through {end}.", self.code());
+        Ok(rebased)
+    }
+}
@@ -315,6 +315,17 @@ pub enum Request {
 }
 
 impl Request {
+    /// The API [`Method`] called by the request.
+    pub fn method(&self) -> Method {
+        match self {
+            Self::Data => Method::GetData,
+            Self::Dataset => Method::GetDataSetList,
+            Self::Parameter => Method::GetParameterList,
+            Self::ParameterValue => Method::GetParameterValues,
+            Self::ParameterValueFilter => Method::GetParameterValuesFiltered,
+        }
+    }
+
     #[tracing::instrument(skip_all)]
     pub fn init(&self) -> Result<App, BeaErr> {
         match self {
@@ -272,6 +272,13 @@ impl BeaResponse {
         }
     }
 
+    /// Replaces the results with the records in `data`, as when filling in a response read with
+    /// [`outline`](crate::outline).
+    pub(crate) fn with_data(mut self, data: Data) -> Self {
+        self.beaapi.results = Results::from(data);
+        self
+    }
+
     pub fn into_parts(&self) -> (RequestParameters, Results) {
         let req = self.request().clone();
         let res = self.results().clone();
@@ -309,6 +316,198 @@ impl TryFrom<&serde_json::Value> for BeaResponse {
     }
 }
 
+/// The `ApiErrorKind` enum classifies the errors returned by the BEA API in place of results.
+///
+/// The BEA does not publish a stable list of `APIErrorCode` values, so [`ApiErrorKind::classify`]
+/// trusts the handful of codes with a single meaning, see [`ApiErrorKind::CODES`], and otherwise
+/// infers the kind from the error description.  The MNE dataset reports errors in a format of its
+/// own, with numbers unrelated to `APIErrorCode`, so its kind comes from the description.  Use
+/// [`ApiErrorKind::is_no_data`], [`ApiErrorKind::is_request_error`] and
+/// [`ApiErrorKind::is_server_error`] to tell apart a selection with no published data, a
+/// malformed request and a problem on the server.
+///
+/// ```
+/// use bears::ApiErrorKind;
+///
+/// let kind = ApiErrorKind::from_description("No data exists for the selected parameters.");
+/// assert_eq!(kind, ApiErrorKind::NoData);
+/// assert!(kind.is_no_data());
+/// ```
+#[derive(
+    Debug,
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    strum::EnumIter,
+    derive_more::Display,
+    derive_more::FromStr,
+)]
+pub enum ApiErrorKind {
+    /// The UserID is missing, unregistered or inactive.
+    InvalidKey,
+    /// The dataset named in the request does not exist.
+    UnknownDataset,
+    /// The method named in the request does not exist.
+    InvalidMethod,
+    /// A parameter required by the dataset is missing from the request.
+    MissingParameter,
+    /// The request names a parameter the dataset does not accept.
+    InvalidParameter,
+    /// A parameter holds a value the dataset does not accept.
+    InvalidValue,
+    /// The request is valid, but no data is published for the selection.
+    NoData,
+    /// The response would exceed the size the BEA is willing to return.
+    TooLarge,
+    /// The caller exceeded the rate limits of the BEA server.
+    RequestsExceeded,
+    /// The BEA server failed to process a valid request.
+    Server,
+    /// The description does not match a known kind of error.
+    #[default]
+    Unknown,
+}
+
+impl ApiErrorKind {
+    /// The `APIErrorCode` values reported for a single kind of error.  Other codes, such as 101,
+    /// accompany errors of several kinds.
+    pub const CODES: [(i32, Self); 4] = [
+        (3, Self::InvalidKey),
+        (40, Self::MissingParameter),
+        (429, Self::RequestsExceeded),
+        (500, Self::Server),
+    ];
+
+    /// The kind of error for the `APIErrorCode` value `code`, or `None` if the code is unknown or
+    /// shared between kinds of error.
+    pub fn from_code(code: i32) -> Option<Self> {
+        Self::CODES
+            .iter()
+            .find(|(known, _)| *known == code)
+            .map(|(_, kind)| *kind)
+    }
+
+    /// Classifies an error by its `code`, falling back to the `description` where the code does
+    /// not settle the kind.
+    pub fn classify(code: i32, description: &str) -> Self {
+        Self::from_code(code).unwrap_or_else(|| Self::from_description(description))
+    }
+
+    /// Infers the kind of error from the `description` reported by the BEA.
+    ///
+    /// Phrases are checked from the most to the least specific, so that "request size exceeded"
+    /// reads as a response too large rather than a rate limit.
+    pub fn from_description(description: &str) -> Self {
+        let text = description.to_lowercase();
+        let has = |words: &[&str]| words.iter().any(|w| text.contains(w));
+        if has(&["userid", "user id", "api key", "not registered"]) {
+            Self::InvalidKey
+        } else if has(&[
+            "too large",
+            "exceeds the maximum",
+            "size limit",
+            "size exceeded",
+            "reduce the size",
+        ]) {
+            Self::TooLarge
+        } else if has(&["exceeded", "too many requests", "throttl", "rate limit"]) {
+            Self::RequestsExceeded
+        } else if has(&[
+            "no data",
+            "data not available",
+            "does not contain data",
+            "no records",
+            "not published",
+        ]) {
+            Self::NoData
+        } else if has(&[
+            "missing",
+            "is required",
+            "are required",
+            "requires parameter",
+        ]) {
+            Self::MissingParameter
+        } else if has(&[
+            "invalid dataset",
+            "unknown dataset",
+            "not a valid dataset",
+            "dataset requested does not exist",
+            "dataset does not exist",
+        ]) {
+            Self::UnknownDataset
+        } else if has(&[
+            "invalid method",
+            "unknown method",
+            "not a valid method",
+            "method requested does not exist",
+            "method does not exist",
+        ]) {
+            Self::InvalidMethod
+        } else if text.contains("parameter")
+            && has(&[
+                "unknown parameter",
+                "not a valid parameter",
+                "not recognized",
+            ])
+        {
+            Self::InvalidParameter
+        } else if has(&[
+            "invalid",
+            "not valid",
+            "not a valid",
+            "does not exist",
+            "out of range",
+        ]) {
+            Self::InvalidValue
+        } else if has(&[
+            "internal",
+            "server",
+            "unexpected",
+            "try again",
+            "timed out",
+            "timeout",
+        ]) {
+            Self::Server
+        } else {
+            Self::Unknown
+        }
+    }
+
+    /// Indicates `true` if the request was valid, but the BEA publishes no data for it.
+    /// Repeating the request will not help until the BEA publishes new data.
+    pub fn is_no_data(&self) -> bool {
+        matches!(self, Self::NoData)
+    }
+
+    /// Indicates `true` if the BEA rejected the request as malformed.  Repeating the request will
+    /// fail in the same way.
+    pub fn is_request_error(&self) -> bool {
+        matches!(
+            self,
+            Self::InvalidKey
+                | Self::UnknownDataset
+                | Self::InvalidMethod
+                | Self::MissingParameter
+                | Self::InvalidParameter
+                | Self::InvalidValue
+                | Self::TooLarge
+        )
+    }
+
+    /// Indicates `true` if the request failed on account of the server, including the rate limit,
+    /// and may succeed later.
+    pub fn is_server_error(&self) -> bool {
+        matches!(self, Self::RequestsExceeded | Self::Server)
+    }
+}
+
 #[derive(
     Clone,
     Debug,
@@ -322,6 +521,7 @@ impl TryFrom<&serde_json::Value> for BeaResponse {
     serde::Serialize,
     derive_new::new,
     derive_more::Display,
+    derive_getters::Getters,
 )]
 #[display("API Error - Code: {} - Description: {}", self.code, self.description)]
 pub struct ApiError {
@@ -330,6 +530,12 @@ pub struct ApiError {
 }
 
 impl ApiError {
+    /// The kind of error, from the code or else the description.  See
+    /// [`ApiErrorKind::classify`].
+    pub fn kind(&self) -> ApiErrorKind {
+        ApiErrorKind::classify(self.code, &self.description)
+    }
+
     pub fn read_json(mp: &serde_json::Map<String, serde_json::Value>) -> Result<Self, BeaErr> {
         let key = "Error".to_string();
         if let Some(value) = mp.get(&key) {
@@ -394,6 +600,7 @@ impl TryFrom<&serde_json::Value> for ApiError {
     serde::Serialize,
     derive_new::new,
     derive_more::Display,
+    derive_getters::Getters,
 )]
 #[display("MNE API Error - Number: {number} - Error: {error}")]
 pub struct MneError {
@@ -402,6 +609,11 @@ pub struct MneError {
 }
 
 impl MneError {
+    /// The kind of error, inferred from the error message.
+    pub fn kind(&self) -> ApiErrorKind {
+        ApiErrorKind::from_description(&self.error)
+    }
+
     pub fn read_json(mp: &serde_json::Map<String, serde_json::Value>) -> Result<Self, BeaErr> {
         let key = "Error".to_string();
         if let Some(value) = mp.get(&key) {
@@ -466,6 +678,7 @@ impl TryFrom<&serde_json::Value> for MneError {
     serde::Serialize,
     derive_new::new,
     derive_more::Display,
+    derive_getters::Getters,
 )]
 #[display("API Error - Code: {code} - Description: {description}")]
 pub struct RequestsExceeded {
@@ -474,6 +687,11 @@ pub struct RequestsExceeded {
 }
 
 impl RequestsExceeded {
+    /// The kind of error, always [`ApiErrorKind::RequestsExceeded`].
+    pub fn kind(&self) -> ApiErrorKind {
+        ApiErrorKind::RequestsExceeded
+    }
+
     pub fn read_json(mp: &serde_json::Map<String, serde_json::Value>) -> Result<Self, BeaErr> {
         let key = "Error".to_string();
         if let Some(value) = mp.get(&key) {
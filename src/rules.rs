@@ -0,0 +1,261 @@
+use crate::{
+    App, BeaErr, BeaResponse, Dataset, IoError, MultipleValues, NamesMismatch, Overrides,
+    Parameter, ParameterMissing, ParameterName, Parameters, Queue, SerdeJson, Settings,
+    VariantMissing,
+};
+use std::collections::BTreeMap;
+use strum::IntoEnumIterator;
+
+/// The `Rule` struct records how the BEA accepts values for a single parameter of a dataset, as
+/// reported by [`Method::GetParameterList`](crate::Method::GetParameterList).
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_new::new,
+    derive_getters::Getters,
+)]
+pub struct Rule {
+    name: ParameterName,
+    /// Whether a request must include the parameter.
+    required: bool,
+    /// Whether the parameter accepts a comma-separated list of values.
+    multiple: bool,
+    /// Value used by the BEA when the parameter is omitted.
+    default: Option<String>,
+    /// Token requesting every value of the parameter, such as "ALL" or "X".
+    all_value: Option<String>,
+}
+
+impl From<&Parameter> for Rule {
+    fn from(value: &Parameter) -> Self {
+        // the BEA reports an empty string where no default or all-values token applies
+        let non_empty = |s: &Option<String>| s.clone().filter(|s| !s.is_empty());
+        Self::new(
+            *value.parameter_name(),
+            *value.parameter_is_required_flag(),
+            *value.multiple_accepted_flag(),
+            non_empty(value.parameter_default_value()),
+            non_empty(value.all_value()),
+        )
+    }
+}
+
+/// The `Rules` struct is the catalog of [`Rule`] for the parameters of a [`Dataset`].
+///
+/// Use [`Rules::enforce`] to complete and check the parameters of a request before sending it.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_new::new,
+    derive_getters::Getters,
+)]
+pub struct Rules {
+    dataset: Dataset,
+    rules: BTreeMap<ParameterName, Rule>,
+}
+
+impl Rules {
+    /// Builds the catalog for `dataset` from its response to `GetParameterList`.
+    pub fn from_parameters(dataset: Dataset, parameters: &Parameters) -> Self {
+        let rules = parameters
+            .iter()
+            .map(|p| (*p.parameter_name(), Rule::from(p)))
+            .collect();
+        Self::new(dataset, rules)
+    }
+
+    /// Reads the catalog for `dataset` from the `GetParameterList` response saved in the
+    /// `parameters` folder under `path`.
+    pub fn from_path<P: AsRef<std::path::Path>>(path: P, dataset: Dataset) -> Result<Self, BeaErr> {
+        let path = path
+            .as_ref()
+            .join(format!("parameters/{dataset}_parameters.json"));
+        let file = std::fs::File::open(&path)
+            .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
+        let rdr = std::io::BufReader::new(file);
+        let res: serde_json::Value = serde_json::from_reader(rdr)
+            .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+        let data = BeaResponse::try_from(&res)?;
+        match data.parameters() {
+            Some(parameters) => Ok(Self::from_parameters(dataset, &parameters)),
+            None => {
+                let error = VariantMissing::new(
+                    "Results must be of type Parameters".to_string(),
+                    dataset.to_string(),
+                    line!(),
+                    file!().to_string(),
+                );
+                Err(error.into())
+            }
+        }
+    }
+
+    /// The parameter names accepted by the dataset, in sorted order.
+    pub fn names(&self) -> Vec<ParameterName> {
+        self.rules.keys().copied().collect()
+    }
+
+    /// Compares the parameter names in the catalog against [`Dataset::names`], failing if they
+    /// differ.
+    pub fn check_names(&self) -> Result<(), NamesMismatch> {
+        let mut expected = self.dataset.names();
+        expected.sort();
+        let found = self.names();
+        if expected == found {
+            Ok(())
+        } else {
+            let to_strings = |v: &[ParameterName]| v.iter().map(|n| n.to_string()).collect();
+            let error = NamesMismatch::new(
+                self.dataset.to_string(),
+                to_strings(&expected),
+                to_strings(&found),
+                line!(),
+                file!().to_string(),
+            );
+            Err(error)
+        }
+    }
+
+    /// Completes and checks the request parameters `params` against the catalog.
+    ///
+    /// Omitted parameters with a documented default take that default.  Fails if a required
+    /// parameter is still missing, or if a parameter that accepts a single value holds a
+    /// comma-separated list.  Parameter names match regardless of case, as they do for the BEA.
+    pub fn enforce(&self, params: &mut BTreeMap<String, String>) -> Result<(), BeaErr> {
+        for rule in self.rules.values() {
+            let name = rule.name.to_string();
+            let key = params
+                .keys()
+                .find(|k| k.eq_ignore_ascii_case(&name))
+                .cloned();
+            let value = match key {
+                Some(key) => params[&key].clone(),
+                None => match &rule.default {
+                    Some(default) => {
+                        tracing::trace!("Setting {name} to default {default}.");
+                        params.insert(name.clone(), default.clone());
+                        default.clone()
+                    }
+                    None if rule.required => {
+                        let error = ParameterMissing::new(
+                            self.dataset.to_string(),
+                            name,
+                            line!(),
+                            file!().to_string(),
+                        );
+                        return Err(error.into());
+                    }
+                    None => continue,
+                },
+            };
+            let is_all = rule
+                .all_value
+                .as_ref()
+                .is_some_and(|all| all.eq_ignore_ascii_case(&value));
+            if !rule.multiple && !is_all && value.contains(',') {
+                let error = MultipleValues::new(
+                    self.dataset.to_string(),
+                    name,
+                    value,
+                    line!(),
+                    file!().to_string(),
+                );
+                return Err(error.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The `Catalog` struct holds the [`Rules`] for each [`Dataset`], read from the responses to
+/// `GetParameterList` saved under `BEA_DATA/parameters`.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_more::Deref,
+    derive_more::DerefMut,
+    derive_more::From,
+)]
+#[from(BTreeMap<Dataset, Rules>)]
+pub struct Catalog(BTreeMap<Dataset, Rules>);
+
+impl Catalog {
+    /// Reads the rules for every dataset from the `parameters` folder under `path`.  Datasets
+    /// without a saved `GetParameterList` response are left out of the catalog.
+    pub fn from_path<P: AsRef<std::path::Path>>(path: P) -> Result<Self, BeaErr> {
+        let mut catalog = BTreeMap::new();
+        for dataset in Dataset::iter() {
+            let file = path
+                .as_ref()
+                .join(format!("parameters/{dataset}_parameters.json"));
+            if !file.exists() {
+                tracing::trace!("No parameters saved for {dataset}, skipping.");
+                continue;
+            }
+            let rules = Rules::from_path(&path, dataset)?;
+            catalog.insert(dataset, rules);
+        }
+        Ok(Self(catalog))
+    }
+
+    /// Reads the rules for every dataset from the data root of the default [`Settings`](crate::Settings).
+    pub fn from_env() -> Result<Self, BeaErr> {
+        let path = Settings::resolve(&Overrides::default())?.data().clone();
+        Self::from_path(path)
+    }
+
+    /// Completes and checks the query of `app` against the rules for its dataset.  See
+    /// [`Rules::enforce`].  Datasets absent from the catalog pass unchanged.
+    pub fn enforce(&self, app: &mut App) -> Result<(), BeaErr> {
+        let dataset = app.dataset()?;
+        let mut params = app.query().clone();
+        self.enforce_params(dataset, &mut params)?;
+        app.with_params(params);
+        Ok(())
+    }
+
+    /// Completes and checks the request parameters `params` against the rules for `dataset`.
+    /// See [`Rules::enforce`].  Datasets absent from the catalog pass unchanged.
+    pub fn enforce_params(
+        &self,
+        dataset: Dataset,
+        params: &mut BTreeMap<String, String>,
+    ) -> Result<(), BeaErr> {
+        match self.get(&dataset) {
+            Some(rules) => rules.enforce(params),
+            None => Ok(()),
+        }
+    }
+
+    /// Enforces the rules on each request in `queue`, failing on the first violation.
+    pub fn enforce_queue(&self, queue: &mut Queue) -> Result<(), BeaErr> {
+        for app in queue.iter_mut() {
+            self.enforce(app)?;
+        }
+        Ok(())
+    }
+
+    /// Checks [`Dataset::names`] against the catalog for every dataset.
+    pub fn check_names(&self) -> Result<(), BeaErr> {
+        for rules in self.values() {
+            rules.check_names()?;
+        }
+        Ok(())
+    }
+}
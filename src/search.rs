@@ -0,0 +1,548 @@
+use crate::{
+    BeaErr, BeaResponse, Dataset, FixedAssetDatum, FsStorage, Integer, IoError, MneDiDatum, Naics,
+    NipaDatum, Overrides, ParameterName, SerdeJson, Settings, TableName,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use strum::IntoEnumIterator;
+
+/// The `Entry` struct is a single item in the [`SearchIndex`], either a table described by the
+/// cached `TableName` parameter values, or a line of a table read from downloaded data.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_new::new,
+    derive_getters::Getters,
+)]
+pub struct Entry {
+    dataset: Dataset,
+    table_name: String,
+    /// Line number within the table, or `None` for an entry describing the whole table.
+    line_number: Option<i64>,
+    /// Description of the table, or of the line for line entries.
+    description: String,
+    series_code: Option<String>,
+    metric_name: Option<String>,
+}
+
+impl Entry {
+    /// Indicates `true` if the entry describes a whole table rather than a line.
+    pub fn is_table(&self) -> bool {
+        self.line_number.is_none()
+    }
+}
+
+impl std::fmt::Display for Entry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.dataset, self.table_name)?;
+        if let Some(line) = self.line_number {
+            write!(f, " line {line}")?;
+        }
+        if let Some(code) = &self.series_code {
+            write!(f, " [{code}]")?;
+        }
+        write!(f, ": {}", self.description)?;
+        if let Some(metric) = &self.metric_name {
+            write!(f, " ({metric})")?;
+        }
+        Ok(())
+    }
+}
+
+/// The `Hit` struct is an [`Entry`] matching a keyword search, with its relevance score.
+#[derive(Debug, Clone, PartialEq, Eq, derive_new::new, derive_getters::Getters)]
+pub struct Hit {
+    score: usize,
+    entry: Entry,
+}
+
+/// The `Location` struct answers where a series is published: the dataset, table and line.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_new::new,
+    derive_getters::Getters,
+)]
+pub struct Location {
+    dataset: Dataset,
+    table_name: String,
+    /// Description of the table, if the cached `TableName` values include it.
+    table_description: Option<String>,
+    line_number: i64,
+    line_description: String,
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.dataset, self.table_name)?;
+        if let Some(description) = &self.table_description {
+            write!(f, " ({description})")?;
+        }
+        write!(f, " line {}: {}", self.line_number, self.line_description)
+    }
+}
+
+/// Splits `text` into lowercase words of letters and digits.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// The `SearchIndex` struct is an offline catalog of tables, lines and series, built from the
+/// `TableName` and Regional `LineCode` parameter values cached under `BEA_DATA/parameter_values`
+/// and the GetData responses downloaded under `BEA_DATA/data`.  No calls are made to the BEA API.
+///
+/// Use [`SearchIndex::load`] to reuse the index saved under `BEA_DATA/search`, rebuilding it only
+/// when the mirror has changed since it was saved.
+///
+/// ```
+/// use bears::{Dataset, SearchIndex, TableName};
+///
+/// let mut index = SearchIndex::default();
+/// let tables = vec![TableName::new("T20100".into(), "Personal Income and Its Disposition".into())];
+/// index.with_tables(Dataset::Nipa, &tables);
+/// let hits = index.search("personal income disposition", 10);
+/// assert_eq!(hits[0].entry().table_name(), "T20100");
+/// ```
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_more::Deref,
+    derive_more::From,
+)]
+#[from(BTreeSet<Entry>)]
+pub struct SearchIndex(BTreeSet<Entry>);
+
+impl SearchIndex {
+    /// Datasets whose downloaded GetData responses are indexed line by line.
+    const DATA: [Dataset; 4] = [
+        Dataset::Nipa,
+        Dataset::NIUnderlyingDetail,
+        Dataset::FixedAssets,
+        Dataset::Mne,
+    ];
+
+    /// Location of the saved index under the `BEA_DATA` directory `path`.
+    pub fn index_path<P: AsRef<std::path::Path>>(path: P) -> std::path::PathBuf {
+        path.as_ref().join("search").join("index.json")
+    }
+
+    /// Reads the index saved under the `BEA_DATA` directory `path`, rebuilding and saving it if
+    /// missing or older than any of the files it indexes.
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Self, BeaErr> {
+        let path = path.as_ref();
+        let file = Self::index_path(path);
+        let saved = std::fs::metadata(&file).and_then(|m| m.modified()).ok();
+        match saved {
+            Some(saved) if Self::newest_source(path)?.is_none_or(|newest| newest <= saved) => {
+                tracing::info!("Reading saved search index.");
+                let bytes = std::fs::read(&file)
+                    .map_err(|e| IoError::new(file.clone(), e, line!(), file!().into()))?;
+                serde_json::from_slice(&bytes)
+                    .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()).into())
+            }
+            _ => {
+                tracing::info!("Search index missing or stale, rebuilding.");
+                let index = Self::from_path(path)?;
+                index.save(path)?;
+                Ok(index)
+            }
+        }
+    }
+
+    /// Saves the index under the `BEA_DATA` directory `path`, see [`SearchIndex::index_path`].
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), BeaErr> {
+        let bytes = serde_json::to_vec(self)
+            .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+        FsStorage::write_atomic(&Self::index_path(path), &bytes)
+    }
+
+    /// The latest modification time of the files indexed by [`SearchIndex::from_path`], or
+    /// `None` if there are none.
+    fn newest_source(path: &std::path::Path) -> Result<Option<std::time::SystemTime>, BeaErr> {
+        let mut folders = vec![path.join("parameter_values")];
+        folders.extend(
+            Self::DATA
+                .iter()
+                .map(|dataset| path.join("data").join(dataset.to_string())),
+        );
+        let mut newest = None;
+        for folder in folders.iter().filter(|f| f.exists()) {
+            // the folder time changes when files are added or removed
+            let mut times = vec![folder.clone()];
+            let contents = std::fs::read_dir(folder)
+                .map_err(|e| IoError::new(folder.clone(), e, line!(), file!().into()))?;
+            for item in contents {
+                let item =
+                    item.map_err(|e| IoError::new(folder.clone(), e, line!(), file!().into()))?;
+                times.push(item.path());
+            }
+            for file in times {
+                let modified = std::fs::metadata(&file)
+                    .and_then(|m| m.modified())
+                    .map_err(|e| IoError::new(file, e, line!(), file!().into()))?;
+                newest = newest.max(Some(modified));
+            }
+        }
+        Ok(newest)
+    }
+
+    /// Builds the index from the cached parameter values and downloaded data under the
+    /// `BEA_DATA` directory `path`.  Missing folders are skipped, so the index covers whatever
+    /// has been mirrored so far.
+    pub fn from_path<P: AsRef<std::path::Path>>(path: P) -> Result<Self, BeaErr> {
+        let path = path.as_ref();
+        let mut index = Self::default();
+        for dataset in Dataset::iter() {
+            if !dataset.names().contains(&ParameterName::TableName) {
+                continue;
+            }
+            let file = path.join(format!(
+                "parameter_values/{dataset}_{}_parameter_values.json",
+                ParameterName::TableName
+            ));
+            if !file.exists() {
+                tracing::trace!("No cached table names for {dataset}.");
+                continue;
+            }
+            let tables = Self::read_values(&file)?;
+            tracing::info!("{} {dataset} tables indexed.", tables.len());
+            index.with_tables(dataset, &tables);
+        }
+        let file = path.join(format!(
+            "parameter_values/{}_{}_parameter_values.json",
+            Dataset::Regional,
+            ParameterName::LineCode
+        ));
+        if file.exists() {
+            let lines = Self::read_values(&file)?;
+            tracing::info!("{} Regional lines indexed.", lines.len());
+            index.with_regional(&lines);
+        }
+        for dataset in Self::DATA {
+            let folder = path.join("data").join(dataset.to_string());
+            if !folder.exists() {
+                tracing::trace!("No downloaded data for {dataset}.");
+                continue;
+            }
+            // MNE records need the NAICS codes to read row codes
+            let naics = match dataset {
+                Dataset::Mne => match Naics::from_csv("data/naics_codes.csv") {
+                    Ok(naics) => Some(naics),
+                    Err(source) => {
+                        tracing::warn!("Skipping {dataset} lines, NAICS codes missing: {source}");
+                        continue;
+                    }
+                },
+                _ => None,
+            };
+            let contents = std::fs::read_dir(&folder)
+                .map_err(|e| IoError::new(folder.clone(), e, line!(), file!().into()))?;
+            for item in contents {
+                let file = item
+                    .map_err(|e| IoError::new(folder.clone(), e, line!(), file!().into()))?
+                    .path();
+                if file.extension().is_none_or(|ext| ext != "json") {
+                    continue;
+                }
+                // responses holding an API error rather than data are expected in a mirror
+                if let Err(source) = index.with_file(dataset, &file, naics.as_ref()) {
+                    tracing::warn!("Skipping {file:?}: {source}");
+                }
+            }
+        }
+        Ok(index)
+    }
+
+    /// Builds the index from the data root of the default [`Settings`](crate::Settings).
+    pub fn from_env() -> Result<Self, BeaErr> {
+        let path = Settings::resolve(&Overrides::default())?.data().clone();
+        Self::from_path(path)
+    }
+
+    /// Reads the parameter values from the response saved at `path`.
+    fn read_values<T>(path: &std::path::Path) -> Result<Vec<T>, BeaErr>
+    where
+        T: for<'a> TryFrom<&'a crate::ParameterValueTable, Error = BeaErr>,
+    {
+        let file = std::fs::File::open(path)
+            .map_err(|e| IoError::new(path.into(), e, line!(), file!().into()))?;
+        let rdr = std::io::BufReader::new(file);
+        let res: serde_json::Value = serde_json::from_reader(rdr)
+            .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+        let data = BeaResponse::try_from(&res)?;
+        let values = data.parameter_values().unwrap_or_default();
+        values.iter().map(T::try_from).collect()
+    }
+
+    /// Adds the lines of the GetData response for `dataset` saved at `path`.  Reading MNE
+    /// records requires `naics`.
+    fn with_file(
+        &mut self,
+        dataset: Dataset,
+        path: &std::path::Path,
+        naics: Option<&Naics>,
+    ) -> Result<(), BeaErr> {
+        match dataset {
+            Dataset::Mne => {
+                let naics = naics.cloned().unwrap_or_default();
+                for datum in MneDiDatum::stream(path, naics)? {
+                    self.with_mne(&[datum?]);
+                }
+            }
+            Dataset::FixedAssets => {
+                for datum in FixedAssetDatum::stream(path)? {
+                    let datum = datum?;
+                    self.with_line(
+                        dataset,
+                        datum.table_name(),
+                        *datum.line_number(),
+                        datum.line_description(),
+                        datum.series_code(),
+                        datum.metric_name(),
+                    );
+                }
+            }
+            _ => {
+                for datum in NipaDatum::stream(path)? {
+                    let datum = datum?;
+                    self.with_line(
+                        dataset,
+                        datum.table_name(),
+                        *datum.line_number(),
+                        datum.line_description(),
+                        datum.series_code(),
+                        datum.metric_name(),
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the `tables` of `dataset` to the index.
+    pub fn with_tables(&mut self, dataset: Dataset, tables: &[TableName]) {
+        for table in tables {
+            let entry = Entry::new(
+                dataset,
+                table.name().clone(),
+                None,
+                table.description().clone(),
+                None,
+                None,
+            );
+            self.0.insert(entry);
+        }
+    }
+
+    /// Adds the lines of the NIPA-style `data` from `dataset` to the index.  Records repeat for
+    /// each time period, but each line is indexed once.
+    pub fn with_nipa(&mut self, dataset: Dataset, data: &[NipaDatum]) {
+        for datum in data {
+            self.with_line(
+                dataset,
+                datum.table_name(),
+                *datum.line_number(),
+                datum.line_description(),
+                datum.series_code(),
+                datum.metric_name(),
+            );
+        }
+    }
+
+    /// Adds the Regional `lines`, the `LineCode` parameter values, to the index.  The BEA prefixes
+    /// each description with the table name in brackets, as in "[CAINC1] Personal income";
+    /// lines without the prefix are skipped.
+    pub fn with_regional(&mut self, lines: &[Integer]) {
+        for line in lines {
+            let Some((table_name, description)) = line
+                .description()
+                .strip_prefix('[')
+                .and_then(|rest| rest.split_once(']'))
+            else {
+                continue;
+            };
+            let entry = Entry::new(
+                Dataset::Regional,
+                table_name.to_string(),
+                Some(i64::from(*line.value())),
+                description.trim().to_string(),
+                None,
+                None,
+            );
+            self.0.insert(entry);
+        }
+    }
+
+    /// Adds the rows of the MNE `data` to the index, using the series name as the table and the
+    /// row display order as the line number.  Records repeat for each year and column, but each
+    /// row is indexed once.
+    pub fn with_mne(&mut self, data: &[MneDiDatum]) {
+        for datum in data {
+            let entry = Entry::new(
+                Dataset::Mne,
+                datum.series_name().clone(),
+                Some(*datum.table_row_display_order() as i64),
+                datum.row().clone(),
+                Some(datum.series_id().to_string()),
+                None,
+            );
+            self.0.insert(entry);
+        }
+    }
+
+    /// Adds a single line to the index.
+    fn with_line(
+        &mut self,
+        dataset: Dataset,
+        table_name: &str,
+        line_number: i64,
+        description: &str,
+        series_code: &str,
+        metric_name: &str,
+    ) {
+        let entry = Entry::new(
+            dataset,
+            table_name.to_string(),
+            Some(line_number),
+            description.to_string(),
+            Some(series_code.to_string()),
+            Some(metric_name.to_string()),
+        );
+        self.0.insert(entry);
+    }
+
+    /// Scores `entry` against the words of a query, returning zero if no word matches.
+    ///
+    /// Each query word scores three points for a whole word of the description, two for the
+    /// start of a word and one for any other part.  Entries matching more query words always
+    /// rank higher, and descriptions containing the query as a phrase earn a bonus.
+    fn score(entry: &Entry, query: &str, terms: &[String]) -> usize {
+        let mut text = entry.description.clone();
+        if let Some(metric) = &entry.metric_name {
+            text.push(' ');
+            text.push_str(metric);
+        }
+        let candidates = words(&text);
+        let mut matched = 0;
+        let mut score = 0;
+        for term in terms {
+            let best = candidates
+                .iter()
+                .map(|word| {
+                    if word == term {
+                        3
+                    } else if word.starts_with(term.as_str()) {
+                        2
+                    } else if word.contains(term.as_str()) {
+                        1
+                    } else {
+                        0
+                    }
+                })
+                .max()
+                .unwrap_or_default();
+            if best > 0 {
+                matched += 1;
+                score += best;
+            }
+        }
+        if matched == 0 {
+            return 0;
+        }
+        // no combination of points can outweigh one more matching word
+        score += matched * 4 * terms.len();
+        if entry
+            .description
+            .to_lowercase()
+            .contains(&query.to_lowercase())
+        {
+            score += 2 * terms.len();
+        }
+        score
+    }
+
+    /// Ranks entries by relevance to the keywords in `query`, returning at most `limit` hits.
+    /// Entries match if any word of the query appears in the description or metric name.  Ties
+    /// rank tables before lines, then shorter descriptions first.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<Hit> {
+        let terms = words(query);
+        if terms.is_empty() {
+            return Vec::new();
+        }
+        let mut hits = self
+            .iter()
+            .filter_map(|entry| match Self::score(entry, query.trim(), &terms) {
+                0 => None,
+                score => Some(Hit::new(score, entry.clone())),
+            })
+            .collect::<Vec<Hit>>();
+        hits.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(b.entry.is_table().cmp(&a.entry.is_table()))
+                .then(a.entry.description.len().cmp(&b.entry.description.len()))
+                .then(a.entry.cmp(&b.entry))
+        });
+        hits.truncate(limit);
+        hits
+    }
+
+    /// Lines published under the series code `code`, ignoring case.
+    pub fn series(&self, code: &str) -> Vec<&Entry> {
+        self.iter()
+            .filter(|entry| {
+                entry
+                    .series_code
+                    .as_ref()
+                    .is_some_and(|c| c.eq_ignore_ascii_case(code))
+            })
+            .collect()
+    }
+
+    /// Where the series `code` is published, one location for each dataset, table and line
+    /// reporting it.
+    pub fn locate(&self, code: &str) -> Vec<Location> {
+        let tables = self
+            .iter()
+            .filter(|entry| entry.is_table())
+            .map(|entry| ((entry.dataset, &entry.table_name), &entry.description))
+            .collect::<BTreeMap<(Dataset, &String), &String>>();
+        self.series(code)
+            .into_iter()
+            .filter_map(|entry| {
+                let line_number = entry.line_number?;
+                let table_description = tables
+                    .get(&(entry.dataset, &entry.table_name))
+                    .map(|d| d.to_string());
+                Some(Location::new(
+                    entry.dataset,
+                    entry.table_name.clone(),
+                    table_description,
+                    line_number,
+                    entry.description.clone(),
+                ))
+            })
+            .collect()
+    }
+}
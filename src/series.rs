@@ -0,0 +1,797 @@
+use crate::{
+    BeaErr, Data, FixedAssetData, FixedAssetDatum, Frequency, FrequencyMismatch, Measure,
+    MneDiData, MneDiDatum, NipaData, NipaDatum, Period, Unit, VariantMissing,
+};
+use std::collections::BTreeMap;
+
+/// The `Series` struct holds the observations of a single BEA series, ordered by [`Period`].
+///
+/// A series is identified by its `code` within a `table_name`, and carries the metadata shared by
+/// each of its observations.  Observations are stored as `Option<f64>`, where `None` marks a value
+/// the BEA suppressed or did not report for that period.  Each series holds observations of a
+/// single [`Frequency`], so that annual and quarterly values of the same series code are kept
+/// apart.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    PartialEq,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_getters::Getters,
+    derive_setters::Setters,
+)]
+#[setters(prefix = "with_", borrow_self, into)]
+pub struct Series {
+    code: String,
+    table_name: String,
+    line_number: Option<i64>,
+    line_description: String,
+    metric_name: String,
+    unit: Unit,
+    /// Power of ten applied to each observation, from the `UNIT_MULT` field.
+    unit_mult: i64,
+    /// First and last periods of the reference period of an index or chained-dollar series,
+    /// when known.
+    reference: Option<(Period, Period)>,
+    /// Whether the series is a flow, stock or rate, which decides how it aggregates.  `None` for
+    /// rates of change and unrecognized units.
+    measurement: Option<Measurement>,
+    #[setters(skip)]
+    observations: BTreeMap<Period, Option<f64>>,
+}
+
+impl Series {
+    /// Creates an empty series with the metadata of a NIPA record.
+    pub fn from_nipa(datum: &NipaDatum) -> Self {
+        Self {
+            code: datum.series_code().clone(),
+            table_name: datum.table_name().clone(),
+            line_number: Some(*datum.line_number()),
+            line_description: datum.line_description().clone(),
+            metric_name: datum.metric_name().clone(),
+            unit: datum.unit(),
+            unit_mult: datum.unit_mult().unwrap_or_default(),
+            reference: None,
+            measurement: Measurement::infer(
+                &datum.unit(),
+                &[datum.table_name(), datum.line_description()],
+                Measurement::AnnualRate,
+            ),
+            observations: BTreeMap::new(),
+        }
+    }
+
+    /// Creates an empty series with the metadata of a Fixed Assets record.
+    pub fn from_fixed_assets(datum: &FixedAssetDatum) -> Self {
+        Self {
+            code: datum.series_code().clone(),
+            table_name: datum.table_name().clone(),
+            line_number: Some(*datum.line_number()),
+            line_description: datum.line_description().clone(),
+            metric_name: datum.metric_name().clone(),
+            unit: datum.unit(),
+            unit_mult: datum.unit_mult().unwrap_or_default(),
+            reference: None,
+            measurement: Measurement::infer(
+                &datum.unit(),
+                &[datum.line_description(), datum.metric_name()],
+                Measurement::Flow,
+            ),
+            observations: BTreeMap::new(),
+        }
+    }
+
+    /// Creates an empty series with the metadata of an MNE record.  MNE records have no series
+    /// code or line number, so the code combines the series id with the row and column labels.
+    pub fn from_mne(datum: &MneDiDatum) -> Self {
+        let scale = datum.table_scale().to_lowercase();
+        let unit_mult = if scale.contains("billion") {
+            9
+        } else if scale.contains("million") {
+            6
+        } else if scale.contains("thousand") {
+            3
+        } else {
+            0
+        };
+        let unit = Unit::from_parts("Level", datum.table_scale());
+        Self {
+            code: Self::mne_code(datum),
+            table_name: datum.series_name().clone(),
+            line_number: None,
+            line_description: datum.row().clone(),
+            metric_name: datum.column().clone(),
+            measurement: Measurement::infer(&unit, &[datum.series_name()], Measurement::Flow),
+            unit,
+            unit_mult,
+            reference: None,
+            observations: BTreeMap::new(),
+        }
+    }
+
+    /// The code identifying the series of an MNE record, combining the series id with the row
+    /// and column labels.
+    fn mne_code(datum: &MneDiDatum) -> String {
+        format!("{}/{}/{}", datum.series_id(), datum.row(), datum.column())
+    }
+
+    /// Records the `value` observed in `period`, replacing any previous observation.
+    pub fn insert(&mut self, period: Period, value: Option<f64>) {
+        self.observations.insert(period, value);
+    }
+
+    /// The observation for `period`.  Returns `None` if the period is missing or suppressed.
+    pub fn get(&self, period: &Period) -> Option<f64> {
+        self.observations.get(period).copied().flatten()
+    }
+
+    /// The frequency of the observations, or `None` if the series is empty.
+    pub fn frequency(&self) -> Option<Frequency> {
+        self.observations.keys().next().map(|p| p.frequency())
+    }
+
+    /// The first and last periods of the series, or `None` if the series is empty.
+    pub fn span(&self) -> Option<(Period, Period)> {
+        let first = self.observations.keys().next()?;
+        let last = self.observations.keys().next_back()?;
+        Some((*first, *last))
+    }
+
+    /// The number of periods recorded, including suppressed values.
+    pub fn len(&self) -> usize {
+        self.observations.len()
+    }
+
+    /// Indicates `true` if the series has no observations.
+    pub fn is_empty(&self) -> bool {
+        self.observations.is_empty()
+    }
+
+    /// Iterates over periods with a reported value, skipping suppressed observations.
+    pub fn values(&self) -> impl Iterator<Item = (&Period, f64)> {
+        self.observations
+            .iter()
+            .filter_map(|(period, value)| value.map(|v| (period, v)))
+    }
+
+    /// Returns an error unless every series in `series` shares the same frequency.
+    fn check_frequency(series: &[&Self]) -> Result<(), FrequencyMismatch> {
+        let mut frequencies = series.iter().filter_map(|s| s.frequency());
+        if let Some(first) = frequencies.next() {
+            for other in frequencies {
+                if other != first {
+                    let error = FrequencyMismatch::new(
+                        first.value(),
+                        other.value(),
+                        line!(),
+                        file!().to_string(),
+                    );
+                    return Err(error);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Aligns `series` on the periods where every series has a reported value.  Each row holds
+    /// the values in the same order as `series`.  Fails if the series differ in frequency.
+    ///
+    /// ```
+    /// use bears::{Period, Series};
+    ///
+    /// let mut a = Series::default();
+    /// a.insert(Period::Year(2022), Some(1.0));
+    /// a.insert(Period::Year(2023), Some(2.0));
+    /// let mut b = Series::default();
+    /// b.insert(Period::Year(2023), Some(3.0));
+    /// let aligned = Series::align(&[&a, &b]).unwrap();
+    /// assert_eq!(aligned.len(), 1);
+    /// assert_eq!(aligned[&Period::Year(2023)], vec![2.0, 3.0]);
+    /// ```
+    pub fn align(series: &[&Self]) -> Result<BTreeMap<Period, Vec<f64>>, FrequencyMismatch> {
+        let joined = Self::join(series)?;
+        let aligned = joined
+            .into_iter()
+            .filter_map(|(period, row)| {
+                row.into_iter()
+                    .collect::<Option<Vec<f64>>>()
+                    .map(|row| (period, row))
+            })
+            .collect();
+        Ok(aligned)
+    }
+
+    /// Joins `series` on the union of their periods, marking missing or suppressed values as
+    /// `None`.  Each row holds the values in the same order as `series`.  Fails if the series
+    /// differ in frequency.
+    pub fn join(series: &[&Self]) -> Result<BTreeMap<Period, Vec<Option<f64>>>, FrequencyMismatch> {
+        Self::check_frequency(series)?;
+        let mut joined = BTreeMap::new();
+        for s in series {
+            for period in s.observations.keys() {
+                joined
+                    .entry(*period)
+                    .or_insert_with(|| series.iter().map(|s| s.get(period)).collect());
+            }
+        }
+        Ok(joined)
+    }
+
+    /// Creates a series sharing the metadata of `self`, with a new `unit` and `observations`.  The
+    /// unit multiplier of a derived series is zero, since rates are not scaled, and rates of
+    /// change have no [`Measurement`].
+    fn derive(&self, unit: Unit, observations: BTreeMap<Period, Option<f64>>) -> Self {
+        Self {
+            unit,
+            unit_mult: 0,
+            measurement: None,
+            observations,
+            ..self.clone()
+        }
+    }
+
+    /// Applies `f` to each observation and the observation `lag` periods earlier.  Periods whose
+    /// lag falls before the start of the series are omitted.  The result is `None` where either
+    /// value is missing or suppressed, or where `f` returns `None`.
+    fn lagged<F>(&self, lag: i64, f: F) -> BTreeMap<Period, Option<f64>>
+    where
+        F: Fn(f64, f64) -> Option<f64>,
+    {
+        let Some((first, _)) = self.span() else {
+            return BTreeMap::new();
+        };
+        self.observations
+            .iter()
+            .filter_map(|(period, value)| {
+                let prior = period.offset(-lag)?;
+                if prior < first {
+                    return None;
+                }
+                let result = match (value, self.get(&prior)) {
+                    (Some(current), Some(prior)) => f(*current, prior),
+                    _ => None,
+                };
+                Some((*period, result))
+            })
+            .collect()
+    }
+
+    /// Percent change from `prior` to `current`, or `None` if `prior` is zero.
+    fn change(current: f64, prior: f64) -> Option<f64> {
+        if prior == 0.0 {
+            None
+        } else {
+            Some((current / prior - 1.0) * 100.0)
+        }
+    }
+
+    /// Percent change from the preceding period, at the frequency of the series.  The result is
+    /// not annualized.  Gaps and suppressed values yield `None` for the affected periods.
+    pub fn percent_change(&self) -> Self {
+        let observations = self.lagged(1, Self::change);
+        self.derive(Unit::PercentChange, observations)
+    }
+
+    /// Percent change from the same period one year earlier.  For an annual series, this matches
+    /// [`Series::percent_change`].
+    pub fn year_over_year(&self) -> Self {
+        let lag = self.span().map_or(1, |(p, _)| p.periods_per_year() as i64);
+        let observations = self.lagged(lag, Self::change);
+        self.derive(Unit::PercentChange, observations)
+    }
+
+    /// Percent change from the preceding period, compounded to an annual rate as the BEA reports
+    /// quarterly and monthly growth.  The exponent is the number of periods per year, so a
+    /// quarterly ratio is raised to the fourth power and a monthly ratio to the twelfth.
+    ///
+    /// ```
+    /// use bears::{Period, Series};
+    ///
+    /// let mut gdp = Series::default();
+    /// gdp.insert(Period::Quarter(2023, 1), Some(100.0));
+    /// gdp.insert(Period::Quarter(2023, 2), Some(101.0));
+    /// let growth = gdp.annualized_growth();
+    /// let rate = growth.get(&Period::Quarter(2023, 2)).unwrap();
+    /// assert!((rate - 4.060401).abs() < 1e-6);
+    /// ```
+    pub fn annualized_growth(&self) -> Self {
+        let exponent = self.span().map_or(1, |(p, _)| p.periods_per_year() as i32);
+        let observations = self.lagged(1, |current, prior| {
+            if prior == 0.0 {
+                None
+            } else {
+                Some(((current / prior).powi(exponent) - 1.0) * 100.0)
+            }
+        });
+        self.derive(Unit::PercentChangeAnnualRate, observations)
+    }
+
+    /// Compound annual growth rate, in percent, between the periods `from` and `to`.  The periods
+    /// must match the frequency of the series.  Returns `None` if either value is missing or
+    /// suppressed, if the periods coincide, or if the starting value is not positive.
+    pub fn cagr(&self, from: &Period, to: &Period) -> Result<Option<f64>, FrequencyMismatch> {
+        let distance = from.distance(to)?;
+        if let Some(frequency) = self.frequency()
+            && frequency != from.frequency()
+        {
+            let error = FrequencyMismatch::new(
+                frequency.value(),
+                from.frequency().value(),
+                line!(),
+                file!().to_string(),
+            );
+            return Err(error);
+        }
+        let years = distance as f64 / from.periods_per_year() as f64;
+        let rate = match (self.get(from), self.get(to)) {
+            (Some(start), Some(end)) if start > 0.0 && distance != 0 => {
+                Some(((end / start).powf(1.0 / years) - 1.0) * 100.0)
+            }
+            _ => None,
+        };
+        Ok(rate)
+    }
+
+    /// Contribution of `self` to the percent change in `aggregate`, in percentage points.  The
+    /// contribution in each period is the change in `self` divided by the prior level of
+    /// `aggregate`, so that the contributions of additive components sum to the percent change of
+    /// the aggregate.
+    ///
+    /// Both series must share a frequency and an additive unit.  Chained-dollar components are
+    /// rejected because they do not sum to their aggregate.
+    pub fn contributions(&self, aggregate: &Self) -> Result<Self, BeaErr> {
+        Self::check_frequency(&[self, aggregate])?;
+        let component = Measure::new(0.0, self.unit.clone(), self.unit_mult);
+        let total = Measure::new(0.0, aggregate.unit.clone(), aggregate.unit_mult);
+        // Fails if the units differ or cannot be summed.
+        component.checked_add(&total)?;
+        // Express the component in the multiplier of the aggregate.
+        let scale = 10_f64.powi((self.unit_mult - aggregate.unit_mult) as i32);
+        let observations = self
+            .lagged(1, |current, prior| Some((current - prior) * scale))
+            .into_iter()
+            .map(|(period, change)| {
+                let base = period
+                    .prev()
+                    .and_then(|prior| aggregate.get(&prior))
+                    .filter(|base| *base != 0.0);
+                let value = match (change, base) {
+                    (Some(change), Some(base)) => Some(change / base * 100.0),
+                    _ => None,
+                };
+                (period, value)
+            })
+            .collect();
+        Ok(self.derive(Unit::PercentagePoints, observations))
+    }
+
+    /// Converts the series to the lower `frequency` by combining sub-periods with `method`, so that
+    /// monthly values become quarterly or annual, and quarterly values become annual.  Only
+    /// complete periods are returned: a quarter requires all three months, a year all four
+    /// quarters or twelve months.  If any sub-period is suppressed, the aggregate is `None`.
+    ///
+    /// Fails if `frequency` is higher than the frequency of the series.
+    ///
+    /// ```
+    /// use bears::{Aggregation, Frequency, Period, Series};
+    ///
+    /// let mut monthly = Series::default();
+    /// for period in Period::range(Period::Month(2023, 1), Period::Month(2023, 4)).unwrap() {
+    ///     monthly.insert(period, Some(1.0));
+    /// }
+    /// let quarterly = monthly.aggregate(Frequency::Quarterly, Aggregation::Sum).unwrap();
+    /// assert_eq!(quarterly.len(), 1);
+    /// assert_eq!(quarterly.get(&Period::Quarter(2023, 1)), Some(3.0));
+    /// ```
+    pub fn aggregate(
+        &self,
+        frequency: Frequency,
+        method: Aggregation,
+    ) -> Result<Self, FrequencyMismatch> {
+        let Some(source) = self.frequency() else {
+            return Ok(self.clone());
+        };
+        if source.periods_per_year() < frequency.periods_per_year() {
+            let error = FrequencyMismatch::new(
+                source.value(),
+                frequency.value(),
+                line!(),
+                file!().to_string(),
+            );
+            return Err(error);
+        }
+        let size = (source.periods_per_year() / frequency.periods_per_year()) as usize;
+        let mut groups = BTreeMap::<Period, Vec<Option<f64>>>::new();
+        for (period, value) in &self.observations {
+            groups
+                .entry(period.to_frequency(frequency))
+                .or_default()
+                .push(*value);
+        }
+        let observations = groups
+            .into_iter()
+            .filter(|(_, values)| values.len() == size)
+            .map(|(period, values)| {
+                let values = values.into_iter().collect::<Option<Vec<f64>>>();
+                (period, values.and_then(|v| method.apply(&v)))
+            })
+            .collect();
+        Ok(Self {
+            observations,
+            ..self.clone()
+        })
+    }
+
+    /// Converts the series to the lower `frequency` using the [`Aggregation`] suited to its
+    /// [`Measurement`]: flows sum, stocks keep the end-of-period value, and rates and flows at
+    /// annual rates average.  Fails if the series has no measurement, as with rates of change.
+    pub fn aggregate_by_measurement(&self, frequency: Frequency) -> Result<Self, BeaErr> {
+        match self.measurement.map(Aggregation::for_measurement) {
+            Some(method) => Ok(self.aggregate(frequency, method)?),
+            None => {
+                let error = VariantMissing::new(
+                    "No default aggregation for series".to_string(),
+                    self.unit.to_string(),
+                    line!(),
+                    file!().to_string(),
+                );
+                Err(error.into())
+            }
+        }
+    }
+
+    /// Compares the aggregate of `self` against `published` values of the same series at a lower
+    /// frequency, such as BEA's own annual values for a quarterly series.  Reports each period
+    /// where the relative difference exceeds `tolerance`.  Periods missing from either series are
+    /// skipped.
+    ///
+    /// Fails if the units differ or `published` has a higher frequency than `self`.
+    pub fn compare_aggregate(
+        &self,
+        published: &Self,
+        method: Aggregation,
+        tolerance: f64,
+    ) -> Result<Vec<Discrepancy>, BeaErr> {
+        let left = Measure::new(0.0, self.unit.clone(), self.unit_mult);
+        let right = Measure::new(0.0, published.unit.clone(), published.unit_mult);
+        left.check_unit(&right, "compare")?;
+        let Some(frequency) = published.frequency() else {
+            return Ok(Vec::new());
+        };
+        let computed = self.aggregate(frequency, method)?;
+        let scale = 10_f64.powi((published.unit_mult - self.unit_mult) as i32);
+        let discrepancies = computed
+            .values()
+            .filter_map(|(period, value)| {
+                let target = published.get(period)? * scale;
+                let difference = (value - target).abs() / target.abs().max(f64::EPSILON);
+                if difference > tolerance {
+                    Some(Discrepancy::new(
+                        self.code.clone(),
+                        self.table_name.clone(),
+                        *period,
+                        value,
+                        target,
+                    ))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        Ok(discrepancies)
+    }
+}
+
+/// The `Measurement` enum records what the values of a [`Series`] measure over time, which decides
+/// the [`Aggregation`] used to convert it to a lower frequency.
+///
+/// ```
+/// use bears::{Aggregation, Measurement, Unit};
+///
+/// let dollars = Unit::CurrentDollars;
+/// let gdp = Measurement::infer(&dollars, &["Gross domestic product"], Measurement::AnnualRate);
+/// assert_eq!(gdp.map(Aggregation::for_measurement), Some(Aggregation::Average));
+/// let stock = Measurement::infer(&dollars, &["Net Stock of Fixed Assets"], Measurement::Flow);
+/// assert_eq!(stock.map(Aggregation::for_measurement), Some(Aggregation::EndOfPeriod));
+/// assert_eq!(Measurement::infer(&Unit::PercentChange, &[], Measurement::Flow), None);
+/// ```
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    strum::EnumIter,
+    derive_more::Display,
+)]
+pub enum Measurement {
+    /// Quantities accumulated over the period, such as hours worked or annual investment.
+    Flow,
+    /// Flows seasonally adjusted and expressed at annual rates, as with quarterly and monthly NIPA
+    /// dollar values.
+    AnnualRate,
+    /// Positions measured at the end of the period, such as net stocks of fixed assets,
+    /// inventories and direct investment positions.
+    Stock,
+    /// Indexes, percentages and averages over the period, such as price indexes and employment.
+    Rate,
+}
+
+impl Measurement {
+    /// Phrases in table titles and line descriptions marking a stock.
+    const STOCKS: [&str; 4] = ["net stock", "inventories", "position", "outstanding"];
+
+    /// Infers the measurement of values in `unit`, using the table titles and line descriptions
+    /// in `labels` to recognize stocks.  Dollar values not recognized as stocks take `dollars`,
+    /// which depends on whether the dataset reports them at annual rates.  Rates of change and
+    /// unrecognized units return `None`.
+    pub fn infer(unit: &Unit, labels: &[&str], dollars: Self) -> Option<Self> {
+        match unit {
+            Unit::CurrentDollars | Unit::ChainedDollars | Unit::ConstantDollars => {
+                let labels = labels.join(" ").to_lowercase();
+                // "Change in private inventories" is a flow, not a stock.
+                let stock = Self::STOCKS.iter().any(|phrase| labels.contains(phrase))
+                    && !labels.contains("change in");
+                Some(if stock { Self::Stock } else { dollars })
+            }
+            Unit::Index(_) | Unit::Percent | Unit::Persons => Some(Self::Rate),
+            Unit::Hours => Some(Self::Flow),
+            Unit::PercentChange
+            | Unit::PercentChangeAnnualRate
+            | Unit::PercentagePoints
+            | Unit::Other(_) => None,
+        }
+    }
+}
+
+/// The `Aggregation` enum names the method used to combine sub-periods when converting a series
+/// to a lower frequency.
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    strum::EnumIter,
+    derive_more::Display,
+)]
+pub enum Aggregation {
+    /// Total of the sub-periods, for flows not reported at annual rates.
+    Sum,
+    /// Mean of the sub-periods, for flows reported at annual rates, indexes and levels.
+    Average,
+    /// Value of the last sub-period, for stocks measured at the end of the period.
+    EndOfPeriod,
+}
+
+impl Aggregation {
+    /// The conventional aggregation for a series of the given `measurement`.
+    pub fn for_measurement(measurement: Measurement) -> Self {
+        match measurement {
+            Measurement::Flow => Self::Sum,
+            Measurement::Stock => Self::EndOfPeriod,
+            Measurement::AnnualRate | Measurement::Rate => Self::Average,
+        }
+    }
+
+    /// Combines `values` using the method.  Returns `None` if `values` is empty.
+    pub fn apply(&self, values: &[f64]) -> Option<f64> {
+        if values.is_empty() {
+            return None;
+        }
+        let value = match self {
+            Self::Sum => values.iter().sum(),
+            Self::Average => values.iter().sum::<f64>() / values.len() as f64,
+            Self::EndOfPeriod => values[values.len() - 1],
+        };
+        Some(value)
+    }
+}
+
+/// The `Discrepancy` struct reports a period where an aggregated series disagrees with the value
+/// published by the BEA.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    PartialOrd,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_new::new,
+    derive_getters::Getters,
+)]
+pub struct Discrepancy {
+    code: String,
+    table_name: String,
+    period: Period,
+    /// Value aggregated from the higher-frequency series.
+    computed: f64,
+    /// Value published at the lower frequency.
+    published: f64,
+}
+
+/// Identifies a [`Series`] within a [`SeriesCollection`].  The same series code can appear in
+/// several tables and at several frequencies.
+type SeriesKey = (String, String, Frequency);
+
+/// The `SeriesCollection` struct groups the records of a GetData response into a [`Series`] for
+/// each series code, table and frequency.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    PartialEq,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_more::Deref,
+    derive_more::DerefMut,
+)]
+pub struct SeriesCollection(BTreeMap<SeriesKey, Series>);
+
+impl SeriesCollection {
+    /// The series for `table_name`, `code` and the frequency of `period`.  The series is built
+    /// from `template` only when the key is new, so records of an existing series do not copy
+    /// its metadata.
+    fn entry(
+        &mut self,
+        table_name: &str,
+        code: String,
+        period: &Period,
+        template: impl FnOnce() -> Series,
+    ) -> &mut Series {
+        let key = (table_name.to_string(), code, period.frequency());
+        self.0.entry(key).or_insert_with(template)
+    }
+
+    /// Adds a NIPA record to the collection, creating the series if necessary.
+    pub fn push_nipa(&mut self, datum: &NipaDatum) {
+        let period = datum.time_period();
+        let code = datum.series_code().clone();
+        self.entry(datum.table_name(), code, period, || {
+            Series::from_nipa(datum)
+        })
+        .insert(*period, *datum.data_value());
+    }
+
+    /// Adds a Fixed Assets record to the collection, creating the series if necessary.
+    pub fn push_fixed_assets(&mut self, datum: &FixedAssetDatum) {
+        let period = datum.time_period();
+        let code = datum.series_code().clone();
+        self.entry(datum.table_name(), code, period, || {
+            Series::from_fixed_assets(datum)
+        })
+        .insert(*period, *datum.data_value());
+    }
+
+    /// Adds an MNE record to the collection, creating the series if necessary.  Suppressed values
+    /// are recorded as `None`.
+    pub fn push_mne(&mut self, datum: &MneDiDatum) {
+        let period = datum.year();
+        let value = datum.data_value_unformatted().number().map(|v| v as f64);
+        let code = Series::mne_code(datum);
+        self.entry(datum.series_name(), code, period, || {
+            Series::from_mne(datum)
+        })
+        .insert(*period, value);
+    }
+
+    /// Iterates over each series with the given series `code`, across tables and frequencies.
+    pub fn by_code<'a>(&'a self, code: &'a str) -> impl Iterator<Item = &'a Series> {
+        self.0.values().filter(move |s| s.code == code)
+    }
+
+    /// Iterates over each series in the table `table_name`, ordered by line number.
+    pub fn by_table(&self, table_name: &str) -> Vec<&Series> {
+        let mut series = self
+            .0
+            .values()
+            .filter(|s| s.table_name == table_name)
+            .collect::<Vec<&Series>>();
+        series.sort_by_key(|s| s.line_number);
+        series
+    }
+
+    /// The series on line `line_number` of the table `table_name` at `frequency`.
+    pub fn by_line(
+        &self,
+        table_name: &str,
+        line_number: i64,
+        frequency: Frequency,
+    ) -> Option<&Series> {
+        self.0.values().find(|s| {
+            s.table_name == table_name
+                && s.line_number == Some(line_number)
+                && s.frequency() == Some(frequency)
+        })
+    }
+
+    /// The series with the given `code` at `frequency`.  If the code appears in several tables,
+    /// returns the first by table name.
+    pub fn get(&self, code: &str, frequency: Frequency) -> Option<&Series> {
+        self.0
+            .values()
+            .find(|s| s.code == code && s.frequency() == Some(frequency))
+    }
+
+    /// The names of the tables present in the collection.
+    pub fn table_names(&self) -> std::collections::BTreeSet<&String> {
+        self.0.values().map(|s| &s.table_name).collect()
+    }
+
+    /// Checks that each series present at several frequencies agrees with itself, by aggregating
+    /// the higher frequency with [`Series::aggregate_by_measurement`] and comparing against the
+    /// lower.  Catches files mixed up across frequencies.  Series without a [`Measurement`] are
+    /// skipped.
+    pub fn check_frequencies(&self, tolerance: f64) -> Result<Vec<Discrepancy>, BeaErr> {
+        let mut discrepancies = Vec::new();
+        for ((table, code, frequency), series) in &self.0 {
+            let Some(method) = series.measurement.map(Aggregation::for_measurement) else {
+                tracing::trace!(
+                    "Skipping {table} {code}: no aggregation for {}.",
+                    series.unit
+                );
+                continue;
+            };
+            for published in [Frequency::Annual, Frequency::Quarterly] {
+                if published.periods_per_year() >= frequency.periods_per_year() {
+                    continue;
+                }
+                let key = (table.clone(), code.clone(), published);
+                if let Some(other) = self.0.get(&key) {
+                    discrepancies.extend(series.compare_aggregate(other, method, tolerance)?);
+                }
+            }
+        }
+        if !discrepancies.is_empty() {
+            tracing::warn!("{} discrepancies across frequencies.", discrepancies.len());
+        }
+        Ok(discrepancies)
+    }
+}
+
+impl From<&NipaData> for SeriesCollection {
+    fn from(value: &NipaData) -> Self {
+        let mut collection = Self::default();
+        value.iter().for_each(|datum| collection.push_nipa(datum));
+        collection
+    }
+}
+
+impl From<&FixedAssetData> for SeriesCollection {
+    fn from(value: &FixedAssetData) -> Self {
+        let mut collection = Self::default();
+        value
+            .iter()
+            .for_each(|datum| collection.push_fixed_assets(datum));
+        collection
+    }
+}
+
+impl From<&MneDiData> for SeriesCollection {
+    fn from(value: &MneDiData) -> Self {
+        let mut collection = Self::default();
+        value.iter().for_each(|datum| collection.push_mne(datum));
+        collection
+    }
+}
+
+impl From<&Data> for SeriesCollection {
+    fn from(value: &Data) -> Self {
+        match value {
+            Data::Nipa(data) => Self::from(data),
+            Data::FixedAssets(data) => Self::from(data),
+            Data::MneDi(data) => Self::from(data),
+        }
+    }
+}
@@ -0,0 +1,830 @@
+use crate::{outline, BeaErr, IoError, ReqwestError, SerdeJson, StorageStatus};
+use std::collections::BTreeMap;
+
+/// The `StorageKey` struct identifies a stored object by its logical location, a relative path
+/// with `/` separators such as "data/NIPA/NIPA_T10101.json".  The key for a request is
+/// [`App::storage_key`](crate::App::storage_key), matching the layout of the `BEA_DATA`
+/// directory.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_more::Display,
+    derive_more::AsRef,
+)]
+#[as_ref(str)]
+pub struct StorageKey(String);
+
+impl StorageKey {
+    /// Creates a key from `key`, dropping leading and trailing separators.
+    pub fn new<S: AsRef<str>>(key: S) -> Self {
+        Self(key.as_ref().trim_matches('/').to_string())
+    }
+
+    /// Creates a key from the relative `path`, joining its components with `/`.
+    pub fn from_path<P: AsRef<std::path::Path>>(path: P) -> Self {
+        let key = path
+            .as_ref()
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        Self::new(key)
+    }
+
+    /// The key as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Indicates `true` if the key starts with the folder or key `prefix`.
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        self.0.starts_with(prefix.trim_start_matches('/'))
+    }
+}
+
+impl From<&str> for StorageKey {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+/// The `Storage` trait abstracts where the library persists responses, histories and caches.
+/// Objects are byte strings addressed by [`StorageKey`].
+///
+/// Implemented by [`FsStorage`] for the `BEA_DATA` directory layout, [`MemoryStorage`] for
+/// tests and short-lived processes, and [`S3Storage`] for S3-compatible object stores.  The
+/// [`Backend`] enum holds any of the three.
+pub trait Storage {
+    /// Writes `bytes` to `key`, replacing any previous contents.
+    fn put(
+        &self,
+        key: &StorageKey,
+        bytes: Vec<u8>,
+    ) -> impl Future<Output = Result<(), BeaErr>> + Send;
+
+    /// Reads the contents of `key`, or `None` if absent.
+    fn get(&self, key: &StorageKey)
+        -> impl Future<Output = Result<Option<Vec<u8>>, BeaErr>> + Send;
+
+    /// Lists the keys beginning with `prefix`, in sorted order.
+    fn list(&self, prefix: &str) -> impl Future<Output = Result<Vec<StorageKey>, BeaErr>> + Send;
+
+    /// Indicates `true` if `key` is present.
+    fn exists(&self, key: &StorageKey) -> impl Future<Output = Result<bool, BeaErr>> + Send;
+
+    /// Removes `key`, returning `true` if it was present.
+    fn delete(&self, key: &StorageKey) -> impl Future<Output = Result<bool, BeaErr>> + Send;
+}
+
+/// The `FsStorage` struct stores objects as files under the directory `root`, each key a path
+/// relative to the root.  Compatible with the layout written by [`App::save`](crate::App::save).
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_new::new,
+    derive_getters::Getters,
+)]
+pub struct FsStorage {
+    root: std::path::PathBuf,
+}
+
+impl FsStorage {
+    /// The file holding `key`.
+    pub fn path(&self, key: &StorageKey) -> std::path::PathBuf {
+        key.as_str()
+            .split('/')
+            .fold(self.root.clone(), |path, part| path.join(part))
+    }
+
+    /// Temporary file in the folder of `path`, renamed over `path` once fully written.  Hidden
+    /// with a leading dot and skipped when listing keys.
+    pub fn partial(path: &std::path::Path) -> std::path::PathBuf {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        path.with_file_name(format!(".{name}.{}.part", uuid::Uuid::new_v4()))
+    }
+
+    /// Writes `bytes` to a temporary file and renames it over `path`, so an interrupted write
+    /// never leaves a partial file at `path`.  Creates the parent folders if missing.
+    pub fn write_atomic(path: &std::path::Path, bytes: &[u8]) -> Result<(), BeaErr> {
+        if let Some(parent) = path.parent()
+            && !parent.exists()
+        {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| IoError::new(parent.into(), e, line!(), file!().into()))?;
+        }
+        let partial = Self::partial(path);
+        if let Err(e) = std::fs::write(&partial, bytes) {
+            std::fs::remove_file(&partial).ok();
+            return Err(IoError::new(partial, e, line!(), file!().into()).into());
+        }
+        std::fs::rename(&partial, path)
+            .map_err(|e| IoError::new(path.into(), e, line!(), file!().into()))?;
+        Ok(())
+    }
+
+    /// Reads the contents of `key`, or `None` if absent.  The blocking counterpart of
+    /// [`Storage::get`], for the parameter and value caches read outside an async context.
+    pub fn read(&self, key: &StorageKey) -> Result<Option<Vec<u8>>, BeaErr> {
+        let path = self.path(key);
+        match std::fs::read(&path) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(IoError::new(path, e, line!(), file!().into()).into()),
+        }
+    }
+
+    /// Parses the contents of `key` as JSON, reading the file through a buffer rather than into
+    /// memory first.  Fails if absent.
+    pub fn read_json(&self, key: &StorageKey) -> Result<serde_json::Value, BeaErr> {
+        let path = self.path(key);
+        let file = std::fs::File::open(&path)
+            .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
+        serde_json::from_reader(std::io::BufReader::new(file))
+            .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()).into())
+    }
+
+    /// Indicates `true` if `key` is present.  The blocking counterpart of [`Storage::exists`].
+    pub fn contains(&self, key: &StorageKey) -> bool {
+        self.path(key).is_file()
+    }
+
+    /// Lists the keys beginning with `prefix`, in sorted order.  The blocking counterpart of
+    /// [`Storage::list`].
+    pub fn keys(&self, prefix: &str) -> Result<Vec<StorageKey>, BeaErr> {
+        let mut keys = Vec::new();
+        if self.root.exists() {
+            self.walk(&self.root, &mut keys)?;
+        }
+        keys.retain(|key| key.starts_with(prefix));
+        keys.sort();
+        Ok(keys)
+    }
+
+    /// Removes `key`, returning `true` if it was present.
+    fn remove(&self, key: &StorageKey) -> Result<bool, BeaErr> {
+        let path = self.path(key);
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(IoError::new(path, e, line!(), file!().into()).into()),
+        }
+    }
+
+    /// Runs the filesystem call `f` on the blocking thread pool, so that reads and writes do not
+    /// stall the async runtime.
+    async fn blocking<T, F>(f: F) -> Result<T, BeaErr>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> Result<T, BeaErr> + Send + 'static,
+    {
+        match tokio::task::spawn_blocking(f).await {
+            Ok(result) => result,
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        }
+    }
+
+    /// Adds the files under `dir` to `keys`, descending into folders.
+    fn walk(&self, dir: &std::path::Path, keys: &mut Vec<StorageKey>) -> Result<(), BeaErr> {
+        let contents = std::fs::read_dir(dir)
+            .map_err(|e| IoError::new(dir.into(), e, line!(), file!().into()))?;
+        for item in contents {
+            let path = item
+                .map_err(|e| IoError::new(dir.into(), e, line!(), file!().into()))?
+                .path();
+            if path.is_dir() {
+                self.walk(&path, keys)?;
+            } else if path.extension().is_some_and(|ext| ext == "part") {
+                continue;
+            } else if let Ok(relative) = path.strip_prefix(&self.root) {
+                keys.push(StorageKey::from_path(relative));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Storage for FsStorage {
+    async fn put(&self, key: &StorageKey, bytes: Vec<u8>) -> Result<(), BeaErr> {
+        let path = self.path(key);
+        Self::blocking(move || Self::write_atomic(&path, &bytes)).await
+    }
+
+    async fn get(&self, key: &StorageKey) -> Result<Option<Vec<u8>>, BeaErr> {
+        let (storage, key) = (self.clone(), key.clone());
+        Self::blocking(move || storage.read(&key)).await
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<StorageKey>, BeaErr> {
+        let (storage, prefix) = (self.clone(), prefix.to_string());
+        Self::blocking(move || storage.keys(&prefix)).await
+    }
+
+    async fn exists(&self, key: &StorageKey) -> Result<bool, BeaErr> {
+        let (storage, key) = (self.clone(), key.clone());
+        Self::blocking(move || Ok(storage.contains(&key))).await
+    }
+
+    async fn delete(&self, key: &StorageKey) -> Result<bool, BeaErr> {
+        let (storage, key) = (self.clone(), key.clone());
+        Self::blocking(move || storage.remove(&key)).await
+    }
+}
+
+/// The `MemoryStorage` struct keeps objects in memory, for tests and for processes that do not
+/// need to keep responses.  Clones share the same contents.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStorage {
+    objects: std::sync::Arc<std::sync::Mutex<BTreeMap<StorageKey, Vec<u8>>>>,
+}
+
+impl MemoryStorage {
+    /// Locks the contents.  A panic while holding the lock cannot leave the map half-written,
+    /// so a poisoned lock is recovered.
+    fn objects(&self) -> std::sync::MutexGuard<'_, BTreeMap<StorageKey, Vec<u8>>> {
+        self.objects
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Number of objects stored.
+    pub fn len(&self) -> usize {
+        self.objects().len()
+    }
+
+    /// Indicates `true` if no objects are stored.
+    pub fn is_empty(&self) -> bool {
+        self.objects().is_empty()
+    }
+}
+
+impl Storage for MemoryStorage {
+    async fn put(&self, key: &StorageKey, bytes: Vec<u8>) -> Result<(), BeaErr> {
+        self.objects().insert(key.clone(), bytes);
+        Ok(())
+    }
+
+    async fn get(&self, key: &StorageKey) -> Result<Option<Vec<u8>>, BeaErr> {
+        Ok(self.objects().get(key).cloned())
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<StorageKey>, BeaErr> {
+        let keys = self
+            .objects()
+            .keys()
+            .filter(|key| key.starts_with(prefix))
+            .cloned()
+            .collect();
+        Ok(keys)
+    }
+
+    async fn exists(&self, key: &StorageKey) -> Result<bool, BeaErr> {
+        Ok(self.objects().contains_key(key))
+    }
+
+    async fn delete(&self, key: &StorageKey) -> Result<bool, BeaErr> {
+        Ok(self.objects().remove(key).is_some())
+    }
+}
+
+/// SHA-256 digest of an empty payload, sent with requests that have no body.
+pub const EMPTY_SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+/// Encodes `bytes` as lowercase hexadecimal.
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Percent-encodes `value` as required by AWS Signature Version 4, leaving unreserved
+/// characters, and `/` if `path` is set, unchanged.
+fn uri_encode(value: &str, path: bool) -> String {
+    let mut encoded = String::new();
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b'/' if path => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+/// Replaces the XML entities the S3 API uses in object keys.
+fn xml_unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// The text of each `<tag>` element in the XML document `xml`.
+fn xml_values<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let mut values = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+        match rest.find(&close) {
+            Some(end) => {
+                values.push(&rest[..end]);
+                rest = &rest[end + close.len()..];
+            }
+            None => break,
+        }
+    }
+    values
+}
+
+/// The `S3Storage` struct stores objects in a bucket of an S3-compatible object store, such as
+/// Amazon S3 or a local MinIO server.  Requests use path-style addressing
+/// (`{endpoint}/{bucket}/{key}`), which MinIO accepts without DNS setup, and are signed with AWS
+/// Signature Version 4.
+///
+/// ```
+/// use bears::S3Storage;
+///
+/// let endpoint = url::Url::parse("http://localhost:9000").unwrap();
+/// let storage = S3Storage::new(endpoint, "bea", "us-east-1", "minioadmin", "minioadmin");
+/// assert_eq!(storage.bucket(), "bea");
+/// ```
+#[derive(Debug, Clone, derive_getters::Getters)]
+pub struct S3Storage {
+    endpoint: url::Url,
+    bucket: String,
+    region: String,
+    access_key: String,
+    #[getter(skip)]
+    secret_key: String,
+    /// Optional prefix for every key, so that several mirrors can share a bucket.
+    prefix: Option<String>,
+    #[getter(skip)]
+    http: reqwest::Client,
+}
+
+impl S3Storage {
+    /// Creates a backend for `bucket` at `endpoint`, signing requests for `region` with the
+    /// credentials `access_key` and `secret_key`.  Requests share the pooled HTTP client with the
+    /// connect and request timeouts used for the BEA server until replaced with [`Self::with_http`].
+    pub fn new<S: ToString>(
+        endpoint: url::Url,
+        bucket: S,
+        region: S,
+        access_key: S,
+        secret_key: S,
+    ) -> Self {
+        Self {
+            bucket: bucket.to_string(),
+            region: region.to_string(),
+            access_key: access_key.to_string(),
+            secret_key: secret_key.to_string(),
+            prefix: None,
+            http: crate::client::shared_http(&endpoint).clone(),
+            endpoint,
+        }
+    }
+
+    /// Stores every key under the folder `prefix` within the bucket.
+    pub fn with_prefix<S: AsRef<str>>(mut self, prefix: S) -> Self {
+        let prefix = prefix.as_ref().trim_matches('/');
+        self.prefix = (!prefix.is_empty()).then(|| prefix.to_string());
+        self
+    }
+
+    /// Sends requests using the HTTP client `http`, such as the pool of a
+    /// [`BeaClient`](crate::BeaClient).
+    pub fn with_http(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// The object name for `key`, including the prefix.
+    fn object(&self, key: &str) -> String {
+        match &self.prefix {
+            Some(prefix) if key.is_empty() => format!("{prefix}/"),
+            Some(prefix) => format!("{prefix}/{key}"),
+            None => key.to_string(),
+        }
+    }
+
+    /// The url of the object `object`, or of the bucket if `object` is empty.
+    fn url(&self, object: &str) -> url::Url {
+        let mut url = self.endpoint.clone();
+        let base = url.path().trim_end_matches('/').to_string();
+        let path = match object.is_empty() {
+            true => format!("{base}/{}", self.bucket),
+            false => format!("{base}/{}/{}", self.bucket, uri_encode(object, true)),
+        };
+        url.set_path(&path);
+        url
+    }
+
+    /// The `Authorization` header value signing a request with `method` to `url`, with the
+    /// payload digest `payload` (hex SHA-256) at time `amz_date` (such as "20130524T000000Z").
+    /// Signs the `host`, `x-amz-content-sha256` and `x-amz-date` headers.
+    pub fn authorization(
+        &self,
+        method: &str,
+        url: &url::Url,
+        payload: &str,
+        amz_date: &str,
+    ) -> String {
+        let host = match url.port() {
+            Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+        let mut query = url
+            .query_pairs()
+            .map(|(k, v)| (uri_encode(&k, false), uri_encode(&v, false)))
+            .collect::<Vec<(String, String)>>();
+        query.sort();
+        let query = query
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect::<Vec<String>>()
+            .join("&");
+        let path = match url.path() {
+            "" => "/",
+            path => path,
+        };
+        let headers =
+            format!("host:{host}\nx-amz-content-sha256:{payload}\nx-amz-date:{amz_date}\n");
+        let signed = "host;x-amz-content-sha256;x-amz-date";
+        let canonical = format!("{method}\n{path}\n{query}\n{headers}\n{signed}\n{payload}");
+        let date = &amz_date[..8.min(amz_date.len())];
+        let scope = format!("{date}/{}/s3/aws4_request", self.region);
+        let digest = hex(&hmac_sha256::Hash::hash(canonical.as_bytes()));
+        let to_sign = format!("AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{digest}");
+        let key = hmac_sha256::HMAC::mac(date, format!("AWS4{}", self.secret_key));
+        let key = hmac_sha256::HMAC::mac(&self.region, key);
+        let key = hmac_sha256::HMAC::mac("s3", key);
+        let key = hmac_sha256::HMAC::mac("aws4_request", key);
+        let signature = hex(&hmac_sha256::HMAC::mac(to_sign, key));
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed}, Signature={signature}",
+            self.access_key
+        )
+    }
+
+    /// Sends a signed request with `method` to `url`, with `body` as the payload.
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        url: url::Url,
+        body: Vec<u8>,
+    ) -> Result<reqwest::Response, BeaErr> {
+        let payload = match body.is_empty() {
+            true => EMPTY_SHA256.to_string(),
+            false => hex(&hmac_sha256::Hash::hash(&body)),
+        };
+        let amz_date = jiff::Timestamp::now()
+            .strftime("%Y%m%dT%H%M%SZ")
+            .to_string();
+        let authorization = self.authorization(method.as_str(), &url, &payload, &amz_date);
+        let request = self
+            .http
+            .request(method.clone(), url.clone())
+            .header("x-amz-content-sha256", payload)
+            .header("x-amz-date", amz_date)
+            .header("authorization", authorization)
+            .body(body);
+        request.send().await.map_err(|source| {
+            ReqwestError::new(
+                url.to_string(),
+                method.to_string(),
+                source,
+                line!(),
+                file!().to_string(),
+            )
+            .into()
+        })
+    }
+
+    /// Fails unless the `response` to a request for `key` succeeded.
+    fn check(&self, key: &str, response: &reqwest::Response) -> Result<(), StorageStatus> {
+        let status = response.status();
+        match status.is_success() {
+            true => Ok(()),
+            false => Err(StorageStatus::new(
+                format!("{}/{}", self.bucket, key),
+                status.as_u16(),
+                line!(),
+                file!().to_string(),
+            )),
+        }
+    }
+}
+
+impl Storage for S3Storage {
+    async fn put(&self, key: &StorageKey, bytes: Vec<u8>) -> Result<(), BeaErr> {
+        let object = self.object(key.as_str());
+        let response = self
+            .send(reqwest::Method::PUT, self.url(&object), bytes)
+            .await?;
+        self.check(&object, &response)?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &StorageKey) -> Result<Option<Vec<u8>>, BeaErr> {
+        let object = self.object(key.as_str());
+        let url = self.url(&object);
+        let response = self
+            .send(reqwest::Method::GET, url.clone(), Vec::new())
+            .await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        self.check(&object, &response)?;
+        let bytes = response.bytes().await.map_err(|source| {
+            ReqwestError::new(
+                url.to_string(),
+                "get".to_string(),
+                source,
+                line!(),
+                file!().to_string(),
+            )
+        })?;
+        Ok(Some(bytes.to_vec()))
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<StorageKey>, BeaErr> {
+        let prefix = self.object(prefix.trim_start_matches('/'));
+        let mut keys = Vec::new();
+        let mut token: Option<String> = None;
+        loop {
+            let mut url = self.url("");
+            {
+                let mut query = url.query_pairs_mut();
+                query.append_pair("list-type", "2");
+                query.append_pair("prefix", &prefix);
+                if let Some(token) = &token {
+                    query.append_pair("continuation-token", token);
+                }
+            }
+            let response = self
+                .send(reqwest::Method::GET, url.clone(), Vec::new())
+                .await?;
+            self.check(&prefix, &response)?;
+            let xml = response.text().await.map_err(|source| {
+                ReqwestError::new(
+                    url.to_string(),
+                    "get".to_string(),
+                    source,
+                    line!(),
+                    file!().to_string(),
+                )
+            })?;
+            for key in xml_values(&xml, "Key") {
+                let key = xml_unescape(key);
+                let key = match &self.prefix {
+                    Some(root) => key.strip_prefix(root.as_str()).unwrap_or(&key).to_string(),
+                    None => key,
+                };
+                keys.push(StorageKey::new(key));
+            }
+            token = xml_values(&xml, "NextContinuationToken")
+                .first()
+                .map(|t| xml_unescape(t));
+            if token.is_none() {
+                break;
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+
+    async fn exists(&self, key: &StorageKey) -> Result<bool, BeaErr> {
+        let object = self.object(key.as_str());
+        let response = self
+            .send(reqwest::Method::HEAD, self.url(&object), Vec::new())
+            .await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        self.check(&object, &response)?;
+        Ok(true)
+    }
+
+    async fn delete(&self, key: &StorageKey) -> Result<bool, BeaErr> {
+        // S3 reports success whether or not the key existed
+        let present = self.exists(key).await?;
+        if present {
+            let object = self.object(key.as_str());
+            let response = self
+                .send(reqwest::Method::DELETE, self.url(&object), Vec::new())
+                .await?;
+            self.check(&object, &response)?;
+        }
+        Ok(present)
+    }
+}
+
+/// The `Backend` enum holds one of the [`Storage`] implementations, so that a
+/// [`BeaClient`](crate::BeaClient) can switch between them at run time.  Clones share the
+/// underlying storage.
+#[derive(Debug, Clone, derive_more::From)]
+pub enum Backend {
+    Fs(FsStorage),
+    Memory(MemoryStorage),
+    S3(S3Storage),
+}
+
+impl Backend {
+    /// Starts writing the object at `key` one chunk at a time.  The filesystem backend streams
+    /// the chunks to a temporary file beside the destination, while other backends collect them
+    /// in memory.  Nothing reaches `key` until [`Staged::commit`].
+    pub fn stage(&self, key: &StorageKey) -> Result<Staged, BeaErr> {
+        let sink = match self {
+            Self::Fs(storage) => {
+                let path = storage.path(key);
+                if let Some(parent) = path.parent()
+                    && !parent.exists()
+                {
+                    std::fs::create_dir_all(parent)
+                        .map_err(|e| IoError::new(parent.into(), e, line!(), file!().into()))?;
+                }
+                let partial = FsStorage::partial(&path);
+                let file = std::fs::File::create(&partial)
+                    .map_err(|e| IoError::new(partial.clone(), e, line!(), file!().into()))?;
+                Sink::File {
+                    file: std::io::BufWriter::new(file),
+                    partial,
+                    path,
+                }
+            }
+            Self::Memory(_) | Self::S3(_) => Sink::Buffer(Vec::new()),
+        };
+        Ok(Staged {
+            key: key.clone(),
+            storage: self.clone(),
+            sink: Some(sink),
+            written: 0,
+        })
+    }
+}
+
+/// Destination of the chunks written to a [`Staged`] object.
+#[derive(Debug)]
+enum Sink {
+    File {
+        file: std::io::BufWriter<std::fs::File>,
+        partial: std::path::PathBuf,
+        path: std::path::PathBuf,
+    },
+    Buffer(Vec<u8>),
+}
+
+/// The `Staged` struct is an object being written to a [`Backend`] one chunk at a time, created
+/// by [`Backend::stage`].  Validate the contents with [`Staged::outline`] before calling
+/// [`Staged::commit`] to move them to the key.  Dropping an uncommitted object, such as on an
+/// error or a cancelled download, removes its temporary file and leaves the key untouched.
+#[derive(Debug)]
+pub struct Staged {
+    key: StorageKey,
+    storage: Backend,
+    sink: Option<Sink>,
+    written: u64,
+}
+
+impl Staged {
+    /// Appends `chunk` to the object.
+    pub fn write(&mut self, chunk: &[u8]) -> Result<(), BeaErr> {
+        match &mut self.sink {
+            Some(Sink::File { file, partial, .. }) => {
+                std::io::Write::write_all(file, chunk)
+                    .map_err(|e| IoError::new(partial.clone(), e, line!(), file!().into()))?;
+            }
+            Some(Sink::Buffer(buffer)) => buffer.extend_from_slice(chunk),
+            None => {}
+        }
+        self.written += chunk.len() as u64;
+        Ok(())
+    }
+
+    /// Bytes written so far.
+    pub fn written(&self) -> u64 {
+        self.written
+    }
+
+    /// The key receiving the object on commit.
+    pub fn key(&self) -> &StorageKey {
+        &self.key
+    }
+
+    /// Reads the contents written so far as a response [`outline`], failing if they are
+    /// incomplete or invalid JSON.  The contents are parsed in a single streaming pass that drops
+    /// the data records, so validating a large download does not hold it in memory.
+    pub fn outline(&mut self) -> Result<serde_json::Value, BeaErr> {
+        match &mut self.sink {
+            Some(Sink::File { file, partial, .. }) => {
+                std::io::Write::flush(file)
+                    .map_err(|e| IoError::new(partial.clone(), e, line!(), file!().into()))?;
+                let reader = std::fs::File::open(&partial)
+                    .map_err(|e| IoError::new(partial.clone(), e, line!(), file!().into()))?;
+                outline(std::io::BufReader::new(reader))
+            }
+            Some(Sink::Buffer(buffer)) => outline(buffer.as_slice()),
+            None => Ok(serde_json::Value::Null),
+        }
+    }
+
+    /// Moves the object to its key, renaming the temporary file over the destination or sending
+    /// the collected bytes to the backend.  Returns the bytes written.
+    pub async fn commit(mut self) -> Result<u64, BeaErr> {
+        match self.sink.take() {
+            Some(Sink::File {
+                file,
+                partial,
+                path,
+            }) => {
+                FsStorage::blocking(move || {
+                    let file = file.into_inner().map_err(|e| {
+                        IoError::new(partial.clone(), e.into_error(), line!(), file!().into())
+                    })?;
+                    let committed = file
+                        .sync_all()
+                        .and_then(|_| std::fs::rename(&partial, &path));
+                    if let Err(e) = committed {
+                        std::fs::remove_file(&partial).ok();
+                        return Err(IoError::new(path, e, line!(), file!().into()).into());
+                    }
+                    Ok(())
+                })
+                .await?;
+            }
+            Some(Sink::Buffer(buffer)) => self.storage.put(&self.key, buffer).await?,
+            None => {}
+        }
+        Ok(self.written)
+    }
+}
+
+impl Drop for Staged {
+    fn drop(&mut self) {
+        if let Some(Sink::File { partial, .. }) = self.sink.take() {
+            std::fs::remove_file(&partial).ok();
+        }
+    }
+}
+
+impl Storage for Backend {
+    async fn put(&self, key: &StorageKey, bytes: Vec<u8>) -> Result<(), BeaErr> {
+        match self {
+            Self::Fs(storage) => storage.put(key, bytes).await,
+            Self::Memory(storage) => storage.put(key, bytes).await,
+            Self::S3(storage) => storage.put(key, bytes).await,
+        }
+    }
+
+    async fn get(&self, key: &StorageKey) -> Result<Option<Vec<u8>>, BeaErr> {
+        match self {
+            Self::Fs(storage) => storage.get(key).await,
+            Self::Memory(storage) => storage.get(key).await,
+            Self::S3(storage) => storage.get(key).await,
+        }
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<StorageKey>, BeaErr> {
+        match self {
+            Self::Fs(storage) => storage.list(prefix).await,
+            Self::Memory(storage) => storage.list(prefix).await,
+            Self::S3(storage) => storage.list(prefix).await,
+        }
+    }
+
+    async fn exists(&self, key: &StorageKey) -> Result<bool, BeaErr> {
+        match self {
+            Self::Fs(storage) => storage.exists(key).await,
+            Self::Memory(storage) => storage.exists(key).await,
+            Self::S3(storage) => storage.exists(key).await,
+        }
+    }
+
+    async fn delete(&self, key: &StorageKey) -> Result<bool, BeaErr> {
+        match self {
+            Self::Fs(storage) => storage.delete(key).await,
+            Self::Memory(storage) => storage.delete(key).await,
+            Self::S3(storage) => storage.delete(key).await,
+        }
+    }
+}
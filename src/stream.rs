@@ -0,0 +1,541 @@
+use crate::{
+    results::{ApiError, MneError},
+    BeaErr, Csv, Dataset, DatasetMissing, IoError, JsonParseError, JsonParseErrorKind, KeyMissing,
+    NotArray, NotObject, RequestParameters, SerdeJson, VariantMissing,
+};
+use serde::de::{DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+
+/// Number of raw records buffered between the parsing thread and the consumer.  Bounds the memory
+/// used by a [`DataStream`] regardless of the size of the source file.
+pub const STREAM_BUFFER: usize = 1024;
+
+/// A single record from the `Data` array, prior to conversion into a datum type.
+pub type Record = serde_json::Map<String, serde_json::Value>;
+
+type Reader<T> = Box<dyn FnMut(&Record) -> Result<T, BeaErr>>;
+
+/// The `DataStream` struct walks the `BEAAPI.Results.Data` array of a GetData response one record
+/// at a time.
+///
+/// A background thread deserializes the file incrementally, sending each record across a bounded
+/// channel, so loading runs in memory proportional to [`STREAM_BUFFER`] rather than the size of the
+/// file.  The `reader` converts each raw record into the target type `T` as the iterator advances.
+///
+/// Dropping the stream before it is exhausted closes the channel, and the background thread stops
+/// parsing at the next record.
+pub struct DataStream<T> {
+    rx: std::sync::mpsc::Receiver<Result<Record, BeaErr>>,
+    reader: Reader<T>,
+    handle: Option<std::thread::JoinHandle<()>>,
+}
+
+impl<T> DataStream<T> {
+    /// Opens the file at `path` and begins streaming records, converting each using `reader`.
+    pub fn from_path<P, F>(path: P, reader: F) -> Result<Self, BeaErr>
+    where
+        P: AsRef<std::path::Path>,
+        F: FnMut(&Record) -> Result<T, BeaErr> + 'static,
+    {
+        Self::from_path_for(path, &[], reader)
+    }
+
+    /// Opens the file at `path` and streams records as [`DataStream::from_path`], failing with
+    /// [`DatasetMissing`] if the `DATASETNAME` of the request recorded in the response is not one
+    /// of `datasets`.  An empty slice accepts any dataset.
+    pub fn from_path_for<P, F>(path: P, datasets: &[Dataset], reader: F) -> Result<Self, BeaErr>
+    where
+        P: AsRef<std::path::Path>,
+        F: FnMut(&Record) -> Result<T, BeaErr> + 'static,
+    {
+        let path = std::path::PathBuf::from(path.as_ref());
+        let file = std::fs::File::open(&path)
+            .map_err(|e| IoError::new(path.clone(), e, line!(), file!().into()))?;
+        tracing::trace!("Streaming records from {path:?}.");
+        Ok(Self::from_reader_for(
+            std::io::BufReader::new(file),
+            datasets,
+            reader,
+        ))
+    }
+
+    /// Streams records from any source implementing [`std::io::Read`].
+    pub fn from_reader<R, F>(rdr: R, reader: F) -> Self
+    where
+        R: std::io::Read + Send + 'static,
+        F: FnMut(&Record) -> Result<T, BeaErr> + 'static,
+    {
+        Self::from_reader_for(rdr, &[], reader)
+    }
+
+    /// Streams records from `rdr`, checking the dataset of the response against `datasets` as
+    /// [`DataStream::from_path_for`].
+    pub fn from_reader_for<R, F>(rdr: R, datasets: &[Dataset], reader: F) -> Self
+    where
+        R: std::io::Read + Send + 'static,
+        F: FnMut(&Record) -> Result<T, BeaErr> + 'static,
+    {
+        let (tx, rx) = std::sync::mpsc::sync_channel(STREAM_BUFFER);
+        let datasets = datasets.to_vec();
+        let handle = std::thread::spawn(move || Self::parse(rdr, tx, datasets));
+        Self {
+            rx,
+            reader: Box::new(reader),
+            handle: Some(handle),
+        }
+    }
+
+    /// Deserializes the response, sending records to `tx` as they are read.  Runs on the
+    /// background thread.
+    fn parse<R: std::io::Read>(
+        rdr: R,
+        tx: std::sync::mpsc::SyncSender<Result<Record, BeaErr>>,
+        datasets: Vec<Dataset>,
+    ) {
+        let mut de = serde_json::Deserializer::from_reader(rdr);
+        let channel = Channel {
+            tx,
+            closed: std::cell::Cell::new(false),
+            datasets,
+        };
+        let tx = &channel;
+        let seed = ResponseSeed { tx };
+        let result = seed
+            .deserialize(&mut de)
+            .and_then(|result| de.end().map(|_| result));
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(error)) => {
+                tx.send(Err(error)).ok();
+            }
+            // The consumer dropped the stream, so there is nobody left to tell.
+            Err(_) if tx.closed.get() => {}
+            Err(source) => {
+                let error = SerdeJson::new(source, line!(), file!().to_string());
+                tx.send(Err(error.into())).ok();
+            }
+        }
+    }
+
+    /// Writes each record of the stream to a CSV file at `path`, without holding more than
+    /// [`STREAM_BUFFER`] records in memory.  Returns the number of records written.
+    pub fn to_csv<P: AsRef<std::path::Path>>(self, path: P) -> Result<usize, BeaErr>
+    where
+        T: serde::Serialize,
+    {
+        let path = std::path::PathBuf::from(path.as_ref());
+        let mut wtr = csv::Writer::from_path(&path)
+            .map_err(|e| Csv::new(path.clone(), e, line!(), file!().to_string()))?;
+        let mut count = 0;
+        for datum in self {
+            wtr.serialize(datum?)
+                .map_err(|e| Csv::new(path.clone(), e, line!(), file!().to_string()))?;
+            count += 1;
+        }
+        wtr.flush()
+            .map_err(|e| IoError::new(path, e, line!(), file!().to_string()))?;
+        tracing::info!("{count} records written.");
+        Ok(count)
+    }
+}
+
+impl<T> Iterator for DataStream<T> {
+    type Item = Result<T, BeaErr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.rx.recv() {
+            Ok(Ok(record)) => Some((self.reader)(&record)),
+            Ok(Err(error)) => Some(Err(error)),
+            Err(_) => {
+                // Channel closed, the parser has finished.
+                if let Some(handle) = self.handle.take()
+                    && handle.join().is_err()
+                {
+                    tracing::error!("Stream parser panicked.");
+                }
+                None
+            }
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for DataStream<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DataStream")
+            .field("finished", &self.handle.is_none())
+            .finish()
+    }
+}
+
+/// Message used to abort parsing when the receiving half of the channel has been dropped.
+const CLOSED: &str = "data stream closed by receiver";
+
+/// The sending half of the record channel.  The `closed` flag records that the receiver has
+/// been dropped, so the parser can tell its own abort apart from a malformed response without
+/// inspecting the error message, which serde decorates with the position in the input.
+struct Channel {
+    tx: std::sync::mpsc::SyncSender<Result<Record, BeaErr>>,
+    closed: std::cell::Cell<bool>,
+    /// Datasets the response may come from, any dataset if empty.
+    datasets: Vec<Dataset>,
+}
+
+impl Channel {
+    /// Sends `item`, marking the channel closed if the receiver has been dropped.
+    fn send(&self, item: Result<Record, BeaErr>) -> Result<(), ()> {
+        self.tx.send(item).map_err(|_| self.closed.set(true))
+    }
+}
+
+type Sender<'a> = &'a Channel;
+
+/// Locates the `BEAAPI` object at the top level of the response.
+struct ResponseSeed<'a> {
+    tx: Sender<'a>,
+}
+
+impl<'de> DeserializeSeed<'de> for ResponseSeed<'_> {
+    type Value = Result<(), BeaErr>;
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_map(KeyVisitor {
+            key: "BEAAPI",
+            seed: BeaapiSeed { tx: self.tx },
+        })
+    }
+}
+
+/// Locates the `Results` object inside of `BEAAPI`.
+struct BeaapiSeed<'a> {
+    tx: Sender<'a>,
+}
+
+impl<'de> DeserializeSeed<'de> for BeaapiSeed<'_> {
+    type Value = Result<(), BeaErr>;
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for BeaapiSeed<'_> {
+    type Value = Result<(), BeaErr>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "an object containing the key Results")
+    }
+
+    /// Checks the dataset named in `Request` before streaming `Results`.  The BEA writes the
+    /// request first, so a response from the wrong dataset fails before any record is sent.
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut result = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "Request" if !self.tx.datasets.is_empty() => {
+                    let value = map.next_value::<serde_json::Value>()?;
+                    if let Err(error) = check_dataset(&value, &self.tx.datasets) {
+                        // Skip the rest of the response without sending records.
+                        while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+                        return Ok(Err(error));
+                    }
+                }
+                "Results" if result.is_none() => {
+                    result = Some(map.next_value_seed(ResultsSeed { tx: self.tx })?);
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        match result {
+            Some(result) => Ok(result),
+            None => {
+                let error = KeyMissing::new("Results".to_string(), line!(), file!().to_string());
+                Ok(Err(JsonParseError::from(error).into()))
+            }
+        }
+    }
+}
+
+/// Reads the `DATASETNAME` of the request parameters in `value`, failing with [`DatasetMissing`]
+/// if it is not one of `datasets`.
+fn check_dataset(value: &serde_json::Value, datasets: &[Dataset]) -> Result<(), BeaErr> {
+    let dataset = RequestParameters::try_from(value)?.dataset()?;
+    if datasets.contains(&dataset) {
+        Ok(())
+    } else {
+        tracing::warn!("Not {datasets:?} variant.");
+        let error = DatasetMissing::new(
+            format!("{datasets:?} variant needed, found {dataset}"),
+            line!(),
+            file!().to_string(),
+        );
+        Err(error.into())
+    }
+}
+
+/// Walks the entries of a map, handing the value at `key` to `seed` and skipping the rest.
+struct KeyVisitor<S> {
+    key: &'static str,
+    seed: S,
+}
+
+impl<'de, S> Visitor<'de> for KeyVisitor<S>
+where
+    S: DeserializeSeed<'de, Value = Result<(), BeaErr>>,
+{
+    type Value = Result<(), BeaErr>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "an object containing the key {}", self.key)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut seed = Some(self.seed);
+        let mut result = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match seed.take() {
+                Some(s) if key == self.key => result = Some(map.next_value_seed(s)?),
+                other => {
+                    seed = other;
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        match result {
+            Some(result) => Ok(result),
+            None => {
+                let error = KeyMissing::new(self.key.to_string(), line!(), file!().to_string());
+                Ok(Err(JsonParseError::from(error).into()))
+            }
+        }
+    }
+}
+
+/// Sends each record of the `Data` array inside of `Results`.  If the server returned an error in
+/// place of data, reports the error instead.
+struct ResultsSeed<'a> {
+    tx: Sender<'a>,
+}
+
+impl<'de> DeserializeSeed<'de> for ResultsSeed<'_> {
+    type Value = Result<(), BeaErr>;
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ResultsSeed<'_> {
+    type Value = Result<(), BeaErr>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a Results object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut found = false;
+        let mut error = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "Data" => {
+                    map.next_value_seed(DataSeed { tx: self.tx })?;
+                    found = true;
+                }
+                "Error" => {
+                    let value = map.next_value::<serde_json::Value>()?;
+                    let mut m = serde_json::Map::new();
+                    m.insert(key, value);
+                    error = Some(m);
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        if found {
+            Ok(Ok(()))
+        } else {
+            let input = match error {
+                Some(m) => match ApiError::read_json(&m) {
+                    Ok(api) => api.to_string(),
+                    Err(_) => match MneError::read_json(&m) {
+                        Ok(mne) => mne.to_string(),
+                        Err(_) => "Error".to_string(),
+                    },
+                },
+                None => "Results".to_string(),
+            };
+            tracing::warn!("Data variant missing.");
+            let error = VariantMissing::new(
+                "Data variant missing".to_string(),
+                input,
+                line!(),
+                file!().to_string(),
+            );
+            Ok(Err(error.into()))
+        }
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        while seq.next_element::<IgnoredAny>()?.is_some() {}
+        let error = NotObject::new(line!(), file!().to_string());
+        Ok(Err(
+            JsonParseError::from(JsonParseErrorKind::from(error)).into()
+        ))
+    }
+}
+
+/// Sends each element of the `Data` array across the channel as it is parsed.
+struct DataSeed<'a> {
+    tx: Sender<'a>,
+}
+
+impl<'de> DeserializeSeed<'de> for DataSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for DataSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "an array of data records")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut count = 0;
+        while let Some(value) = seq.next_element::<serde_json::Value>()? {
+            let item = match value {
+                serde_json::Value::Object(m) => Ok(m),
+                _ => {
+                    let error = NotObject::new(line!(), file!().to_string());
+                    let error = JsonParseErrorKind::from(error);
+                    Err(JsonParseError::from(error).into())
+                }
+            };
+            if self.tx.send(item).is_err() {
+                return Err(serde::de::Error::custom(CLOSED));
+            }
+            count += 1;
+        }
+        tracing::trace!("Data found: {count} records.");
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+        let error = NotArray::new(line!(), file!().to_string());
+        let error = JsonParseErrorKind::from(error);
+        let item = Err(JsonParseError::from(error).into());
+        if self.tx.send(item).is_err() {
+            return Err(serde::de::Error::custom(CLOSED));
+        }
+        Ok(())
+    }
+}
+
+/// Reads the response in `rdr` in a single streaming pass, returning the response without the
+/// records of the `BEAAPI.Results.Data` array, which are checked for well-formed JSON and then
+/// dropped as they are read.  The `Data` key is kept, holding an empty array, and a `Results`
+/// array is likewise outlined as an empty array.  Memory use depends on the size of the request
+/// and any error in the response rather than on the number of records, so a downloaded file can
+/// be validated and classified before it is kept.
+pub fn outline<R: std::io::Read>(rdr: R) -> Result<serde_json::Value, BeaErr> {
+    let mut de = serde_json::Deserializer::from_reader(rdr);
+    let value = OutlineSeed(Level::Response)
+        .deserialize(&mut de)
+        .and_then(|value| de.end().map(|_| value))
+        .map_err(|source| SerdeJson::new(source, line!(), file!().to_string()))?;
+    Ok(value)
+}
+
+/// Position in the response of the value outlined by an [`OutlineSeed`].
+#[derive(Debug, Clone, Copy)]
+enum Level {
+    /// The top level object, holding `BEAAPI`.
+    Response,
+    /// The `BEAAPI` object, holding `Request` and `Results`.
+    Beaapi,
+    /// The `Results` object, holding `Data` or an `Error`.
+    Results,
+}
+
+/// Deserializes the value at a [`Level`] of the response, descending into `BEAAPI` and `Results`
+/// and skipping the `Data` array.  Other values are read in full.
+struct OutlineSeed(Level);
+
+impl<'de> DeserializeSeed<'de> for OutlineSeed {
+    type Value = serde_json::Value;
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for OutlineSeed {
+    type Value = serde_json::Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a BEA API response")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut outline = serde_json::Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            let value = match (self.0, key.as_str()) {
+                (Level::Response, "BEAAPI") => map.next_value_seed(OutlineSeed(Level::Beaapi))?,
+                (Level::Beaapi, "Results") => map.next_value_seed(OutlineSeed(Level::Results))?,
+                (Level::Results, "Data") => {
+                    map.next_value::<IgnoredAny>()?;
+                    serde_json::Value::Array(Vec::new())
+                }
+                _ => map.next_value::<serde_json::Value>()?,
+            };
+            outline.insert(key, value);
+        }
+        Ok(serde_json::Value::Object(outline))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        match self.0 {
+            Level::Results => {
+                while seq.next_element::<IgnoredAny>()?.is_some() {}
+                Ok(serde_json::Value::Array(Vec::new()))
+            }
+            _ => {
+                let mut values = Vec::new();
+                while let Some(value) = seq.next_element::<serde_json::Value>()? {
+                    values.push(value);
+                }
+                Ok(serde_json::Value::Array(values))
+            }
+        }
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(serde_json::Value::Null)
+    }
+}
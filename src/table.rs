@@ -0,0 +1,530 @@
+use crate::{
+    BTreeKeyMissing, BeaErr, Csv, ExportFormat, Frequency, FsStorage, IoError, Period, SerdeJson,
+    Series, SeriesCollection, VariantMissing,
+};
+
+/// Relative difference tolerated between a parent line and the sum of its children.  Published
+/// values are rounded, so components rarely sum exactly to their aggregate.
+pub const ADDITIVITY_TOLERANCE: f64 = 1e-4;
+
+/// The `TableLine` struct is a single line of a [`TableView`], holding the series published on
+/// that line and its place in the table hierarchy.
+#[derive(
+    Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, derive_getters::Getters,
+)]
+pub struct TableLine {
+    series: Series,
+    /// Level of indentation, where zero is the outermost level.
+    depth: usize,
+    /// Line number of the parent line, if any.
+    parent: Option<i64>,
+    /// Sign applied when summing the line into its parent, negative for "Less:" lines.
+    sign: i8,
+}
+
+impl TableLine {
+    fn new(series: Series) -> Self {
+        let sign = Self::sign_of(&series);
+        Self {
+            series,
+            depth: 0,
+            parent: None,
+            sign,
+        }
+    }
+
+    /// Sign of a line summed into its parent, negative for "Less:" lines.
+    fn sign_of(series: &Series) -> i8 {
+        if series.line_description().starts_with("Less:") {
+            -1
+        } else {
+            1
+        }
+    }
+
+    /// The line number within the table.
+    pub fn line_number(&self) -> i64 {
+        self.series.line_number().unwrap_or_default()
+    }
+
+    /// The line description, indented two spaces per level of depth.
+    pub fn label(&self) -> String {
+        format!(
+            "{}{}",
+            "  ".repeat(self.depth),
+            self.series.line_description()
+        )
+    }
+}
+
+/// The `Residual` struct reports a period where the children of a line do not sum to the line.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    PartialOrd,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_new::new,
+    derive_getters::Getters,
+)]
+pub struct Residual {
+    line_number: i64,
+    period: Period,
+    /// Value published on the parent line.
+    published: f64,
+    /// Signed sum of the child lines.
+    sum: f64,
+}
+
+/// The `Hierarchy` enum records where the parent of each line of a [`TableView`] came from.
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_more::Display,
+)]
+pub enum Hierarchy {
+    /// No line has a parent.
+    #[default]
+    Flat,
+    /// Inferred from the values of the table itself, so the lines add up by construction.
+    Inferred,
+    /// Taken from a [`TableLayout`] or adopted from another table, independent of the values.
+    Layout,
+}
+
+/// The `TableLayout` struct describes the published layout of a table: the indentation of each
+/// line, in line order, as shown on the BEA website and in the iTable downloads.  The API does not
+/// report indentation, so layouts are read from a CSV file or captured from a view of another
+/// table with [`TableLayout::from_view`].
+///
+/// The parent of a line is the nearest line above it with one less level of indentation.
+///
+/// ```
+/// use bears::TableLayout;
+///
+/// let layout = TableLayout::new("T10105", vec![(1, 0), (2, 1), (3, 2), (4, 2), (5, 1)]);
+/// assert_eq!(layout.parent(3), Some(2));
+/// assert_eq!(layout.parent(5), Some(1));
+/// assert_eq!(layout.parent(1), None);
+/// ```
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    derive_new::new,
+    derive_getters::Getters,
+)]
+pub struct TableLayout {
+    #[new(into)]
+    table_name: String,
+    /// Line number and level of indentation of each line, in the order published.
+    lines: Vec<(i64, usize)>,
+}
+
+/// A row of a layout CSV file.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct LayoutRecord {
+    #[serde(rename = "LineNumber")]
+    line_number: i64,
+    #[serde(rename = "Indent")]
+    indent: usize,
+}
+
+impl TableLayout {
+    /// Captures the layout of `view`, such as a current-dollar table whose hierarchy is known,
+    /// for use with other tables or vintages sharing its line numbers.
+    pub fn from_view(view: &TableView) -> Self {
+        let lines = view
+            .lines
+            .iter()
+            .map(|l| (l.line_number(), l.depth))
+            .collect();
+        Self::new(view.table_name.clone(), lines)
+    }
+
+    /// Reads the layout of `table_name` from the CSV file at `path`, with a `LineNumber` and an
+    /// `Indent` column, one row per line in published order.
+    pub fn from_csv<P: AsRef<std::path::Path>>(table_name: &str, path: P) -> Result<Self, BeaErr> {
+        let path = std::path::PathBuf::from(path.as_ref());
+        let mut rdr = csv::Reader::from_path(&path)
+            .map_err(|e| Csv::new(path.clone(), e, line!(), file!().to_string()))?;
+        let mut lines = Vec::new();
+        for record in rdr.deserialize() {
+            let record: LayoutRecord =
+                record.map_err(|e| Csv::new(path.clone(), e, line!(), file!().to_string()))?;
+            lines.push((record.line_number, record.indent));
+        }
+        Ok(Self::new(table_name, lines))
+    }
+
+    /// Writes the layout to a CSV file at `path`, readable by [`TableLayout::from_csv`].
+    pub fn to_csv<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), BeaErr> {
+        let path = std::path::PathBuf::from(path.as_ref());
+        let mut wtr = csv::Writer::from_path(&path)
+            .map_err(|e| Csv::new(path.clone(), e, line!(), file!().to_string()))?;
+        for (line_number, indent) in &self.lines {
+            let record = LayoutRecord {
+                line_number: *line_number,
+                indent: *indent,
+            };
+            wtr.serialize(record)
+                .map_err(|e| Csv::new(path.clone(), e, line!(), file!().to_string()))?;
+        }
+        wtr.flush()
+            .map_err(|e| IoError::new(path, e, line!(), file!().to_string()))?;
+        Ok(())
+    }
+
+    /// The parent of line `line_number`, the nearest line above with less indentation.
+    pub fn parent(&self, line_number: i64) -> Option<i64> {
+        let index = self.lines.iter().position(|(l, _)| *l == line_number)?;
+        let indent = self.lines[index].1;
+        self.lines[..index]
+            .iter()
+            .rev()
+            .find(|(_, i)| *i < indent)
+            .map(|(l, _)| *l)
+    }
+}
+
+/// The `TableView` struct presents the series of a NIPA table as the BEA publishes it: lines in
+/// order, indented by their place in the hierarchy, with periods as columns.
+///
+/// The API does not report indentation, so by default the hierarchy is inferred from the values
+/// for display.  A line is the parent of the run of lines that follow it when their signed sum
+/// matches it in every period.  Lines whose description begins with "Less:" enter the sum
+/// negatively, as do all but the first child of a line beginning with "Net", such as "Net exports
+/// of goods and services".  An inferred hierarchy adds up by construction, and values in a unit
+/// that does not add up, like chained dollars or indexes, cannot reveal it, so apply a
+/// [`TableLayout`] with [`TableView::with_layout`], or copy the hierarchy of another table with
+/// [`TableView::adopt_hierarchy`], before checking additivity.
+#[derive(
+    Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, derive_getters::Getters,
+)]
+pub struct TableView {
+    table_name: String,
+    frequency: Frequency,
+    lines: Vec<TableLine>,
+    periods: Vec<Period>,
+    hierarchy: Hierarchy,
+}
+
+impl TableView {
+    /// Builds the view of `table_name` at `frequency` from the series in `collection`.  Fails if
+    /// the collection holds no series for the table at that frequency.
+    pub fn from_collection(
+        collection: &SeriesCollection,
+        table_name: &str,
+        frequency: Frequency,
+    ) -> Result<Self, BeaErr> {
+        let lines = collection
+            .by_table(table_name)
+            .into_iter()
+            .filter(|s| s.frequency() == Some(frequency))
+            .map(|s| TableLine::new(s.clone()))
+            .collect::<Vec<TableLine>>();
+        if lines.is_empty() {
+            let error = BTreeKeyMissing::new(
+                format!("{table_name} at frequency {}", frequency.value()),
+                line!(),
+                file!().to_string(),
+            );
+            return Err(error.into());
+        }
+        let periods = lines
+            .iter()
+            .flat_map(|l| l.series.observations().keys().copied())
+            .collect::<std::collections::BTreeSet<Period>>()
+            .into_iter()
+            .collect();
+        let mut view = Self {
+            table_name: table_name.to_string(),
+            frequency,
+            lines,
+            periods,
+            hierarchy: Hierarchy::Flat,
+        };
+        view.infer_hierarchy();
+        Ok(view)
+    }
+
+    /// Builds a view for each table and frequency present in `collection`.
+    pub fn tables(collection: &SeriesCollection) -> Vec<Self> {
+        let mut views = Vec::new();
+        for table_name in collection.table_names() {
+            for frequency in [Frequency::Annual, Frequency::Quarterly, Frequency::Monthly] {
+                if let Ok(view) = Self::from_collection(collection, table_name, frequency) {
+                    views.push(view);
+                }
+            }
+        }
+        views
+    }
+
+    /// Indicates `true` if the signed sum of `children` matches `parent` in every period where
+    /// all are reported, and at least one such period exists.
+    fn adds_up(parent: &Series, children: &[(i8, &Series)]) -> bool {
+        let mut compared = false;
+        for (period, value) in parent.values() {
+            let sum = children
+                .iter()
+                .map(|(sign, s)| s.get(period).map(|v| *sign as f64 * v))
+                .sum::<Option<f64>>();
+            if let Some(sum) = sum {
+                if (sum - value).abs() > ADDITIVITY_TOLERANCE * value.abs().max(1.0) {
+                    return false;
+                }
+                compared = true;
+            }
+        }
+        compared
+    }
+
+    /// Finds the subtree rooted at line `index`, returning the index following its last
+    /// descendant.  Children are the shortest run of two or more following subtrees whose signed
+    /// sum matches the line.  Results are cached in `ends`.
+    fn subtree(&mut self, index: usize, ends: &mut Vec<Option<usize>>) -> usize {
+        if let Some(end) = ends[index] {
+            return end;
+        }
+        let is_net = self.lines[index]
+            .series
+            .line_description()
+            .starts_with("Net");
+        let mut children = Vec::new();
+        let mut next = index + 1;
+        let mut end = index + 1;
+        while next < self.lines.len() {
+            let sign = if is_net && !children.is_empty() {
+                -1
+            } else {
+                self.lines[next].sign
+            };
+            children.push((next, sign));
+            next = self.subtree(next, ends);
+            if children.len() < 2 {
+                continue;
+            }
+            let parts = children
+                .iter()
+                .map(|(i, sign)| (*sign, &self.lines[*i].series))
+                .collect::<Vec<(i8, &Series)>>();
+            if Self::adds_up(&self.lines[index].series, &parts) {
+                let parent = self.lines[index].line_number();
+                for (i, sign) in &children {
+                    self.lines[*i].parent = Some(parent);
+                    self.lines[*i].sign = *sign;
+                }
+                end = next;
+                break;
+            }
+        }
+        ends[index] = Some(end);
+        end
+    }
+
+    /// Assigns the parent and depth of each line from the values of the table.
+    fn infer_hierarchy(&mut self) {
+        let mut ends = vec![None; self.lines.len()];
+        let mut index = 0;
+        while index < self.lines.len() {
+            index = self.subtree(index, &mut ends);
+        }
+        if self.lines.iter().any(|l| l.parent.is_some()) {
+            self.hierarchy = Hierarchy::Inferred;
+        }
+        self.set_depths();
+    }
+
+    /// Replaces the hierarchy with the parents given by `layout`.  Lines missing from the layout
+    /// have no parent.  Signs follow the line descriptions: "Less:" lines and all but the first
+    /// child of a "Net" line enter the sum negatively.
+    pub fn with_layout(&mut self, layout: &TableLayout) -> &mut Self {
+        let descriptions = self
+            .lines
+            .iter()
+            .map(|l| (l.line_number(), l.series.line_description().clone()))
+            .collect::<std::collections::BTreeMap<i64, String>>();
+        let mut first_child = std::collections::BTreeSet::new();
+        for line in &mut self.lines {
+            line.parent = layout.parent(line.line_number());
+            line.sign = TableLine::sign_of(&line.series);
+            if let Some(parent) = line.parent {
+                let is_net = descriptions
+                    .get(&parent)
+                    .is_some_and(|d| d.starts_with("Net"));
+                if !first_child.insert(parent) && is_net {
+                    line.sign = -1;
+                }
+            }
+        }
+        self.hierarchy = Hierarchy::Layout;
+        self.set_depths();
+        self
+    }
+
+    /// Computes the depth of each line from the parent assignments.
+    fn set_depths(&mut self) {
+        let parents = self
+            .lines
+            .iter()
+            .map(|l| (l.line_number(), l.parent))
+            .collect::<std::collections::BTreeMap<i64, Option<i64>>>();
+        for line in &mut self.lines {
+            let mut depth = 0;
+            let mut parent = line.parent;
+            while let Some(p) = parent {
+                depth += 1;
+                parent = parents.get(&p).copied().flatten();
+            }
+            line.depth = depth;
+        }
+    }
+
+    /// Copies the parents and signs of `other` onto the lines of `self` with the same line
+    /// numbers.  Use to give a table in chained dollars or indexes the hierarchy of the
+    /// corresponding current-dollar table.  The hierarchy no longer derives from the values of
+    /// `self`, so [`TableView::check_additivity`] can find residuals.
+    pub fn adopt_hierarchy(&mut self, other: &Self) {
+        let template = other
+            .lines
+            .iter()
+            .map(|l| (l.line_number(), (l.parent, l.sign)))
+            .collect::<std::collections::BTreeMap<i64, (Option<i64>, i8)>>();
+        for line in &mut self.lines {
+            if let Some((parent, sign)) = template.get(&line.line_number()) {
+                line.parent = *parent;
+                line.sign = *sign;
+            }
+        }
+        if other.hierarchy != Hierarchy::Flat {
+            self.hierarchy = Hierarchy::Layout;
+        }
+        self.set_depths();
+    }
+
+    /// The lines whose parent is line `line_number`.
+    pub fn children(&self, line_number: i64) -> Vec<&TableLine> {
+        self.lines
+            .iter()
+            .filter(|l| l.parent == Some(line_number))
+            .collect()
+    }
+
+    /// Reports each line and period where the signed sum of the children differs from the parent
+    /// by more than `tolerance`, relative to the parent.  Periods where any value is missing are
+    /// skipped.
+    ///
+    /// Fails unless the hierarchy comes from a [`TableLayout`] or another table, since a
+    /// hierarchy inferred from the values of the table adds up by construction.
+    pub fn check_additivity(&self, tolerance: f64) -> Result<Vec<Residual>, BeaErr> {
+        if self.hierarchy != Hierarchy::Layout {
+            let error = VariantMissing::new(
+                "Additivity check requires a table layout".to_string(),
+                self.hierarchy.to_string(),
+                line!(),
+                file!().to_string(),
+            );
+            return Err(error.into());
+        }
+        let mut residuals = Vec::new();
+        for line in &self.lines {
+            let children = self.children(line.line_number());
+            if children.is_empty() {
+                continue;
+            }
+            for (period, value) in line.series.values() {
+                let sum = children
+                    .iter()
+                    .map(|c| c.series.get(period).map(|v| c.sign as f64 * v))
+                    .sum::<Option<f64>>();
+                if let Some(sum) = sum
+                    && (sum - value).abs() > tolerance * value.abs().max(1.0)
+                {
+                    residuals.push(Residual::new(line.line_number(), *period, value, sum));
+                }
+            }
+        }
+        Ok(residuals)
+    }
+
+    /// Pivots the table so that each line holds its values across [`TableView::periods`].
+    pub fn pivot(&self) -> Vec<(&TableLine, Vec<Option<f64>>)> {
+        self.lines
+            .iter()
+            .map(|line| {
+                let values = self.periods.iter().map(|p| line.series.get(p)).collect();
+                (line, values)
+            })
+            .collect()
+    }
+
+    /// Writes the pivoted table to a CSV file at `path`, with a column for the line number, the
+    /// indented label, and each period.
+    pub fn to_csv<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), BeaErr> {
+        let path = std::path::PathBuf::from(path.as_ref());
+        let mut wtr = csv::Writer::from_path(&path)
+            .map_err(|e| Csv::new(path.clone(), e, line!(), file!().to_string()))?;
+        let mut header = vec!["Line".to_string(), self.table_name.clone()];
+        header.extend(self.periods.iter().map(|p| p.to_string()));
+        wtr.write_record(&header)
+            .map_err(|e| Csv::new(path.clone(), e, line!(), file!().to_string()))?;
+        for (line, values) in self.pivot() {
+            let mut record = vec![line.line_number().to_string(), line.label()];
+            record.extend(
+                values
+                    .iter()
+                    .map(|v| v.map(|v| v.to_string()).unwrap_or_default()),
+            );
+            wtr.write_record(&record)
+                .map_err(|e| Csv::new(path.clone(), e, line!(), file!().to_string()))?;
+        }
+        wtr.flush()
+            .map_err(|e| IoError::new(path, e, line!(), file!().to_string()))?;
+        Ok(())
+    }
+
+    /// Writes the view, its lines, periods and hierarchy, to a JSON file at `path`.
+    pub fn to_json<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), BeaErr> {
+        let bytes = serde_json::to_vec(self)
+            .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+        FsStorage::write_atomic(path.as_ref(), &bytes)
+    }
+
+    /// Writes the view in `format` to the folder `directory`, named after the table and its
+    /// frequency, as in `T10101_Q.csv`.  Returns the path of the file written.
+    pub fn export<P: AsRef<std::path::Path>>(
+        &self,
+        format: ExportFormat,
+        directory: P,
+    ) -> Result<std::path::PathBuf, BeaErr> {
+        let directory = directory.as_ref();
+        std::fs::create_dir_all(directory)
+            .map_err(|e| IoError::new(directory.into(), e, line!(), file!().into()))?;
+        let name = format!(
+            "{}_{}.{}",
+            self.table_name,
+            self.frequency.value(),
+            format.extension()
+        );
+        let path = directory.join(name);
+        match format {
+            ExportFormat::Csv => self.to_csv(&path)?,
+            ExportFormat::Json => self.to_json(&path)?,
+        }
+        Ok(path)
+    }
+}
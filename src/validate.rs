@@ -1,25 +1,328 @@
+use crate::{
+    closest, App, BeaErr, Dataset, Frequency, NipaRanges, Overrides, ParameterName, Queue,
+    Regional, Settings, ValueInvalid, ValueSet, ValueSets,
+};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Number of suggestions reported when a value is not found among the cached values.
+const SUGGESTIONS: usize = 5;
+
+/// Keywords accepted by the Regional dataset in place of individual GeoFips codes.
+pub const GEO_FIPS_KEYWORDS: [&str; 7] = ["STATE", "COUNTY", "MSA", "MIC", "PORT", "DIV", "CSA"];
+
+/// Keywords accepted in place of individual values of parameter `name` in `dataset`, which the
+/// BEA does not list among the parameter values.
+pub fn keywords(dataset: Dataset, name: ParameterName) -> &'static [&'static str] {
+    use ParameterName as pn;
+    match (dataset, name) {
+        (Dataset::Regional, pn::GeoFips) => &GEO_FIPS_KEYWORDS,
+        (Dataset::Regional, pn::Year) => &["ALL", "LAST5", "LAST10"],
+        (Dataset::Mne, pn::Country | pn::Industry | pn::SeriesID | pn::State | pn::Year) => {
+            &["ALL"]
+        }
+        (_, pn::Year) => &["ALL", "X"],
+        _ => &[],
+    }
+}
+
+/// Indicates `true` if `value` matches `legal` ignoring case, or if both are numbers of equal
+/// value.  The cache stores codes such as GeoFips as integers, so "01000" must match "1000".
+pub(crate) fn matches(value: &str, legal: &str) -> bool {
+    if value.eq_ignore_ascii_case(legal) {
+        return true;
+    }
+    match (value.parse::<i64>(), legal.parse::<i64>()) {
+        (Ok(left), Ok(right)) => left == right,
+        _ => false,
+    }
+}
+
+/// Checks each comma-separated item of `value` against `legal`, the values of parameter `name`
+/// in `dataset`, returning an error listing the closest legal values for the first item not
+/// found.
+fn check(
+    dataset: Dataset,
+    name: ParameterName,
+    value: &str,
+    legal: &[String],
+) -> Option<ValueInvalid> {
+    let item = value
+        .split(',')
+        .map(str::trim)
+        .find(|item| !legal.iter().any(|l| matches(item, l)))?;
+    let error = ValueInvalid::new(
+        dataset.to_string(),
+        name.to_string(),
+        item.to_string(),
+        closest(item, legal, SUGGESTIONS),
+        line!(),
+        file!().to_string(),
+    );
+    Some(error)
+}
+
+/// Year ranges by table, for datasets where the available years vary by table and frequency.
+fn ranges(set: &ValueSet) -> Option<&NipaRanges> {
+    match set {
+        ValueSet::FixedAssets(set) => Some(set.year()),
+        ValueSet::Nipa(set) => Some(set.year()),
+        ValueSet::NIUnderlyingDetail(set) => Some(set.year()),
+        _ => None,
+    }
+}
+
+/// Checks the request parameters `params` against the cached values in `set`, returning an error
+/// for each invalid parameter.
+///
+/// Parameters that the BEA does not publish values for are skipped.  Where the set records years
+/// by table, years and frequencies are checked against those published for the requested table.
+pub(crate) fn check_params(set: &ValueSet, params: &BTreeMap<String, String>) -> Vec<ValueInvalid> {
+    let dataset = set.dataset();
+    let range = ranges(set).and_then(|ranges| {
+        params
+            .get(&ParameterName::TableName.to_string())
+            .and_then(|table| ranges.get(table))
+    });
+    let frequencies = params
+        .get(&ParameterName::Frequency.to_string())
+        .map(|f| {
+            f.split(',')
+                .filter_map(|f| match f.trim() {
+                    "A" => Some(Frequency::Annual),
+                    "Q" => Some(Frequency::Quarterly),
+                    "M" => Some(Frequency::Monthly),
+                    _ => None,
+                })
+                .collect::<Vec<Frequency>>()
+        })
+        .unwrap_or(vec![Frequency::Annual]);
+    let mut errors = Vec::new();
+    for (key, value) in params {
+        let Ok(name) = ParameterName::from_str(key) else {
+            continue;
+        };
+        let mut legal = match (name, range) {
+            (ParameterName::Frequency, Some(range)) => {
+                [Frequency::Annual, Frequency::Quarterly, Frequency::Monthly]
+                    .into_iter()
+                    .filter(|f| range.years(*f).is_some())
+                    .map(|f| f.value())
+                    .collect()
+            }
+            (ParameterName::Year, Some(range)) => {
+                let mut years = Vec::new();
+                for frequency in &frequencies {
+                    years.extend(range.years(*frequency).unwrap_or_default());
+                }
+                years
+            }
+            _ => match set.values(name) {
+                Some(values) => values,
+                None => continue,
+            },
+        };
+        legal.sort();
+        legal.dedup();
+        legal.extend(keywords(dataset, name).iter().map(|k| k.to_string()));
+        if let Some(error) = check(dataset, name, value, &legal) {
+            errors.push(error);
+        }
+    }
+    errors
+}
+
+/// The `Invalid` struct reports an [`App`] in a [`Queue`] with parameter values absent from the
+/// cached parameter values.
+#[derive(Debug, Clone, PartialEq, Eq, derive_new::new, derive_getters::Getters)]
+pub struct Invalid {
+    /// Position of the request in the queue.
+    index: usize,
+    app: App,
+    /// One error for each offending parameter.
+    errors: Vec<ValueInvalid>,
+}
+
+/// The `Validate` struct checks requests against the parameter values cached under
+/// `BEA_DATA/parameter_values`, without calling the BEA API.  Use it to screen a [`Queue`]
+/// before download, so that requests bound to fail do not spend the rate limit.
+///
+/// Datasets whose values were not loaded, and parameters that the BEA does not publish values
+/// for, pass unchecked.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, derive_new::new)]
+pub struct Validate {
+    values: ValueSets,
+}
+
+impl Validate {
+    /// Loads the cached values for each of `datasets` from the directory `path`.
+    pub fn from_path(path: &std::path::PathBuf, datasets: &[Dataset]) -> Result<Self, BeaErr> {
+        let values = ValueSets::from_path(path, datasets)?;
+        Ok(Self::new(values))
+    }
+
+    /// Loads the cached values for each of `datasets` from the data root of the default [`Settings`](crate::Settings).
+    pub fn from_env(datasets: &[Dataset]) -> Result<Self, BeaErr> {
+        let path = Settings::resolve(&Overrides::default())?.data().clone();
+        Self::from_path(&path, datasets)
+    }
+
+    /// The legal values of parameter `name` in `dataset`, including any keywords accepted in
+    /// their place.  Returns `None` if no values are cached for the parameter.
+    pub fn values(&self, dataset: Dataset, name: ParameterName) -> Option<Vec<String>> {
+        let mut values = self.values.get(dataset)?.values(name)?;
+        values.extend(keywords(dataset, name).iter().map(|k| k.to_string()));
+        Some(values)
+    }
+
+    /// Indicates whether `value` is legal for parameter `name` in `dataset`.  Each item of a
+    /// comma-separated list must be legal.  Returns `None` if no values are cached for the
+    /// parameter.
+    pub fn is_valid(&self, dataset: Dataset, name: ParameterName, value: &str) -> Option<bool> {
+        let legal = self.values(dataset, name)?;
+        Some(check(dataset, name, value, &legal).is_none())
+    }
+
+    /// Checks that `value` is legal for parameter `name` in `dataset`, returning an error that
+    /// lists the closest legal values if not.  Parameters without cached values pass.
+    pub fn check(&self, dataset: Dataset, name: ParameterName, value: &str) -> Result<(), BeaErr> {
+        if let Some(legal) = self.values(dataset, name)
+            && let Some(error) = check(dataset, name, value, &legal)
+        {
+            return Err(error.into());
+        }
+        Ok(())
+    }
+
+    /// Checks the query parameters of `app`, returning an error for each invalid parameter.
+    /// Fails if the query does not name a dataset.
+    pub fn app(&self, app: &App) -> Result<Vec<ValueInvalid>, BeaErr> {
+        let dataset = app.dataset()?;
+        match self.values.get(dataset) {
+            Some(set) => Ok(check_params(set, app.query())),
+            None => {
+                tracing::trace!("No cached values for {dataset}.");
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    /// Checks each request in `queue`, reporting every request with an invalid parameter.
+    pub fn queue(&self, queue: &Queue) -> Result<Vec<Invalid>, BeaErr> {
+        let mut invalid = Vec::new();
+        for (index, app) in queue.iter().enumerate() {
+            let errors = self.app(app)?;
+            if !errors.is_empty() {
+                tracing::warn!("Request {index} has {} invalid parameters.", errors.len());
+                invalid.push(Invalid::new(index, app.clone(), errors));
+            }
+        }
+        Ok(invalid)
+    }
+}
+
+/// Years available in the Regional dataset, keyed by the first day of the year.
 #[derive(
-    Clone,
-    Debug,
-    Default,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    Hash,
-    serde::Deserialize,
-    serde::Serialize,
+    Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize, derive_more::Deref,
 )]
-pub struct Validate;
-
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
 pub struct Year(std::collections::HashMap<jiff::civil::Date, String>);
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+impl Year {
+    /// Indicates `true` if `year`, such as "2023", is available.
+    pub fn contains(&self, year: &str) -> bool {
+        year.parse::<i16>()
+            .ok()
+            .and_then(|year| jiff::civil::Date::new(year, 1, 1).ok())
+            .is_some_and(|date| self.contains_key(&date))
+    }
+}
+
+impl From<&Regional> for Year {
+    fn from(value: &Regional) -> Self {
+        let years = value
+            .year()
+            .iter()
+            .map(|y| (*y.date(), y.description().clone()))
+            .collect();
+        Self(years)
+    }
+}
+
+/// Area codes available in the Regional dataset, with the name of each area.
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize, derive_more::Deref,
+)]
 pub struct GeoFips(std::collections::HashMap<i32, String>);
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+impl GeoFips {
+    /// Indicates `true` if `geo_fips`, such as "01000", is an available code or keyword.
+    pub fn contains(&self, geo_fips: &str) -> bool {
+        GEO_FIPS_KEYWORDS
+            .iter()
+            .any(|k| k.eq_ignore_ascii_case(geo_fips))
+            || geo_fips
+                .parse::<i32>()
+                .is_ok_and(|code| self.contains_key(&code))
+    }
+}
+
+impl From<&Regional> for GeoFips {
+    fn from(value: &Regional) -> Self {
+        let codes = value
+            .geo_fips()
+            .iter()
+            .map(|i| (*i.value(), i.description().clone()))
+            .collect();
+        Self(codes)
+    }
+}
+
+/// Line codes available in the Regional dataset, with the description of each line.
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize, derive_more::Deref,
+)]
 pub struct LineCode(std::collections::HashMap<i32, String>);
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+impl LineCode {
+    /// Indicates `true` if `line_code` is available.
+    pub fn contains(&self, line_code: &str) -> bool {
+        line_code
+            .parse::<i32>()
+            .is_ok_and(|code| self.contains_key(&code))
+    }
+}
+
+impl From<&Regional> for LineCode {
+    fn from(value: &Regional) -> Self {
+        let codes = value
+            .line_code()
+            .iter()
+            .map(|i| (*i.value(), i.description().clone()))
+            .collect();
+        Self(codes)
+    }
+}
+
+/// Tables available in the Regional dataset, with the description of each table.
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize, derive_more::Deref,
+)]
 pub struct TableName(std::collections::HashMap<String, String>);
+
+impl TableName {
+    /// Indicates `true` if the table `name` is available.
+    pub fn contains(&self, name: &str) -> bool {
+        self.contains_key(name)
+    }
+}
+
+impl From<&Regional> for TableName {
+    fn from(value: &Regional) -> Self {
+        let tables = value
+            .table_name()
+            .iter()
+            .map(|t| (t.name().clone(), t.description().clone()))
+            .collect();
+        Self(tables)
+    }
+}
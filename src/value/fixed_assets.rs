@@ -1,5 +1,5 @@
 use crate::{
-    bea_data, BeaErr, BeaResponse, Dataset, IoError, NipaRange, NipaRanges, ParameterName,
+    App, BeaClient, BeaErr, BeaResponse, Dataset, IoError, NipaRange, NipaRanges, ParameterName,
     ParameterValueTable, ParameterValueTableVariant, Queue, Request, SelectionKind, SerdeJson, Set,
     TableName,
 };
@@ -17,14 +17,25 @@ impl FixedAssets {
         FixedAssetsIterator::new(self)
     }
 
+    /// Builds a queue requesting each combination of the parameter values cached in the
+    /// `BEA_DATA` directory.
     pub fn queue() -> Result<Queue, BeaErr> {
-        let req = Request::Data;
-        let mut app = req.init()?;
+        Self::queue_with(&BeaClient::from_env()?)
+    }
+
+    /// Builds the queue using the key, url and storage root of `client`, reading the parameter
+    /// values cached under the storage root.
+    pub fn queue_with(client: &BeaClient) -> Result<Queue, BeaErr> {
+        let app = client.request(Request::Data);
+        Self::queue_from(app, client.root())
+    }
+
+    /// Builds the queue from the template request `app` and the parameter values cached under
+    /// `path`.
+    fn queue_from(mut app: App, path: &std::path::PathBuf) -> Result<Queue, BeaErr> {
         let dataset = Dataset::FixedAssets;
         app.with_dataset(dataset);
-        dotenvy::dotenv().ok();
-        let path = bea_data()?;
-        let data = FixedAssets::try_from(&path)?;
+        let data = FixedAssets::try_from(path)?;
         let mut queue = Vec::new();
         for params in data.iter() {
             tracing::trace!("{params:#?}");
@@ -34,6 +34,15 @@ impl Frequency {
         s.to_string()
     }
 
+    /// The number of periods of this frequency in one year.
+    pub fn periods_per_year(&self) -> u8 {
+        match self {
+            Self::Annual => 1,
+            Self::Quarterly => 4,
+            Self::Monthly => 12,
+        }
+    }
+
     pub fn params(&self) -> (String, String) {
         let key = ParameterName::Frequency.to_string();
         let value = self.value();
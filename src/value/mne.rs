@@ -1,10 +1,10 @@
-use crate::{bea_data, SerdeJson};
 pub use crate::{
     AffiliateLevel, BeaErr, BeaResponse, BoolOptions, Dataset, DirectionOfInvestment, Footnotes,
     Integer, IntegerKind, IntegerOptions, IoError, MneDoi, OwnershipLevel, ParameterName,
     ParameterValueTable, ParameterValueTableVariant, Queue, Request, SelectionKind, Set, State,
     YearKind, YearOptions,
 };
+use crate::{App, BeaClient, Prune, SerdeJson};
 use strum::IntoEnumIterator;
 
 #[derive(
@@ -51,20 +51,35 @@ impl Mne {
         )
     }
 
+    /// Builds a queue requesting each combination of the parameter values cached in the
+    /// `BEA_DATA` directory.
     pub fn queue() -> Result<Queue, BeaErr> {
-        let req = Request::Data;
-        let mut app = req.init()?;
+        Self::queue_with(&BeaClient::from_env()?)
+    }
+
+    /// Builds the queue using the key, url and storage root of `client`, reading the parameter
+    /// values cached under the storage root.
+    pub fn queue_with(client: &BeaClient) -> Result<Queue, BeaErr> {
+        let app = client.request(Request::Data);
+        Self::queue_from(app, client.root())
+    }
+
+    /// Builds the queue from the template request `app` and the parameter values cached under
+    /// `path`.
+    fn queue_from(mut app: App, path: &std::path::PathBuf) -> Result<Queue, BeaErr> {
         let dataset = Dataset::Mne;
         app.with_dataset(dataset);
-        dotenvy::dotenv().ok();
-        let path = bea_data()?;
-        let data = Mne::try_from(&path)?;
+        let data = Mne::try_from(path)?;
+        // skip combinations that cached filtered values show cannot exist
+        let prune = Prune::new(path, dataset);
+        let mut params_iter = prune.prune(data.iter());
         let mut queue = Vec::new();
-        for params in data.iter() {
+        for params in params_iter.by_ref() {
             tracing::trace!("{params:#?}");
             app.with_params(params.clone());
             queue.push(app.clone());
         }
+        tracing::info!("Pruned {} requests from {dataset}.", params_iter.skipped());
         Ok(Queue::new(queue))
     }
 }
@@ -7,6 +7,7 @@ mod mne;
 mod nipa;
 mod numeric;
 mod owner;
+mod period;
 mod row_code;
 mod selection;
 mod sets;
@@ -25,6 +26,7 @@ pub use numeric::{
     AnnotatedInteger, Annotation, BoolOptions, Integer, IntegerKind, IntegerOptions,
 };
 pub use owner::{AffiliateKind, AffiliateLevel, OwnershipKind, OwnershipLevel};
+pub use period::{Period, PeriodRange};
 pub use row_code::RowCode;
 pub use selection::SelectionKind;
 pub use sets::{
@@ -423,6 +423,15 @@ impl AnnotatedInteger {
             Self::Both(anno, num) => format!("{num}{anno}"),
         }
     }
+
+    /// The numeric portion of the value, if any.  Returns `None` for suppressed values reported
+    /// only as an annotation, such as "(D)".
+    pub fn number(&self) -> Option<i64> {
+        match self {
+            Self::Annotation(_) => None,
+            Self::Integer(num) | Self::Both(_, num) => Some(*num),
+        }
+    }
 }
 
 impl Default for AnnotatedInteger {
@@ -0,0 +1,336 @@
+use crate::{Frequency, FrequencyMismatch, PeriodInvalid};
+
+/// The `Period` enum represents the `TimePeriod` of an observation, preserving its frequency.
+///
+/// The BEA reports annual observations as "2023", quarterly observations as "2023Q2" and monthly
+/// observations as "2023M04".  Unlike a civil date, a `Period` keeps these apart, so that the
+/// second quarter of 2023 never compares equal to April 2023.  Periods order chronologically by
+/// start date, with longer periods sorting ahead of shorter periods that begin on the same day.
+///
+/// ```
+/// use bears::{Frequency, Period};
+///
+/// let q2: Period = "2023Q2".parse().unwrap();
+/// assert_eq!(q2, Period::Quarter(2023, 2));
+/// assert_eq!(q2.to_string(), "2023Q2");
+/// assert_eq!(q2.frequency(), Frequency::Quarterly);
+/// assert_eq!(q2.start().unwrap(), jiff::civil::date(2023, 4, 1));
+/// assert_eq!(q2.end().unwrap(), jiff::civil::date(2023, 6, 30));
+/// assert!(Period::Quarter(2023, 5).start().is_err());
+/// assert_eq!(q2.next(), Some(Period::Quarter(2023, 3)));
+/// assert_ne!(q2, "2023M04".parse::<Period>().unwrap());
+/// assert!("20000".parse::<Period>().is_err());
+/// ```
+///
+/// Years must lie within [`Period::YEARS`], the range of a civil date.  The parser and the
+/// checked constructors reject other years, and stepping past either end yields `None`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Period {
+    /// A calendar year.
+    Year(i16),
+    /// A year and quarter, numbered 1 through 4.
+    Quarter(i16, u8),
+    /// A year and month, numbered 1 through 12.
+    Month(i16, u8),
+}
+
+impl Period {
+    /// Years representable as a [`jiff::civil::Date`].
+    pub const YEARS: std::ops::RangeInclusive<i16> = -9999..=9999;
+
+    /// Creates an annual period, checking that `year` lies within [`Period::YEARS`].
+    pub fn annual(year: i16) -> Result<Self, PeriodInvalid> {
+        if Self::YEARS.contains(&year) {
+            Ok(Self::Year(year))
+        } else {
+            let error = PeriodInvalid::new(format!("{year}"), line!(), file!().to_string());
+            Err(error)
+        }
+    }
+
+    /// Creates a quarterly period, checking that `quarter` lies between 1 and 4 and `year` lies
+    /// within [`Period::YEARS`].
+    pub fn quarter(year: i16, quarter: u8) -> Result<Self, PeriodInvalid> {
+        if Self::YEARS.contains(&year) && (1..=4).contains(&quarter) {
+            Ok(Self::Quarter(year, quarter))
+        } else {
+            let error =
+                PeriodInvalid::new(format!("{year}Q{quarter}"), line!(), file!().to_string());
+            Err(error)
+        }
+    }
+
+    /// Creates a monthly period, checking that `month` lies between 1 and 12 and `year` lies
+    /// within [`Period::YEARS`].
+    pub fn month(year: i16, month: u8) -> Result<Self, PeriodInvalid> {
+        if Self::YEARS.contains(&year) && (1..=12).contains(&month) {
+            Ok(Self::Month(year, month))
+        } else {
+            let error =
+                PeriodInvalid::new(format!("{year}M{month:02}"), line!(), file!().to_string());
+            Err(error)
+        }
+    }
+
+    /// The calendar year containing the period.
+    pub fn year(&self) -> i16 {
+        match self {
+            Self::Year(year) | Self::Quarter(year, _) | Self::Month(year, _) => *year,
+        }
+    }
+
+    /// The frequency of observation implied by the period.
+    pub fn frequency(&self) -> Frequency {
+        match self {
+            Self::Year(_) => Frequency::Annual,
+            Self::Quarter(..) => Frequency::Quarterly,
+            Self::Month(..) => Frequency::Monthly,
+        }
+    }
+
+    /// The number of periods of this frequency in one year.
+    pub fn periods_per_year(&self) -> u8 {
+        self.frequency().periods_per_year()
+    }
+
+    /// The first and last month of the period, counted from January of year zero.
+    fn months(&self) -> (i64, i64) {
+        let first = self.year() as i64 * 12;
+        match self {
+            Self::Year(_) => (first, first + 11),
+            Self::Quarter(_, quarter) => {
+                let start = first + (*quarter as i64 - 1) * 3;
+                (start, start + 2)
+            }
+            Self::Month(_, month) => (first + *month as i64 - 1, first + *month as i64 - 1),
+        }
+    }
+
+    /// The year and the first and last month of the period within that year.  Fails if the
+    /// quarter or month is out of range, as when built without the checked constructors.
+    fn calendar(&self) -> Result<(i16, i8, i8), PeriodInvalid> {
+        match self {
+            Self::Year(year) => Ok((*year, 1, 12)),
+            Self::Quarter(year, quarter) if (1..=4).contains(quarter) => {
+                let last = *quarter as i8 * 3;
+                Ok((*year, last - 2, last))
+            }
+            Self::Month(year, month) if (1..=12).contains(month) => {
+                Ok((*year, *month as i8, *month as i8))
+            }
+            _ => Err(self.invalid(line!())),
+        }
+    }
+
+    /// The error reporting `self` as an invalid period, raised at `line`.
+    fn invalid(&self, line: u32) -> PeriodInvalid {
+        PeriodInvalid::new(self.to_string(), line, file!().to_string())
+    }
+
+    /// The first day of the period.  Fails if the year lies outside [`Period::YEARS`], or the
+    /// quarter or month is out of range, which the parser and the checked constructors reject.
+    pub fn start(&self) -> Result<jiff::civil::Date, PeriodInvalid> {
+        let (year, first, _) = self.calendar()?;
+        jiff::civil::Date::new(year, first, 1).map_err(|_| self.invalid(line!()))
+    }
+
+    /// The last day of the period.  Fails for the same periods as [`Period::start`].
+    pub fn end(&self) -> Result<jiff::civil::Date, PeriodInvalid> {
+        let (year, _, last) = self.calendar()?;
+        jiff::civil::Date::new(year, last, 1)
+            .map(|date| date.last_of_month())
+            .map_err(|_| self.invalid(line!()))
+    }
+
+    /// Indicates `true` if every day of `other` falls within `self`.
+    pub fn contains(&self, other: &Self) -> bool {
+        let (start, end) = self.months();
+        let (other_start, other_end) = other.months();
+        start <= other_start && other_end <= end
+    }
+
+    /// The period of frequency `frequency` containing the start of `self`.  Converting to a higher
+    /// frequency returns the first sub-period, so `2023` becomes `2023Q1` or `2023M01`.
+    pub fn to_frequency(&self, frequency: Frequency) -> Self {
+        let year = self.year();
+        let month = (self.months().0.rem_euclid(12) + 1) as u8;
+        match frequency {
+            Frequency::Annual => Self::Year(year),
+            Frequency::Quarterly => Self::Quarter(year, (month - 1) / 3 + 1),
+            Frequency::Monthly => Self::Month(year, month),
+        }
+    }
+
+    /// Counts periods of this frequency since year zero, so that consecutive periods differ by one.
+    fn ordinal(&self) -> i64 {
+        match self {
+            Self::Year(year) => *year as i64,
+            Self::Quarter(year, quarter) => *year as i64 * 4 + *quarter as i64 - 1,
+            Self::Month(year, month) => *year as i64 * 12 + *month as i64 - 1,
+        }
+    }
+
+    /// Inverse of [`Period::ordinal`] at the frequency of `self`.  Returns `None` if the year
+    /// falls outside [`Period::YEARS`].
+    fn with_ordinal(&self, ordinal: i64) -> Option<Self> {
+        let per_year = self.periods_per_year() as i64;
+        let year = i16::try_from(ordinal.div_euclid(per_year))
+            .ok()
+            .filter(|year| Self::YEARS.contains(year))?;
+        let sub = u8::try_from(ordinal.rem_euclid(per_year) + 1).ok()?;
+        match self {
+            Self::Year(_) => Some(Self::Year(year)),
+            Self::Quarter(..) => Some(Self::Quarter(year, sub)),
+            Self::Month(..) => Some(Self::Month(year, sub)),
+        }
+    }
+
+    /// The period `n` steps away at the same frequency.  Negative values of `n` step backward.
+    /// Returns `None` if the result falls outside [`Period::YEARS`].
+    pub fn offset(&self, n: i64) -> Option<Self> {
+        self.with_ordinal(self.ordinal().checked_add(n)?)
+    }
+
+    /// The following period at the same frequency, if any.
+    pub fn next(&self) -> Option<Self> {
+        self.offset(1)
+    }
+
+    /// The preceding period at the same frequency, if any.
+    pub fn prev(&self) -> Option<Self> {
+        self.offset(-1)
+    }
+
+    /// The number of steps from `self` to `other`, if both share a frequency.
+    pub fn distance(&self, other: &Self) -> Result<i64, FrequencyMismatch> {
+        self.check_frequency(other)?;
+        Ok(other.ordinal() - self.ordinal())
+    }
+
+    fn check_frequency(&self, other: &Self) -> Result<(), FrequencyMismatch> {
+        if self.frequency() == other.frequency() {
+            Ok(())
+        } else {
+            let error = FrequencyMismatch::new(
+                self.frequency().value(),
+                other.frequency().value(),
+                line!(),
+                file!().to_string(),
+            );
+            Err(error)
+        }
+    }
+
+    /// Iterates over each period from `start` through `end` inclusive.  The endpoints must share
+    /// a frequency.  If `end` precedes `start`, the range is empty.
+    ///
+    /// ```
+    /// use bears::Period;
+    ///
+    /// let start = Period::Quarter(2022, 3);
+    /// let end = Period::Quarter(2023, 2);
+    /// let periods = Period::range(start, end).unwrap().collect::<Vec<Period>>();
+    /// assert_eq!(periods.len(), 4);
+    /// assert!(Period::range(start, Period::Year(2023)).is_err());
+    /// ```
+    pub fn range(start: Self, end: Self) -> Result<PeriodRange, FrequencyMismatch> {
+        start.check_frequency(&end)?;
+        Ok(PeriodRange {
+            next: Some(start),
+            end: end.ordinal(),
+        })
+    }
+
+    /// Ranking used to break ties between periods that start on the same day.
+    fn rank(&self) -> u8 {
+        match self {
+            Self::Year(_) => 0,
+            Self::Quarter(..) => 1,
+            Self::Month(..) => 2,
+        }
+    }
+}
+
+impl Default for Period {
+    fn default() -> Self {
+        Self::Year(0)
+    }
+}
+
+impl PartialOrd for Period {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Period {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.months().0, self.rank()).cmp(&(other.months().0, other.rank()))
+    }
+}
+
+impl std::fmt::Display for Period {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Year(year) => write!(f, "{year}"),
+            Self::Quarter(year, quarter) => write!(f, "{year}Q{quarter}"),
+            Self::Month(year, month) => write!(f, "{year}M{month:02}"),
+        }
+    }
+}
+
+impl std::str::FromStr for Period {
+    type Err = PeriodInvalid;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || PeriodInvalid::new(s.to_string(), line!(), file!().to_string());
+        let s = s.trim();
+        let (year, suffix) = match s.find(['Q', 'M']) {
+            Some(i) => s.split_at(i),
+            None => (s, ""),
+        };
+        let year = year.parse::<i16>().map_err(|_| invalid())?;
+        let mut chars = suffix.chars();
+        match chars.next() {
+            None => Self::annual(year).map_err(|_| invalid()),
+            Some(kind) => {
+                let num = chars.as_str().parse::<u8>().map_err(|_| invalid())?;
+                match kind {
+                    'Q' => Self::quarter(year, num),
+                    _ => Self::month(year, num),
+                }
+                .map_err(|_| invalid())
+            }
+        }
+    }
+}
+
+impl serde::Serialize for Period {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Period {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Iterator over consecutive periods of the same frequency, created by [`Period::range`].
+#[derive(Debug, Clone)]
+pub struct PeriodRange {
+    next: Option<Period>,
+    end: i64,
+}
+
+impl Iterator for PeriodRange {
+    type Item = Period;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let period = self.next.filter(|period| period.ordinal() <= self.end)?;
+        self.next = period.next();
+        Some(period)
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
-    BeaErr, BeaResponse, Dataset, FixedAssets, Frequencies, Frequency, Integer, IoError, Metadata,
-    Mne, NiUnderlyingDetail, Nipa, ParameterFields, ParameterName, ParameterValueTable,
-    ParameterValueTableVariant, SerdeJson, Set, TableName, Year,
+    BeaErr, BeaResponse, Dataset, FixedAssets, Frequencies, Frequency, FsStorage, Integer,
+    Metadata, Mne, NiUnderlyingDetail, Nipa, ParameterFields, ParameterName, ParameterValueTable,
+    ParameterValueTableVariant, Set, StorageKey, TableName, Year,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, derive_more::From)]
@@ -205,6 +205,141 @@ impl ValueSet {
             }
         }
     }
+
+    /// The dataset the values belong to.
+    pub fn dataset(&self) -> Dataset {
+        match self {
+            Self::APIDatasetMetadata(_) => Dataset::APIDatasetMetadata,
+            Self::FixedAssets(_) => Dataset::FixedAssets,
+            Self::Iip(_) => Dataset::Iip,
+            Self::InputOutput(_) => Dataset::InputOutput,
+            Self::IntlServTrade(_) => Dataset::IntlServTrade,
+            Self::IntlServSTA(_) => Dataset::IntlServSTA,
+            Self::Ita(_) => Dataset::Ita,
+            Self::GDPbyIndustry(_) => Dataset::GDPbyIndustry,
+            Self::Mne(_) => Dataset::Mne,
+            Self::Nipa(_) => Dataset::Nipa,
+            Self::NIUnderlyingDetail(_) => Dataset::NIUnderlyingDetail,
+            Self::Regional(_) => Dataset::Regional,
+            Self::UnderlyingGDPbyIndustry(_) => Dataset::UnderlyingGDPbyIndustry,
+        }
+    }
+
+    /// The cached values of parameter `name`, as they appear in a request.  Returns `None` if the
+    /// dataset does not take the parameter, or takes it without publishing a list of values.
+    /// Where values vary by table, as years do for NIPA tables, the union over all tables is
+    /// returned.
+    pub fn values(&self, name: ParameterName) -> Option<Vec<String>> {
+        use ParameterName as pn;
+        let fields = |v: &[ParameterFields]| v.iter().map(|f| f.key().to_string()).collect();
+        let integers = |v: &[Integer]| v.iter().map(|i| i.to_string()).collect();
+        let tables = |v: &[TableName]| v.iter().map(|t| t.to_string()).collect();
+        let years = |v: &[Year]| v.iter().map(|y| y.key()).collect();
+        let frequencies = |v: &Frequencies| v.iter().map(|f| f.value()).collect();
+        let by_table = |v: &std::collections::HashMap<Integer, Vec<ParameterFields>>| {
+            let keys = v
+                .values()
+                .flat_map(|f| f.iter().map(|f| f.key().to_string()))
+                .collect::<std::collections::BTreeSet<String>>();
+            keys.into_iter().collect()
+        };
+        let years_by_table = |v: &std::collections::HashMap<Integer, Vec<Year>>| {
+            let keys = v
+                .values()
+                .flat_map(|y| y.iter().map(|y| y.key()))
+                .collect::<std::collections::BTreeSet<String>>();
+            keys.into_iter().collect()
+        };
+        let values = match (self, name) {
+            (Self::FixedAssets(set), pn::TableName) => tables(set.table_name()),
+            (Self::FixedAssets(set), pn::Year) => set.year().years(),
+            (Self::Iip(set), pn::Component) => fields(set.component()),
+            (Self::Iip(set), pn::Frequency) => fields(set.frequency()),
+            (Self::Iip(set), pn::TypeOfInvestment) => fields(set.type_of_investment()),
+            (Self::Iip(set), pn::Year) => years(set.year()),
+            (Self::InputOutput(set), pn::TableID) => integers(set.table_id()),
+            (Self::InputOutput(set), pn::Year) => years(set.year()),
+            (Self::IntlServTrade(set), pn::Affiliation) => fields(set.affiliation()),
+            (Self::IntlServTrade(set), pn::AreaOrCountry) => fields(set.area_or_country()),
+            (Self::IntlServTrade(set), pn::TradeDirection) => fields(set.trade_direction()),
+            (Self::IntlServTrade(set), pn::TypeOfService) => fields(set.type_of_service()),
+            (Self::IntlServTrade(set), pn::Year) => years(set.year()),
+            (Self::IntlServSTA(set), pn::AreaOrCountry) => fields(set.area_or_country()),
+            (Self::IntlServSTA(set), pn::Channel) => fields(set.channel()),
+            (Self::IntlServSTA(set), pn::Destination) => fields(set.destination()),
+            (Self::IntlServSTA(set), pn::Industry) => fields(set.industry()),
+            (Self::IntlServSTA(set), pn::Year) => years(set.year()),
+            (Self::Ita(set), pn::AreaOrCountry) => fields(set.area_or_country()),
+            (Self::Ita(set), pn::Frequency) => fields(set.frequency()),
+            (Self::Ita(set), pn::Indicator) => fields(set.indicator()),
+            (Self::Ita(set), pn::Year) => years(set.year()),
+            (Self::GDPbyIndustry(set), pn::Frequency) => frequencies(set.frequency()),
+            (Self::GDPbyIndustry(set), pn::Industry) => by_table(set.industry()),
+            (Self::GDPbyIndustry(set), pn::TableID) => integers(set.table_id()),
+            (Self::GDPbyIndustry(set), pn::Year) => years_by_table(set.year()),
+            (Self::Mne(set), pn::Classification) => set
+                .classification()
+                .iter()
+                .map(|c| c.key().to_string())
+                .collect(),
+            (Self::Mne(set), pn::Country) => {
+                set.country().iter().map(|c| c.key().clone()).collect()
+            }
+            (Self::Mne(set), pn::DirectionOfInvestment) => set
+                .direction_of_investment()
+                .iter()
+                .map(|d| d.key().clone())
+                .collect(),
+            (Self::Mne(set), pn::GetFootnotes) => {
+                set.get_footnotes().iter().map(|b| b.to_key()).collect()
+            }
+            (Self::Mne(set), pn::Industry) => {
+                set.industry().iter().map(|i| i.key().clone()).collect()
+            }
+            (Self::Mne(set), pn::Investment) => {
+                set.investment().iter().map(|i| i.key().clone()).collect()
+            }
+            (Self::Mne(set), pn::NonbankAffiliatesOnly) => set
+                .nonbank_affiliates_only()
+                .iter()
+                .map(|a| a.key().to_string())
+                .collect(),
+            (Self::Mne(set), pn::OwnershipLevel) => set
+                .ownership_level()
+                .iter()
+                .map(|o| o.key().to_string())
+                .collect(),
+            (Self::Mne(set), pn::ParentInvestment) => set
+                .parent_investment()
+                .iter()
+                .map(|i| i.key().clone())
+                .collect(),
+            (Self::Mne(set), pn::SeriesID) => integers(set.series_id()),
+            (Self::Mne(set), pn::State) => set.state().iter().map(|s| s.key().clone()).collect(),
+            (Self::Mne(set), pn::Year) => set.year().iter().map(|y| y.key().clone()).collect(),
+            (Self::Nipa(set), pn::Frequency) => frequencies(set.frequency()),
+            (Self::Nipa(set), pn::ShowMillions) => set
+                .show_millions()
+                .iter()
+                .map(|m| if *m.show() { "Y" } else { "N" }.to_string())
+                .collect(),
+            (Self::Nipa(set), pn::TableName) => tables(set.table_name()),
+            (Self::Nipa(set), pn::Year) => set.year().years(),
+            (Self::NIUnderlyingDetail(set), pn::Frequency) => frequencies(set.frequency()),
+            (Self::NIUnderlyingDetail(set), pn::TableName) => tables(set.table_name()),
+            (Self::NIUnderlyingDetail(set), pn::Year) => set.year().years(),
+            (Self::Regional(set), pn::GeoFips) => integers(set.geo_fips()),
+            (Self::Regional(set), pn::LineCode) => integers(set.line_code()),
+            (Self::Regional(set), pn::TableName) => tables(set.table_name()),
+            (Self::Regional(set), pn::Year) => years(set.year()),
+            (Self::UnderlyingGDPbyIndustry(set), pn::Frequency) => frequencies(set.frequency()),
+            (Self::UnderlyingGDPbyIndustry(set), pn::Industry) => by_table(set.industry()),
+            (Self::UnderlyingGDPbyIndustry(set), pn::TableID) => integers(set.table_id()),
+            (Self::UnderlyingGDPbyIndustry(set), pn::Year) => years_by_table(set.year()),
+            _ => return None,
+        };
+        Some(values)
+    }
 }
 
 #[derive(
@@ -218,7 +353,9 @@ impl ValueSet {
     derive_more::DerefMut,
     derive_more::AsRef,
     derive_more::AsMut,
+    derive_more::From,
 )]
+#[from(Vec<ValueSet>)]
 pub struct ValueSets(Vec<ValueSet>);
 
 impl ValueSets {
@@ -230,6 +367,11 @@ impl ValueSets {
         }
         Ok(Self(sets))
     }
+
+    /// The values cached for `dataset`, if they were read.
+    pub fn get(&self, dataset: Dataset) -> Option<&ValueSet> {
+        self.iter().find(|set| set.dataset() == dataset)
+    }
 }
 
 #[derive(
@@ -256,18 +398,14 @@ impl TryFrom<&std::path::PathBuf> for ApiMetadata {
         let names = dataset.names();
         // empty vectors to store values
         let mut metadata = Vec::new();
+        let storage = FsStorage::new(value.clone());
         // For each parameter in dataset
         for name in names {
             // open the file at the expected storage location, error if missing
-            let path = value.join(format!(
+            let key = StorageKey::new(format!(
                 "parameter_values/{dataset}_{name}_parameter_values.json"
             ));
-            let file = std::fs::File::open(&path)
-                .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
-            // read the file to json
-            let rdr = std::io::BufReader::new(file);
-            let res: serde_json::Value = serde_json::from_reader(rdr)
-                .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+            let res = storage.read_json(&key)?;
             // parse to internal bea response format
             let data = BeaResponse::try_from(&res)?;
             let results = data.results();
@@ -342,20 +480,15 @@ impl GdpByIndustry {
         // start with table_id because it is a precondition for other parameter values
         let name = ParameterName::Industry;
         // year values vary by table id
-        let path = path.join(format!("parameter_values/{dataset}_{name}"));
+        let storage = FsStorage::new(path.into());
         let mut industries = std::collections::HashMap::new();
         for id in table_id {
             // open the file at the expected storage location, error if missing
-            let path = path.join(format!(
-                "{dataset}_{name}_byTableId_{}_values.json",
+            let key = StorageKey::new(format!(
+                "parameter_values/{dataset}_{name}/{dataset}_{name}_byTableId_{}_values.json",
                 id.value()
             ));
-            let file = std::fs::File::open(&path)
-                .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
-            // read the file to json
-            let rdr = std::io::BufReader::new(file);
-            let res: serde_json::Value = serde_json::from_reader(rdr)
-                .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+            let res = storage.read_json(&key)?;
             // parse to internal bea response format
             let data = BeaResponse::try_from(&res)?;
             let results = data.results();
@@ -388,15 +521,11 @@ impl GdpByIndustry {
         // start with table_id because it is a precondition for other parameter values
         let name = ParameterName::TableID;
         // open the file at the expected storage location, error if missing
-        let path = path.join(format!(
+        let storage = FsStorage::new(path.into());
+        let key = StorageKey::new(format!(
             "parameter_values/{dataset}_{name}_parameter_values.json"
         ));
-        let file = std::fs::File::open(&path)
-            .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
-        // read the file to json
-        let rdr = std::io::BufReader::new(file);
-        let res: serde_json::Value = serde_json::from_reader(rdr)
-            .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+        let res = storage.read_json(&key)?;
         // parse to internal bea response format
         let data = BeaResponse::try_from(&res)?;
         let results = data.results();
@@ -424,20 +553,15 @@ impl GdpByIndustry {
         // start with table_id because it is a precondition for other parameter values
         let name = ParameterName::Year;
         // year values vary by table id
-        let path = path.join(format!("parameter_values/{dataset}_{name}"));
+        let storage = FsStorage::new(path.into());
         let mut years = std::collections::HashMap::new();
         for id in table_id {
             // open the file at the expected storage location, error if missing
-            let path = path.join(format!(
-                "{dataset}_{name}_byTableId_{}_values.json",
+            let key = StorageKey::new(format!(
+                "parameter_values/{dataset}_{name}/{dataset}_{name}_byTableId_{}_values.json",
                 id.value()
             ));
-            let file = std::fs::File::open(&path)
-                .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
-            // read the file to json
-            let rdr = std::io::BufReader::new(file);
-            let res: serde_json::Value = serde_json::from_reader(rdr)
-                .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+            let res = storage.read_json(&key)?;
             // parse to internal bea response format
             let data = BeaResponse::try_from(&res)?;
             let results = data.results();
@@ -487,16 +611,12 @@ impl TryFrom<&std::path::PathBuf> for Iip {
         let mut frequency = Vec::new();
         let mut type_of_investment = Vec::new();
         let mut year = Vec::new();
+        let storage = FsStorage::new(value.clone());
         // For each parameter in dataset
         for name in names {
             // open the file at the expected storage location, error if missing
-            let path = value.join(format!("parameter_values/{dataset}_{name}_values.json"));
-            let file = std::fs::File::open(&path)
-                .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
-            // read the file to json
-            let rdr = std::io::BufReader::new(file);
-            let res: serde_json::Value = serde_json::from_reader(rdr)
-                .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+            let key = StorageKey::new(format!("parameter_values/{dataset}_{name}_values.json"));
+            let res = storage.read_json(&key)?;
             // parse to internal bea response format
             let data = BeaResponse::try_from(&res)?;
             let results = data.results();
@@ -596,16 +716,12 @@ impl TryFrom<&std::path::PathBuf> for InputOutput {
         // empty vectors to store values
         let mut table_id = Vec::new();
         let mut year = Vec::new();
+        let storage = FsStorage::new(value.clone());
         // For each parameter in dataset
         for name in names {
             // open the file at the expected storage location, error if missing
-            let path = value.join(format!("parameter_values/{dataset}_{name}_values.json"));
-            let file = std::fs::File::open(&path)
-                .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
-            // read the file to json
-            let rdr = std::io::BufReader::new(file);
-            let res: serde_json::Value = serde_json::from_reader(rdr)
-                .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+            let key = StorageKey::new(format!("parameter_values/{dataset}_{name}_values.json"));
+            let res = storage.read_json(&key)?;
             // parse to internal bea response format
             let data = BeaResponse::try_from(&res)?;
             let results = data.results();
@@ -669,16 +785,12 @@ impl TryFrom<&std::path::PathBuf> for Ita {
         let mut frequency = Vec::new();
         let mut indicator = Vec::new();
         let mut year = Vec::new();
+        let storage = FsStorage::new(value.clone());
         // For each parameter in dataset
         for name in names {
             // open the file at the expected storage location, error if missing
-            let path = value.join(format!("parameter_values/{dataset}_{name}_values.json"));
-            let file = std::fs::File::open(&path)
-                .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
-            // read the file to json
-            let rdr = std::io::BufReader::new(file);
-            let res: serde_json::Value = serde_json::from_reader(rdr)
-                .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+            let key = StorageKey::new(format!("parameter_values/{dataset}_{name}_values.json"));
+            let res = storage.read_json(&key)?;
             // parse to internal bea response format
             let data = BeaResponse::try_from(&res)?;
             let results = data.results();
@@ -784,16 +896,12 @@ impl TryFrom<&std::path::PathBuf> for IntlServSta {
         let mut destination = Vec::new();
         let mut industry = Vec::new();
         let mut year = Vec::new();
+        let storage = FsStorage::new(value.clone());
         // For each parameter in dataset
         for name in names {
             // open the file at the expected storage location, error if missing
-            let path = value.join(format!("parameter_values/{dataset}_{name}_values.json"));
-            let file = std::fs::File::open(&path)
-                .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
-            // read the file to json
-            let rdr = std::io::BufReader::new(file);
-            let res: serde_json::Value = serde_json::from_reader(rdr)
-                .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+            let key = StorageKey::new(format!("parameter_values/{dataset}_{name}_values.json"));
+            let res = storage.read_json(&key)?;
             // parse to internal bea response format
             let data = BeaResponse::try_from(&res)?;
             let results = data.results();
@@ -913,16 +1021,12 @@ impl TryFrom<&std::path::PathBuf> for IntlServTrade {
         let mut trade_direction = Vec::new();
         let mut type_of_service = Vec::new();
         let mut year = Vec::new();
+        let storage = FsStorage::new(value.clone());
         // For each parameter in dataset
         for name in names {
             // open the file at the expected storage location, error if missing
-            let path = value.join(format!("parameter_values/{dataset}_{name}_values.json"));
-            let file = std::fs::File::open(&path)
-                .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
-            // read the file to json
-            let rdr = std::io::BufReader::new(file);
-            let res: serde_json::Value = serde_json::from_reader(rdr)
-                .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+            let key = StorageKey::new(format!("parameter_values/{dataset}_{name}_values.json"));
+            let res = storage.read_json(&key)?;
             // parse to internal bea response format
             let data = BeaResponse::try_from(&res)?;
             let results = data.results();
@@ -1040,16 +1144,12 @@ impl TryFrom<&std::path::PathBuf> for Regional {
         let mut line_code = Vec::new();
         let mut table_name = Vec::new();
         let mut year = Vec::new();
+        let storage = FsStorage::new(value.clone());
         // For each parameter in dataset
         for name in names {
             // open the file at the expected storage location, error if missing
-            let path = value.join(format!("parameter_values/{dataset}_{name}_values.json"));
-            let file = std::fs::File::open(&path)
-                .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
-            // read the file to json
-            let rdr = std::io::BufReader::new(file);
-            let res: serde_json::Value = serde_json::from_reader(rdr)
-                .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+            let key = StorageKey::new(format!("parameter_values/{dataset}_{name}_values.json"));
+            let res = storage.read_json(&key)?;
             // parse to internal bea response format
             let data = BeaResponse::try_from(&res)?;
             let results = data.results();
@@ -1138,20 +1238,15 @@ impl UnderlyingGdpByIndustry {
         // start with table_id because it is a precondition for other parameter values
         let name = ParameterName::Industry;
         // year values vary by table id
-        let path = path.join(format!("parameter_values/{dataset}_{name}"));
+        let storage = FsStorage::new(path.into());
         let mut industries = std::collections::HashMap::new();
         for id in table_id {
             // open the file at the expected storage location, error if missing
-            let path = path.join(format!(
-                "{dataset}_{name}_byTableId_{}_values.json",
+            let key = StorageKey::new(format!(
+                "parameter_values/{dataset}_{name}/{dataset}_{name}_byTableId_{}_values.json",
                 id.value()
             ));
-            let file = std::fs::File::open(&path)
-                .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
-            // read the file to json
-            let rdr = std::io::BufReader::new(file);
-            let res: serde_json::Value = serde_json::from_reader(rdr)
-                .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+            let res = storage.read_json(&key)?;
             // parse to internal bea response format
             let data = BeaResponse::try_from(&res)?;
             let results = data.results();
@@ -1183,15 +1278,11 @@ impl UnderlyingGdpByIndustry {
         // start with table_id because it is a precondition for other parameter values
         let name = ParameterName::TableID;
         // open the file at the expected storage location, error if missing
-        let path = path.join(format!(
+        let storage = FsStorage::new(path.into());
+        let key = StorageKey::new(format!(
             "parameter_values/{dataset}_{name}_parameter_values.json"
         ));
-        let file = std::fs::File::open(&path)
-            .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
-        // read the file to json
-        let rdr = std::io::BufReader::new(file);
-        let res: serde_json::Value = serde_json::from_reader(rdr)
-            .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+        let res = storage.read_json(&key)?;
         // parse to internal bea response format
         let data = BeaResponse::try_from(&res)?;
         let results = data.results();
@@ -1219,20 +1310,15 @@ impl UnderlyingGdpByIndustry {
         // start with table_id because it is a precondition for other parameter values
         let name = ParameterName::Year;
         // year values vary by table id
-        let path = path.join(format!("parameter_values/{dataset}_{name}"));
+        let storage = FsStorage::new(path.into());
         let mut years = std::collections::HashMap::new();
         for id in table_id {
             // open the file at the expected storage location, error if missing
-            let path = path.join(format!(
-                "{dataset}_{name}_byTableId_{}_values.json",
+            let key = StorageKey::new(format!(
+                "parameter_values/{dataset}_{name}/{dataset}_{name}_byTableId_{}_values.json",
                 id.value()
             ));
-            let file = std::fs::File::open(&path)
-                .map_err(|e| IoError::new(path, e, line!(), file!().into()))?;
-            // read the file to json
-            let rdr = std::io::BufReader::new(file);
-            let res: serde_json::Value = serde_json::from_reader(rdr)
-                .map_err(|e| SerdeJson::new(e, line!(), file!().to_string()))?;
+            let res = storage.read_json(&key)?;
             // parse to internal bea response format
             let data = BeaResponse::try_from(&res)?;
             let results = data.results();
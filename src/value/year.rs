@@ -416,6 +416,16 @@ impl NipaRange {
     pub fn iter(&self) -> NipaRangeIterator {
         NipaRangeIterator::new(self)
     }
+
+    /// The years available at `frequency`, or `None` if the table is not published at that
+    /// frequency.
+    pub fn years(&self, frequency: Frequency) -> Option<Vec<String>> {
+        match frequency {
+            Frequency::Annual => self.annual.as_ref().map(YearRange::keys),
+            Frequency::Monthly => self.monthly.as_ref().map(YearRange::keys),
+            Frequency::Quarterly => self.quarterly.as_ref().map(YearRange::keys),
+        }
+    }
 }
 
 pub fn year_opt(input: &str) -> Result<Option<jiff::civil::Date>, ParseInt> {
@@ -544,6 +554,17 @@ impl NipaRanges {
     pub fn iter(&self) -> NipaRangesIterator {
         NipaRangesIterator::new(self)
     }
+
+    /// Every year available for any table at any frequency, in ascending order.
+    pub fn years(&self) -> Vec<String> {
+        let mut years = std::collections::BTreeSet::new();
+        for range in self.values() {
+            for frequency in [Frequency::Annual, Frequency::Quarterly, Frequency::Monthly] {
+                years.extend(range.years(frequency).unwrap_or_default());
+            }
+        }
+        years.into_iter().collect()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize)]
@@ -116,3 +116,183 @@ async fn download_summary() -> anyhow::Result<()> {
     check::download_summary().await?;
     Ok(())
 }
+
+#[test]
+fn data_stream() -> anyhow::Result<()> {
+    check::data_stream()?;
+    Ok(())
+}
+
+#[test]
+fn measure_units() -> anyhow::Result<()> {
+    check::measure_units()?;
+    Ok(())
+}
+
+#[test]
+fn period() -> anyhow::Result<()> {
+    check::period()?;
+    Ok(())
+}
+
+#[test]
+fn series_collection() -> anyhow::Result<()> {
+    check::series_collection()?;
+    Ok(())
+}
+
+#[test]
+fn series_transforms() -> anyhow::Result<()> {
+    check::series_transforms()?;
+    Ok(())
+}
+
+#[test]
+fn series_aggregate() -> anyhow::Result<()> {
+    check::series_aggregate()?;
+    Ok(())
+}
+
+#[test]
+fn series_measurement() -> anyhow::Result<()> {
+    check::series_measurement()?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn deflate() -> anyhow::Result<()> {
+    check::deflate().await?;
+    Ok(())
+}
+
+#[test]
+fn rebase() -> anyhow::Result<()> {
+    check::rebase()?;
+    Ok(())
+}
+
+#[test]
+fn table_view() -> anyhow::Result<()> {
+    check::table_view()?;
+    Ok(())
+}
+
+#[test]
+fn request_builders() -> anyhow::Result<()> {
+    check::request_builders()?;
+    Ok(())
+}
+
+#[test]
+fn validate_queue() -> anyhow::Result<()> {
+    check::validate_queue()?;
+    Ok(())
+}
+
+#[test]
+fn parameter_rules() -> anyhow::Result<()> {
+    check::catalog_rules()?;
+    Ok(())
+}
+
+#[test]
+fn value_filter() -> anyhow::Result<()> {
+    check::value_filter()?;
+    Ok(())
+}
+
+#[test]
+fn prune_iterator() -> anyhow::Result<()> {
+    check::prune_iterator()?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn prune_warm() -> anyhow::Result<()> {
+    check::prune_warm().await?;
+    Ok(())
+}
+
+#[test]
+fn catalog_search() -> anyhow::Result<()> {
+    check::catalog_search()?;
+    Ok(())
+}
+
+#[test]
+fn search_index_saved() -> anyhow::Result<()> {
+    check::search_index_saved()?;
+    Ok(())
+}
+
+#[test]
+fn api_error_kinds() -> anyhow::Result<()> {
+    check::api_error_kinds()?;
+    Ok(())
+}
+
+#[test]
+fn error_classes() -> anyhow::Result<()> {
+    check::error_classes()?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn shared_client() -> anyhow::Result<()> {
+    check::shared_client().await?;
+    Ok(())
+}
+
+#[test]
+fn config_profiles() -> anyhow::Result<()> {
+    check::config_profiles()?;
+    Ok(())
+}
+
+#[test]
+fn storage_roots() -> anyhow::Result<()> {
+    check::storage_roots()?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn storage_backends() -> anyhow::Result<()> {
+    check::storage_backends().await?;
+    Ok(())
+}
+
+#[test]
+fn s3_signature() -> anyhow::Result<()> {
+    check::s3_signature()?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn queue_progress() -> anyhow::Result<()> {
+    check::queue_progress().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn queue_cancellation() -> anyhow::Result<()> {
+    check::queue_cancellation().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn streamed_download() -> anyhow::Result<()> {
+    check::streamed_download().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn download_failures() -> anyhow::Result<()> {
+    check::download_failures().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn download_plan() -> anyhow::Result<()> {
+    check::download_plan().await?;
+    Ok(())
+}